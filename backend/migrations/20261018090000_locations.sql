@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS locations (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    parent_id TEXT REFERENCES locations(id) ON DELETE CASCADE,
+    created_at TEXT NOT NULL
+);
+
+ALTER TABLE user_models
+    ADD COLUMN location_id TEXT REFERENCES locations(id) ON DELETE SET NULL;
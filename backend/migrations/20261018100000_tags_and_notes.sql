@@ -0,0 +1,21 @@
+CREATE TABLE IF NOT EXISTS tags (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    created_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS user_model_tags (
+    user_model_id TEXT NOT NULL REFERENCES user_models(id) ON DELETE CASCADE,
+    tag_id TEXT NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+    PRIMARY KEY (user_model_id, tag_id)
+);
+
+CREATE TABLE IF NOT EXISTS model_definition_tags (
+    model_definition_id TEXT NOT NULL REFERENCES model_definitions(id) ON DELETE CASCADE,
+    tag_id TEXT NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+    PRIMARY KEY (model_definition_id, tag_id)
+);
+
+ALTER TABLE user_models ADD COLUMN notes TEXT;
+
+ALTER TABLE model_definitions ADD COLUMN notes TEXT;
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS photos (
+    id TEXT PRIMARY KEY,
+    user_model_id TEXT NOT NULL REFERENCES user_models(id) ON DELETE CASCADE,
+    file_name TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    size_bytes INTEGER NOT NULL,
+    width INTEGER NOT NULL,
+    height INTEGER NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS photos_user_model_id ON photos (user_model_id);
@@ -0,0 +1,19 @@
+ALTER TABLE model_definitions ADD COLUMN points INTEGER;
+
+ALTER TABLE model_definitions ADD COLUMN unit_size INTEGER;
+
+CREATE TABLE IF NOT EXISTS army_lists (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    game_id TEXT NOT NULL REFERENCES games(id) ON DELETE CASCADE,
+    points_limit INTEGER,
+    created_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS army_list_entries (
+    id TEXT PRIMARY KEY,
+    army_list_id TEXT NOT NULL REFERENCES army_lists(id) ON DELETE CASCADE,
+    user_model_id TEXT NOT NULL REFERENCES user_models(id) ON DELETE CASCADE,
+    quantity INTEGER NOT NULL,
+    created_at TEXT NOT NULL
+);
@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS projects (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    target_status TEXT NOT NULL,
+    due_date TEXT,
+    created_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS project_user_models (
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    user_model_id TEXT NOT NULL REFERENCES user_models(id) ON DELETE CASCADE,
+    PRIMARY KEY (project_id, user_model_id)
+);
@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS sessions (
+    id TEXT PRIMARY KEY,
+    started_at TEXT NOT NULL,
+    stopped_at TEXT,
+    note TEXT
+);
+
+-- At most one session can be running at a time.
+CREATE UNIQUE INDEX IF NOT EXISTS sessions_single_active
+    ON sessions ((stopped_at IS NULL))
+    WHERE stopped_at IS NULL;
+
+CREATE TABLE IF NOT EXISTS session_user_models (
+    session_id TEXT NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+    user_model_id TEXT NOT NULL REFERENCES user_models(id) ON DELETE CASCADE,
+    PRIMARY KEY (session_id, user_model_id)
+);
@@ -0,0 +1,9 @@
+-- Deleting a game, model definition or user model moves it to the trash instead of
+-- cascading straight away; the purge job removes rows once they pass the retention period.
+ALTER TABLE games ADD COLUMN deleted_at TEXT;
+ALTER TABLE model_definitions ADD COLUMN deleted_at TEXT;
+ALTER TABLE user_models ADD COLUMN deleted_at TEXT;
+
+CREATE INDEX IF NOT EXISTS games_deleted_at ON games (deleted_at);
+CREATE INDEX IF NOT EXISTS model_definitions_deleted_at ON model_definitions (deleted_at);
+CREATE INDEX IF NOT EXISTS user_models_deleted_at ON user_models (deleted_at);
@@ -0,0 +1,84 @@
+-- The latest change to every game, model definition and user model, numbered from a
+-- single sequence that only ever grows. Triggers keep it current on every write path,
+-- cascades and the trash purge included, so `GET /sync/changes` cannot miss a change.
+-- Purged rows stay behind as tombstones.
+CREATE TABLE IF NOT EXISTS sync_changes (
+    seq INTEGER PRIMARY KEY AUTOINCREMENT,
+    entity TEXT NOT NULL,
+    entity_id TEXT NOT NULL,
+    changed_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%d %H:%M:%f', 'now')),
+    purged INTEGER NOT NULL DEFAULT 0,
+    UNIQUE (entity, entity_id)
+);
+
+INSERT INTO sync_changes (entity, entity_id)
+SELECT 'game', id FROM games ORDER BY created_at;
+
+INSERT INTO sync_changes (entity, entity_id)
+SELECT 'model_definition', id FROM model_definitions ORDER BY created_at;
+
+INSERT INTO sync_changes (entity, entity_id)
+SELECT 'user_model', id FROM user_models ORDER BY updated_at;
+
+CREATE TRIGGER IF NOT EXISTS games_sync_insert
+AFTER INSERT ON games
+BEGIN
+    DELETE FROM sync_changes WHERE entity = 'game' AND entity_id = NEW.id;
+    INSERT INTO sync_changes (entity, entity_id) VALUES ('game', NEW.id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS games_sync_update
+AFTER UPDATE ON games
+BEGIN
+    DELETE FROM sync_changes WHERE entity = 'game' AND entity_id = NEW.id;
+    INSERT INTO sync_changes (entity, entity_id) VALUES ('game', NEW.id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS games_sync_delete
+AFTER DELETE ON games
+BEGIN
+    DELETE FROM sync_changes WHERE entity = 'game' AND entity_id = OLD.id;
+    INSERT INTO sync_changes (entity, entity_id, purged) VALUES ('game', OLD.id, 1);
+END;
+
+CREATE TRIGGER IF NOT EXISTS model_definitions_sync_insert
+AFTER INSERT ON model_definitions
+BEGIN
+    DELETE FROM sync_changes WHERE entity = 'model_definition' AND entity_id = NEW.id;
+    INSERT INTO sync_changes (entity, entity_id) VALUES ('model_definition', NEW.id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS model_definitions_sync_update
+AFTER UPDATE ON model_definitions
+BEGIN
+    DELETE FROM sync_changes WHERE entity = 'model_definition' AND entity_id = NEW.id;
+    INSERT INTO sync_changes (entity, entity_id) VALUES ('model_definition', NEW.id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS model_definitions_sync_delete
+AFTER DELETE ON model_definitions
+BEGIN
+    DELETE FROM sync_changes WHERE entity = 'model_definition' AND entity_id = OLD.id;
+    INSERT INTO sync_changes (entity, entity_id, purged) VALUES ('model_definition', OLD.id, 1);
+END;
+
+CREATE TRIGGER IF NOT EXISTS user_models_sync_insert
+AFTER INSERT ON user_models
+BEGIN
+    DELETE FROM sync_changes WHERE entity = 'user_model' AND entity_id = NEW.id;
+    INSERT INTO sync_changes (entity, entity_id) VALUES ('user_model', NEW.id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS user_models_sync_update
+AFTER UPDATE ON user_models
+BEGIN
+    DELETE FROM sync_changes WHERE entity = 'user_model' AND entity_id = NEW.id;
+    INSERT INTO sync_changes (entity, entity_id) VALUES ('user_model', NEW.id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS user_models_sync_delete
+AFTER DELETE ON user_models
+BEGIN
+    DELETE FROM sync_changes WHERE entity = 'user_model' AND entity_id = OLD.id;
+    INSERT INTO sync_changes (entity, entity_id, purged) VALUES ('user_model', OLD.id, 1);
+END;
@@ -0,0 +1,2 @@
+-- Bumped on every write to a lot; served as its ETag so concurrent edits can be caught.
+ALTER TABLE user_models ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
@@ -0,0 +1,45 @@
+-- The PostgreSQL backend stores games, model definitions and lots only; the
+-- columns follow the SQLite schema so the API returns the same shapes.
+CREATE TABLE IF NOT EXISTS games (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMP NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS model_definitions (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    game_id TEXT NOT NULL REFERENCES games(id) ON DELETE CASCADE,
+    points BIGINT,
+    unit_size BIGINT,
+    notes TEXT,
+    created_at TIMESTAMP NOT NULL
+);
+
+-- There are no storage locations here, so `location_id` is always NULL.
+CREATE TABLE IF NOT EXISTS user_models (
+    id TEXT PRIMARY KEY,
+    model_definition_id TEXT NOT NULL REFERENCES model_definitions(id) ON DELETE CASCADE,
+    quantity BIGINT NOT NULL,
+    status TEXT NOT NULL,
+    location_id TEXT,
+    notes TEXT,
+    version BIGINT NOT NULL DEFAULT 1,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS audit_log (
+    id BIGSERIAL PRIMARY KEY,
+    occurred_at TIMESTAMP NOT NULL,
+    actor TEXT NOT NULL,
+    entity TEXT NOT NULL,
+    entity_id TEXT NOT NULL,
+    action TEXT NOT NULL,
+    before JSONB,
+    after JSONB
+);
+
+CREATE INDEX IF NOT EXISTS audit_log_entity ON audit_log (entity, entity_id);
+
+CREATE INDEX IF NOT EXISTS audit_log_occurred_at ON audit_log (occurred_at);
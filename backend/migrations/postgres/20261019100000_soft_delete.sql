@@ -0,0 +1,9 @@
+-- Trashed rows are hidden the same way as in SQLite. Nothing moves rows to
+-- the trash here yet, but data copied over from SQLite keeps its state.
+ALTER TABLE games ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMP;
+ALTER TABLE model_definitions ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMP;
+ALTER TABLE user_models ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMP;
+
+CREATE INDEX IF NOT EXISTS games_deleted_at ON games (deleted_at);
+CREATE INDEX IF NOT EXISTS model_definitions_deleted_at ON model_definitions (deleted_at);
+CREATE INDEX IF NOT EXISTS user_models_deleted_at ON user_models (deleted_at);
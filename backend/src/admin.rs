@@ -0,0 +1,407 @@
+//! Maintenance tasks behind the `backend` subcommands. They go through the
+//! same handlers as the HTTP API, so validation, the audit log and change
+//! events behave exactly as they do for requests.
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{sqlite::SqliteConnectOptions, ConnectOptions, Connection};
+use uuid::Uuid;
+
+use crate::{
+    audit::{Actor, Entity},
+    backups::{self, CreatedBackup},
+    db, stats,
+    sync::{self, ChangesQuery, PushChange, PushRequest, PushResult},
+    users::{self, CreateUser},
+    AppError, AppState, CreateGame, CreateModelDefinition, CreateUserModel, Status,
+};
+
+/// Recorded as the actor in the audit log for changes made from the command line.
+pub const CLI_ACTOR: &str = "cli";
+
+/// Entities in an export file, in the order they have to be imported.
+const EXPORTED: [Entity; 3] = [Entity::Game, Entity::ModelDefinition, Entity::UserModel];
+
+/// A model definition in the sample collection, with the one lot owned of it.
+struct SeedModel {
+    name: &'static str,
+    points: i64,
+    unit_size: i64,
+    quantity: i32,
+    status: Status,
+}
+
+const fn seed_model(
+    name: &'static str,
+    points: i64,
+    unit_size: i64,
+    quantity: i32,
+    status: Status,
+) -> SeedModel {
+    SeedModel {
+        name,
+        points,
+        unit_size,
+        quantity,
+        status,
+    }
+}
+
+/// Sample collection written by [`seed`], by game.
+const SEED: [(&str, [SeedModel; 3]); 2] = [
+    (
+        "Warhammer 40,000",
+        [
+            seed_model("Intercessors", 80, 5, 10, Status::Painted),
+            seed_model("Hellblasters", 115, 5, 5, Status::Assembled),
+            seed_model("Terminators", 170, 5, 5, Status::Unassembled),
+        ],
+    ),
+    (
+        "Age of Sigmar",
+        [
+            seed_model("Liberators", 100, 5, 5, Status::Painted),
+            seed_model("Vindictors", 130, 5, 5, Status::Unassembled),
+            seed_model("Knight-Questor", 100, 1, 1, Status::Assembled),
+        ],
+    ),
+];
+
+/// Why an admin command failed, worded for whoever ran it.
+#[derive(Debug)]
+pub struct AdminError(String);
+
+impl fmt::Display for AdminError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for AdminError {}
+
+impl From<AppError> for AdminError {
+    fn from(error: AppError) -> Self {
+        Self(error.message.to_string())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SeedSummary {
+    pub games: usize,
+    pub model_definitions: usize,
+    pub user_models: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct ImportSummary {
+    /// Records written to the database.
+    pub accepted: usize,
+    /// Records that already existed with a newer change, left as they were.
+    pub conflicts: usize,
+    /// One line per record the API refused, e.g. because its game is missing.
+    pub rejected: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct BackupSummary {
+    pub path: PathBuf,
+    /// Names of older backups deleted by rotation.
+    pub removed: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct RestoreSummary {
+    /// Schema version of the backup before it was migrated.
+    pub schema_version: i64,
+    /// Where the database that was replaced has been moved, if there was one.
+    pub previous: Option<PathBuf>,
+}
+
+/// The file written by [`export`] and read by [`import`]: every live game,
+/// model definition and lot, as `POST /sync/push` changes.
+#[derive(Deserialize, Serialize)]
+struct ExportFile {
+    changes: Vec<PushChange>,
+}
+
+fn actor() -> Actor {
+    Actor(CLI_ACTOR.to_string())
+}
+
+/// Fills an empty database with a small sample collection.
+pub async fn seed(state: &AppState) -> Result<SeedSummary, AdminError> {
+    let existing = sqlx::query_scalar!(r#"SELECT COUNT(*) as "count!: i64" FROM games"#)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|error| AdminError(format!("could not check for existing data: {error}")))?;
+    if existing > 0 {
+        return Err(AdminError(
+            "the database already has games; seed only fills an empty database".to_string(),
+        ));
+    }
+
+    let mut summary = SeedSummary::default();
+    for (game_name, definitions) in SEED {
+        let (_, Json(game)) = crate::create_game(
+            State(state.repository.clone()),
+            State(state.events.clone()),
+            actor(),
+            Json(CreateGame {
+                name: game_name.to_string(),
+            }),
+        )
+        .await?;
+        summary.games += 1;
+
+        for model in definitions {
+            let (_, Json(definition)) = crate::create_model_definition(
+                State(state.repository.clone()),
+                State(state.events.clone()),
+                actor(),
+                Json(CreateModelDefinition {
+                    name: model.name.to_string(),
+                    game_id: game.id,
+                    points: Some(model.points),
+                    unit_size: Some(model.unit_size),
+                }),
+            )
+            .await?;
+            summary.model_definitions += 1;
+
+            crate::create_user_model(
+                State(state.repository.clone()),
+                State(state.events.clone()),
+                actor(),
+                Json(CreateUserModel {
+                    model_definition_id: definition.id,
+                    quantity: model.quantity,
+                    status: model.status,
+                    location_id: None,
+                }),
+            )
+            .await?;
+            summary.user_models += 1;
+        }
+    }
+    Ok(summary)
+}
+
+/// Writes every game, model definition and lot that is not in the trash to
+/// `path`, returning how many records were written. Storage locations are not
+/// exported, so lots come back unassigned when imported elsewhere.
+pub async fn export(state: &AppState, path: &Path) -> Result<usize, AdminError> {
+    let mut changes = Vec::new();
+    let mut since = None;
+    loop {
+        let Json(feed) = sync::list_changes(
+            State(state.clone()),
+            Query(ChangesQuery { since, limit: None }),
+        )
+        .await?;
+        changes.extend(feed.changes);
+        if !feed.has_more {
+            break;
+        }
+        since = Some(feed.cursor);
+    }
+
+    let mut records = Vec::new();
+    for entity in EXPORTED {
+        for change in changes.iter().filter(|change| change.entity == entity) {
+            let Some(mut data) = change.data.clone().filter(|_| !change.deleted) else {
+                continue;
+            };
+            if let Some(location_id) = data.get_mut("location_id") {
+                *location_id = Value::Null;
+            }
+            records.push(PushChange {
+                entity,
+                id: change.id,
+                base_version: None,
+                modified_at: change.changed_at.clone(),
+                deleted: false,
+                data,
+            });
+        }
+    }
+
+    let count = records.len();
+    let text = serde_json::to_string_pretty(&ExportFile { changes: records })
+        .map_err(|error| AdminError(format!("could not encode export: {error}")))?;
+    tokio::fs::write(path, text)
+        .await
+        .map_err(|error| AdminError(format!("could not write {}: {error}", path.display())))?;
+    Ok(count)
+}
+
+/// Loads a file written by [`export`]. Records are matched by ID, so importing
+/// the same file twice changes nothing, and records edited here after they
+/// were exported keep the newer edit.
+pub async fn import(state: &AppState, path: &Path) -> Result<ImportSummary, AdminError> {
+    let text = tokio::fs::read_to_string(path)
+        .await
+        .map_err(|error| AdminError(format!("could not read {}: {error}", path.display())))?;
+    let file: ExportFile = serde_json::from_str(&text).map_err(|error| {
+        AdminError(format!("{} is not an export file: {error}", path.display()))
+    })?;
+
+    let mut summary = ImportSummary::default();
+    let mut changes = file.changes;
+    changes.sort_by_key(|change| EXPORTED.iter().position(|entity| *entity == change.entity));
+    while !changes.is_empty() {
+        let rest = changes.split_off(changes.len().min(sync::MAX_BATCH));
+        let batch = std::mem::replace(&mut changes, rest);
+        let Json(response) = sync::push_changes(
+            State(state.clone()),
+            actor(),
+            Json(PushRequest { changes: batch }),
+        )
+        .await?;
+
+        for result in response.results {
+            match result {
+                PushResult::Accepted { .. } => summary.accepted += 1,
+                PushResult::Conflict { .. } => summary.conflicts += 1,
+                PushResult::Rejected { entity, id, error } => summary
+                    .rejected
+                    .push(format!("{} {id}: {error}", entity.as_str())),
+            }
+        }
+    }
+    Ok(summary)
+}
+
+/// Writes a copy of the database into `dir`, returning the new file's path.
+/// Takes a backup into the configured backup directory, exactly as
+/// `POST /admin/backups` does, including rotation.
+pub async fn backup(state: &AppState) -> Result<BackupSummary, AdminError> {
+    let CreatedBackup { backup, removed } =
+        backups::take_backup(&state.read_pool, &state.backups).await?;
+    Ok(BackupSummary {
+        path: state.backups.dir.join(backup.name),
+        removed,
+    })
+}
+
+/// Replaces the database file at `database_url` with `backup`. Nothing may
+/// have the database open while this runs.
+///
+/// The backup must pass `PRAGMA integrity_check` and must not come from a
+/// newer schema than this build knows. It is copied next to the database and
+/// migrated there; only then is the old file moved aside (to
+/// `<database>.before-restore`) and the copy renamed into its place.
+pub async fn restore(database_url: &str, backup: &Path) -> Result<RestoreSummary, AdminError> {
+    let database = SqliteConnectOptions::from_str(database_url)
+        .map_err(|error| AdminError(format!("invalid database URL: {error}")))?
+        .get_filename()
+        .into_owned();
+    if database_url.contains(":memory:") || database.as_os_str().is_empty() {
+        return Err(AdminError("restore needs a database file".to_string()));
+    }
+    if !tokio::fs::try_exists(backup).await.unwrap_or(false) {
+        return Err(AdminError(format!("{} does not exist", backup.display())));
+    }
+
+    backups::check_integrity(backup).await?;
+    let known = db::schema_version();
+    let schema_version = match backups::schema_version_of(backup).await? {
+        None => {
+            return Err(AdminError(format!(
+                "{} is not a Mini Tracker database",
+                backup.display()
+            )));
+        }
+        Some(version) if version > known => {
+            return Err(AdminError(format!(
+                "{} has schema version {version}, newer than this build's {known}; upgrade the server before restoring it",
+                backup.display()
+            )))
+        }
+        Some(version) => version,
+    };
+
+    let staging = sibling(&database, "restoring");
+    tokio::fs::copy(backup, &staging)
+        .await
+        .map_err(|error| AdminError(format!("could not copy {}: {error}", backup.display())))?;
+    if let Err(error) = migrate_file(&staging).await {
+        let _ = tokio::fs::remove_file(&staging).await;
+        return Err(error);
+    }
+
+    let mut previous = None;
+    if tokio::fs::try_exists(&database).await.unwrap_or(false) {
+        let saved = sibling(&database, "before-restore");
+        for suffix in ["", "-wal", "-shm"] {
+            let from = PathBuf::from(format!("{}{suffix}", database.display()));
+            let to = PathBuf::from(format!("{}{suffix}", saved.display()));
+            let _ = tokio::fs::remove_file(&to).await;
+            if tokio::fs::try_exists(&from).await.unwrap_or(false) {
+                tokio::fs::rename(&from, &to).await.map_err(|error| {
+                    AdminError(format!("could not move {} aside: {error}", from.display()))
+                })?;
+            }
+        }
+        previous = Some(saved);
+    }
+    tokio::fs::rename(&staging, &database)
+        .await
+        .map_err(|error| AdminError(format!("could not put the backup in place: {error}")))?;
+
+    Ok(RestoreSummary {
+        schema_version,
+        previous,
+    })
+}
+
+async fn migrate_file(path: &Path) -> Result<(), AdminError> {
+    let mut conn = SqliteConnectOptions::new()
+        .filename(path)
+        .connect()
+        .await
+        .map_err(|error| AdminError(format!("could not open the restored copy: {error}")))?;
+    db::MIGRATOR
+        .run(&mut conn)
+        .await
+        .map_err(|error| AdminError(format!("could not migrate the restored copy: {error}")))?;
+    conn.close()
+        .await
+        .map_err(|error| AdminError(format!("could not close the restored copy: {error}")))
+}
+
+/// `path` with `.suffix` appended to its file name.
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{suffix}"));
+    path.with_file_name(name)
+}
+
+/// Registers a user, returning their ID.
+pub async fn create_user(state: &AppState, name: &str) -> Result<Uuid, AdminError> {
+    let (_, Json(user)) = users::create_user(
+        State(state.clone()),
+        actor(),
+        Json(CreateUser {
+            name: name.to_string(),
+        }),
+    )
+    .await?;
+    Ok(user.id)
+}
+
+/// Collection totals, as `GET /stats` returns them.
+pub async fn stats(state: &AppState) -> Result<Value, AdminError> {
+    let Json(stats) = stats::collection_stats(State(state.clone())).await?;
+    serde_json::to_value(stats)
+        .map_err(|error| AdminError(format!("could not encode stats: {error}")))
+}
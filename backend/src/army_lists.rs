@@ -0,0 +1,723 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    audit::{self, Action, Actor, Entity},
+    parse_status, parse_uuid, AppError, AppState, ErrorBody, GameSummary, Status,
+};
+
+#[derive(Deserialize, ToSchema)]
+pub(crate) struct CreateArmyList {
+    name: String,
+    game_id: Uuid,
+    points_limit: Option<i64>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub(crate) struct CreateArmyListEntry {
+    user_model_id: Uuid,
+    quantity: i64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct ArmyList {
+    id: Uuid,
+    name: String,
+    game: GameSummary,
+    points_limit: Option<i64>,
+    created_at: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct ArmyListDetail {
+    #[serde(flatten)]
+    list: ArmyList,
+    entries: Vec<ArmyListEntry>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct ArmyListEntry {
+    id: Uuid,
+    user_model_id: Uuid,
+    model_name: String,
+    quantity: i64,
+    /// Points for this entry, or `None` when the model definition has no points value.
+    points: Option<i64>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct ArmyListValidation {
+    valid: bool,
+    total_points: i64,
+    points_limit: Option<i64>,
+    issues: Vec<ValidationIssue>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct ValidationIssue {
+    /// The offending entry, or `None` for problems with the list as a whole.
+    entry_id: Option<Uuid>,
+    kind: IssueKind,
+    message: String,
+}
+
+#[derive(Serialize, Clone, Copy, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum IssueKind {
+    WrongGame,
+    NotEnoughOwned,
+    NotPainted,
+    MissingPoints,
+    IncompleteUnit,
+    OverPointsLimit,
+    InTrash,
+}
+
+struct EntryRecord {
+    id: String,
+    user_model_id: String,
+    model_name: String,
+    quantity: i64,
+    owned_quantity: i64,
+    status: Status,
+    game_id: String,
+    points: Option<i64>,
+    unit_size: Option<i64>,
+}
+
+impl EntryRecord {
+    fn points(&self) -> Option<i64> {
+        let unit_size = self.unit_size.unwrap_or(1).max(1);
+        let units = (self.quantity + unit_size - 1) / unit_size;
+        self.points.map(|points| points * units)
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/army-lists",
+    tag = "army lists",
+    request_body = CreateArmyList,
+    responses(
+        (status = 201, body = ArmyList),
+        (status = 404, description = "The game does not exist", body = ErrorBody),
+        (status = 422, description = "The points limit is below 1", body = ErrorBody),
+    )
+)]
+pub(crate) async fn create_army_list(
+    State(state): State<AppState>,
+    actor: Actor,
+    Json(payload): Json<CreateArmyList>,
+) -> Result<(StatusCode, Json<ArmyList>), AppError> {
+    tracing::info!(
+        name = %payload.name,
+        game_id = %payload.game_id,
+        "creating army list"
+    );
+    if payload.points_limit.is_some_and(|limit| limit <= 0) {
+        return Err(AppError::unprocessable(
+            "points limit must be greater than 0",
+        ));
+    }
+
+    let game_id_value = payload.game_id.to_string();
+    let exists = sqlx::query!(
+        r#"
+        SELECT id
+        FROM games
+        WHERE id = $1 AND deleted_at IS NULL
+        "#,
+        game_id_value
+    )
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|error| AppError::internal("failed to validate game", error))?;
+
+    if exists.is_none() {
+        return Err(AppError::not_found("game not found"));
+    }
+
+    let id = Uuid::new_v4();
+    let id_value = id.to_string();
+    let mut transaction = state
+        .pool
+        .begin()
+        .await
+        .map_err(|error| AppError::internal("failed to start transaction", error))?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO army_lists (id, name, game_id, points_limit, created_at)
+        VALUES ($1, $2, $3, $4, datetime('now'))
+        "#,
+        id_value,
+        payload.name,
+        game_id_value,
+        payload.points_limit
+    )
+    .execute(&mut *transaction)
+    .await
+    .map_err(|error| AppError::internal("failed to create army list", error))?;
+
+    audit::record_row(
+        &mut transaction,
+        &actor,
+        Entity::ArmyList,
+        &id_value,
+        Action::Create,
+        None,
+    )
+    .await?;
+
+    transaction
+        .commit()
+        .await
+        .map_err(|error| AppError::internal("failed to commit transaction", error))?;
+    state
+        .events
+        .changed(Entity::ArmyList, &id_value, Action::Create);
+
+    let list = load_army_list(&state.pool, &id_value)
+        .await?
+        .ok_or_else(|| AppError::internal_message("failed to load army list"))?;
+
+    Ok((StatusCode::CREATED, Json(list)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/army-lists",
+    tag = "army lists",
+    responses((status = 200, body = Vec<ArmyList>))
+)]
+pub(crate) async fn list_army_lists(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<ArmyList>>, AppError> {
+    tracing::info!("listing army lists");
+    let records = sqlx::query!(
+        r#"
+        SELECT
+            army_lists.id as "id!: String",
+            army_lists.name,
+            army_lists.points_limit,
+            army_lists.created_at as "created_at!: String",
+            games.id AS "game_id!: String",
+            games.name AS game_name
+        FROM army_lists
+        INNER JOIN games ON games.id = army_lists.game_id
+        ORDER BY army_lists.created_at
+        "#
+    )
+    .fetch_all(&state.read_pool)
+    .await
+    .map_err(|error| AppError::internal("failed to fetch army lists", error))?;
+
+    let lists = records
+        .into_iter()
+        .map(|record| {
+            Ok(ArmyList {
+                id: parse_uuid(record.id)?,
+                name: record.name,
+                game: GameSummary {
+                    id: parse_uuid(record.game_id)?,
+                    name: record.game_name,
+                },
+                points_limit: record.points_limit,
+                created_at: record.created_at,
+            })
+        })
+        .collect::<Result<Vec<_>, AppError>>()?;
+
+    tracing::info!(count = lists.len(), "listed army lists");
+    Ok(Json(lists))
+}
+
+#[utoipa::path(
+    get,
+    path = "/army-lists/{id}",
+    tag = "army lists",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = ArmyListDetail), (status = 404, body = ErrorBody))
+)]
+pub(crate) async fn get_army_list(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ArmyListDetail>, AppError> {
+    tracing::info!(army_list_id = %id, "loading army list");
+    let id_value = id.to_string();
+    let list = match load_army_list(&state.read_pool, &id_value).await? {
+        Some(list) => list,
+        None => return Err(AppError::not_found("army list not found")),
+    };
+
+    let entries = load_entries(&state.read_pool, &id_value)
+        .await?
+        .into_iter()
+        .map(|entry| {
+            Ok(ArmyListEntry {
+                points: entry.points(),
+                id: parse_uuid(entry.id)?,
+                user_model_id: parse_uuid(entry.user_model_id)?,
+                model_name: entry.model_name,
+                quantity: entry.quantity,
+            })
+        })
+        .collect::<Result<Vec<_>, AppError>>()?;
+
+    Ok(Json(ArmyListDetail { list, entries }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/army-lists/{id}",
+    tag = "army lists",
+    params(("id" = Uuid, Path)),
+    responses((status = 204), (status = 404, body = ErrorBody))
+)]
+pub(crate) async fn delete_army_list(
+    State(state): State<AppState>,
+    actor: Actor,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    tracing::info!(army_list_id = %id, "deleting army list");
+    let id_value = id.to_string();
+    let mut transaction = state
+        .pool
+        .begin()
+        .await
+        .map_err(|error| AppError::internal("failed to start transaction", error))?;
+
+    let before = audit::snapshot(&mut transaction, Entity::ArmyList, &id_value).await?;
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM army_lists
+        WHERE id = $1
+        "#,
+        id_value
+    )
+    .execute(&mut *transaction)
+    .await
+    .map_err(|error| AppError::internal("failed to delete army list", error))?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found("army list not found"));
+    }
+
+    audit::record_row(
+        &mut transaction,
+        &actor,
+        Entity::ArmyList,
+        &id_value,
+        Action::Delete,
+        before,
+    )
+    .await?;
+
+    transaction
+        .commit()
+        .await
+        .map_err(|error| AppError::internal("failed to commit transaction", error))?;
+    state
+        .events
+        .changed(Entity::ArmyList, &id_value, Action::Delete);
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/army-lists/{id}/entries",
+    tag = "army lists",
+    params(("id" = Uuid, Path)),
+    request_body = CreateArmyListEntry,
+    responses(
+        (status = 201, body = ArmyListEntry),
+        (status = 404, description = "The army list or the lot does not exist", body = ErrorBody),
+        (status = 422, description = "Quantity below 1", body = ErrorBody),
+    )
+)]
+pub(crate) async fn create_army_list_entry(
+    State(state): State<AppState>,
+    actor: Actor,
+    Path(army_list_id): Path<Uuid>,
+    Json(payload): Json<CreateArmyListEntry>,
+) -> Result<(StatusCode, Json<ArmyListEntry>), AppError> {
+    tracing::info!(
+        army_list_id = %army_list_id,
+        user_model_id = %payload.user_model_id,
+        quantity = payload.quantity,
+        "adding army list entry"
+    );
+    if payload.quantity <= 0 {
+        return Err(AppError::unprocessable("quantity must be greater than 0"));
+    }
+
+    let army_list_id_value = army_list_id.to_string();
+    if load_army_list(&state.pool, &army_list_id_value)
+        .await?
+        .is_none()
+    {
+        return Err(AppError::not_found("army list not found"));
+    }
+
+    let user_model_id_value = payload.user_model_id.to_string();
+    let exists = sqlx::query!(
+        r#"
+        SELECT user_models.id
+        FROM user_models
+        INNER JOIN model_definitions ON model_definitions.id = user_models.model_definition_id
+        INNER JOIN games ON games.id = model_definitions.game_id
+        WHERE user_models.id = $1
+          AND user_models.deleted_at IS NULL
+          AND model_definitions.deleted_at IS NULL
+          AND games.deleted_at IS NULL
+        "#,
+        user_model_id_value
+    )
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|error| AppError::internal("failed to validate user model", error))?;
+
+    if exists.is_none() {
+        return Err(AppError::not_found("user model not found"));
+    }
+
+    let id = Uuid::new_v4();
+    let id_value = id.to_string();
+    let mut transaction = state
+        .pool
+        .begin()
+        .await
+        .map_err(|error| AppError::internal("failed to start transaction", error))?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO army_list_entries (id, army_list_id, user_model_id, quantity, created_at)
+        VALUES ($1, $2, $3, $4, datetime('now'))
+        "#,
+        id_value,
+        army_list_id_value,
+        user_model_id_value,
+        payload.quantity
+    )
+    .execute(&mut *transaction)
+    .await
+    .map_err(|error| AppError::internal("failed to create army list entry", error))?;
+
+    audit::record_row(
+        &mut transaction,
+        &actor,
+        Entity::ArmyListEntry,
+        &id_value,
+        Action::Create,
+        None,
+    )
+    .await?;
+
+    transaction
+        .commit()
+        .await
+        .map_err(|error| AppError::internal("failed to commit transaction", error))?;
+    state
+        .events
+        .changed(Entity::ArmyListEntry, &id_value, Action::Create);
+
+    let entry = load_entries(&state.pool, &army_list_id_value)
+        .await?
+        .into_iter()
+        .find(|entry| entry.id == id_value)
+        .ok_or_else(|| AppError::internal_message("failed to load army list entry"))?;
+
+    let entry = ArmyListEntry {
+        points: entry.points(),
+        id: parse_uuid(entry.id)?,
+        user_model_id: parse_uuid(entry.user_model_id)?,
+        model_name: entry.model_name,
+        quantity: entry.quantity,
+    };
+
+    Ok((StatusCode::CREATED, Json(entry)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/army-lists/{id}/entries/{entry_id}",
+    tag = "army lists",
+    params(("id" = Uuid, Path), ("entry_id" = Uuid, Path)),
+    responses((status = 204), (status = 404, body = ErrorBody))
+)]
+pub(crate) async fn delete_army_list_entry(
+    State(state): State<AppState>,
+    actor: Actor,
+    Path((army_list_id, entry_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, AppError> {
+    tracing::info!(army_list_id = %army_list_id, entry_id = %entry_id, "removing army list entry");
+    let army_list_id_value = army_list_id.to_string();
+    let entry_id_value = entry_id.to_string();
+    let mut transaction = state
+        .pool
+        .begin()
+        .await
+        .map_err(|error| AppError::internal("failed to start transaction", error))?;
+
+    let before = audit::snapshot(&mut transaction, Entity::ArmyListEntry, &entry_id_value).await?;
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM army_list_entries
+        WHERE id = $1 AND army_list_id = $2
+        "#,
+        entry_id_value,
+        army_list_id_value
+    )
+    .execute(&mut *transaction)
+    .await
+    .map_err(|error| AppError::internal("failed to delete army list entry", error))?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found("army list entry not found"));
+    }
+
+    audit::record_row(
+        &mut transaction,
+        &actor,
+        Entity::ArmyListEntry,
+        &entry_id_value,
+        Action::Delete,
+        before,
+    )
+    .await?;
+
+    transaction
+        .commit()
+        .await
+        .map_err(|error| AppError::internal("failed to commit transaction", error))?;
+    state
+        .events
+        .changed(Entity::ArmyListEntry, &entry_id_value, Action::Delete);
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/army-lists/{id}/validation",
+    tag = "army lists",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = ArmyListValidation), (status = 404, body = ErrorBody))
+)]
+pub(crate) async fn validate_army_list(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ArmyListValidation>, AppError> {
+    tracing::info!(army_list_id = %id, "validating army list");
+    let id_value = id.to_string();
+    let list = match load_army_list(&state.read_pool, &id_value).await? {
+        Some(list) => list,
+        None => return Err(AppError::not_found("army list not found")),
+    };
+    let entries = load_entries(&state.read_pool, &id_value).await?;
+
+    let mut requested: HashMap<&str, i64> = HashMap::new();
+    for entry in &entries {
+        *requested.entry(entry.user_model_id.as_str()).or_default() += entry.quantity;
+    }
+
+    let game_id = list.game.id.to_string();
+    let mut issues = Vec::new();
+    let mut total_points = 0;
+    for entry in &entries {
+        let entry_id = Some(parse_uuid(entry.id.clone())?);
+        let mut issue = |kind, message| {
+            issues.push(ValidationIssue {
+                entry_id,
+                kind,
+                message,
+            })
+        };
+
+        if entry.game_id != game_id {
+            issue(
+                IssueKind::WrongGame,
+                format!("{} belongs to a different game", entry.model_name),
+            );
+        }
+        let requested = requested
+            .get(entry.user_model_id.as_str())
+            .copied()
+            .unwrap_or(entry.quantity);
+        if requested > entry.owned_quantity {
+            issue(
+                IssueKind::NotEnoughOwned,
+                format!(
+                    "{} needs {requested} models but only {} are owned",
+                    entry.model_name, entry.owned_quantity
+                ),
+            );
+        }
+        if entry.status != Status::Painted {
+            issue(
+                IssueKind::NotPainted,
+                format!("{} is not painted", entry.model_name),
+            );
+        }
+        if let Some(unit_size) = entry
+            .unit_size
+            .filter(|unit_size| entry.quantity % unit_size != 0)
+        {
+            issue(
+                IssueKind::IncompleteUnit,
+                format!(
+                    "{} is fielded in units of {unit_size} models",
+                    entry.model_name
+                ),
+            );
+        }
+        match entry.points() {
+            Some(points) => total_points += points,
+            None => issue(
+                IssueKind::MissingPoints,
+                format!("{} has no points value", entry.model_name),
+            ),
+        }
+    }
+
+    let trashed = count_trashed_entries(&state.read_pool, &id_value).await?;
+    if trashed > 0 {
+        issues.push(ValidationIssue {
+            entry_id: None,
+            kind: IssueKind::InTrash,
+            message: format!("{trashed} entries use models that are in the trash"),
+        });
+    }
+
+    if let Some(limit) = list.points_limit.filter(|limit| total_points > *limit) {
+        issues.push(ValidationIssue {
+            entry_id: None,
+            kind: IssueKind::OverPointsLimit,
+            message: format!("list is {total_points} points, over the {limit} point limit"),
+        });
+    }
+
+    Ok(Json(ArmyListValidation {
+        valid: issues.is_empty(),
+        total_points,
+        points_limit: list.points_limit,
+        issues,
+    }))
+}
+
+async fn load_army_list(pool: &SqlitePool, id: &str) -> Result<Option<ArmyList>, AppError> {
+    let record = sqlx::query!(
+        r#"
+        SELECT
+            army_lists.id as "id!: String",
+            army_lists.name,
+            army_lists.points_limit,
+            army_lists.created_at as "created_at!: String",
+            games.id AS "game_id!: String",
+            games.name AS game_name
+        FROM army_lists
+        INNER JOIN games ON games.id = army_lists.game_id
+        WHERE army_lists.id = $1
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|error| AppError::internal("failed to load army list", error))?;
+
+    record
+        .map(|record| {
+            Ok(ArmyList {
+                id: parse_uuid(record.id)?,
+                name: record.name,
+                game: GameSummary {
+                    id: parse_uuid(record.game_id)?,
+                    name: record.game_name,
+                },
+                points_limit: record.points_limit,
+                created_at: record.created_at,
+            })
+        })
+        .transpose()
+}
+
+/// Entries whose lot, model definition or game is in the trash. They are left
+/// out of the list itself, but a list missing models is not ready to play.
+async fn count_trashed_entries(pool: &SqlitePool, army_list_id: &str) -> Result<i64, AppError> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) as "count!: i64"
+        FROM army_list_entries
+        INNER JOIN user_models ON user_models.id = army_list_entries.user_model_id
+        INNER JOIN model_definitions ON model_definitions.id = user_models.model_definition_id
+        INNER JOIN games ON games.id = model_definitions.game_id
+        WHERE army_list_entries.army_list_id = $1
+            AND (
+                user_models.deleted_at IS NOT NULL
+                OR model_definitions.deleted_at IS NOT NULL
+                OR games.deleted_at IS NOT NULL
+            )
+        "#,
+        army_list_id
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|error| AppError::internal("failed to count trashed army list entries", error))
+}
+
+async fn load_entries(pool: &SqlitePool, army_list_id: &str) -> Result<Vec<EntryRecord>, AppError> {
+    let records = sqlx::query!(
+        r#"
+        SELECT
+            army_list_entries.id as "id!: String",
+            army_list_entries.user_model_id as "user_model_id!: String",
+            army_list_entries.quantity as "quantity!: i64",
+            user_models.quantity as "owned_quantity!: i64",
+            user_models.status,
+            model_definitions.name AS model_name,
+            model_definitions.game_id as "game_id!: String",
+            model_definitions.points,
+            model_definitions.unit_size
+        FROM army_list_entries
+        INNER JOIN user_models ON user_models.id = army_list_entries.user_model_id
+        INNER JOIN model_definitions ON model_definitions.id = user_models.model_definition_id
+        INNER JOIN games ON games.id = model_definitions.game_id
+        WHERE army_list_entries.army_list_id = $1
+            AND user_models.deleted_at IS NULL
+            AND model_definitions.deleted_at IS NULL
+            AND games.deleted_at IS NULL
+        ORDER BY army_list_entries.created_at
+        "#,
+        army_list_id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|error| AppError::internal("failed to fetch army list entries", error))?;
+
+    records
+        .into_iter()
+        .map(|record| {
+            Ok(EntryRecord {
+                status: parse_status(&record.status)?,
+                id: record.id,
+                user_model_id: record.user_model_id,
+                model_name: record.model_name,
+                quantity: record.quantity,
+                owned_quantity: record.owned_quantity,
+                game_id: record.game_id,
+                points: record.points,
+                unit_size: record.unit_size,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,326 @@
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Query, State},
+    http::request::Parts,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::{sqlite::SqliteRow, Column, Row, SqliteConnection, TypeInfo, ValueRef};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{AppError, AppState, ErrorBody};
+
+/// Header naming who made a change; requests without it are recorded as `anonymous`.
+pub(crate) const ACTOR_HEADER: &str = "x-actor";
+
+const DEFAULT_LIMIT: i64 = 100;
+const MAX_LIMIT: i64 = 1000;
+
+/// Who is making the request, taken from the `x-actor` header.
+pub struct Actor(pub(crate) String);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Actor {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let actor = match parts.headers.get(ACTOR_HEADER) {
+            Some(value) => value
+                .to_str()
+                .map_err(|_| AppError::bad_request("invalid x-actor header"))?
+                .trim()
+                .to_string(),
+            None => String::new(),
+        };
+
+        if actor.is_empty() {
+            return Ok(Self("anonymous".to_string()));
+        }
+
+        Ok(Self(actor))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Entity {
+    Game,
+    ModelDefinition,
+    UserModel,
+    Location,
+    Tag,
+    Photo,
+    ArmyList,
+    ArmyListEntry,
+    Project,
+    Session,
+    User,
+}
+
+impl Entity {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Entity::Game => "game",
+            Entity::ModelDefinition => "model_definition",
+            Entity::UserModel => "user_model",
+            Entity::Location => "location",
+            Entity::Tag => "tag",
+            Entity::Photo => "photo",
+            Entity::ArmyList => "army_list",
+            Entity::ArmyListEntry => "army_list_entry",
+            Entity::Project => "project",
+            Entity::Session => "session",
+            Entity::User => "user",
+        }
+    }
+
+    pub(crate) fn table(self) -> &'static str {
+        match self {
+            Entity::Game => "games",
+            Entity::ModelDefinition => "model_definitions",
+            Entity::UserModel => "user_models",
+            Entity::Location => "locations",
+            Entity::Tag => "tags",
+            Entity::Photo => "photos",
+            Entity::ArmyList => "army_lists",
+            Entity::ArmyListEntry => "army_list_entries",
+            Entity::Project => "projects",
+            Entity::Session => "sessions",
+            Entity::User => "users",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Copy, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Action {
+    Create,
+    Update,
+    Delete,
+    /// Brought back out of the trash.
+    Restore,
+    /// Permanently removed by the trash purge job.
+    Purge,
+}
+
+impl Action {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Action::Create => "create",
+            Action::Update => "update",
+            Action::Delete => "delete",
+            Action::Restore => "restore",
+            Action::Purge => "purge",
+        }
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(crate) struct AuditFilter {
+    entity: Option<Entity>,
+    entity_id: Option<String>,
+    /// Inclusive lower bound, any format SQLite's `datetime()` understands.
+    since: Option<String>,
+    /// Exclusive upper bound.
+    until: Option<String>,
+    limit: Option<i64>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct AuditEntry {
+    id: i64,
+    occurred_at: String,
+    actor: String,
+    entity: String,
+    entity_id: String,
+    action: String,
+    before: Option<Value>,
+    after: Option<Value>,
+}
+
+/// Appends an entry to the audit log. Call it on the same transaction as the
+/// change so the log and the data can never disagree.
+pub(crate) async fn record(
+    conn: &mut SqliteConnection,
+    actor: &Actor,
+    entity: Entity,
+    entity_id: &str,
+    action: Action,
+    before: Option<Value>,
+    after: Option<Value>,
+) -> Result<(), AppError> {
+    let entity_value = entity.as_str();
+    let action_value = action.as_str();
+    let before_value = before.map(|value| value.to_string());
+    let after_value = after.map(|value| value.to_string());
+    sqlx::query!(
+        r#"
+        INSERT INTO audit_log (occurred_at, actor, entity, entity_id, action, before, after)
+        VALUES (datetime('now'), $1, $2, $3, $4, $5, $6)
+        "#,
+        actor.0,
+        entity_value,
+        entity_id,
+        action_value,
+        before_value,
+        after_value
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|error| AppError::internal("failed to write audit log", error))?;
+
+    Ok(())
+}
+
+/// Records a change to a single row, snapshotting its current state as `after`.
+pub(crate) async fn record_row(
+    conn: &mut SqliteConnection,
+    actor: &Actor,
+    entity: Entity,
+    entity_id: &str,
+    action: Action,
+    before: Option<Value>,
+) -> Result<(), AppError> {
+    let after = snapshot(conn, entity, entity_id).await?;
+    record(conn, actor, entity, entity_id, action, before, after).await
+}
+
+/// Reads a row as a JSON object keyed by column name, or `None` if it does not exist.
+pub(crate) async fn snapshot(
+    conn: &mut SqliteConnection,
+    entity: Entity,
+    entity_id: &str,
+) -> Result<Option<Value>, AppError> {
+    let query = format!("SELECT * FROM {} WHERE id = $1", entity.table());
+    let row = sqlx::query(&query)
+        .bind(entity_id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|error| AppError::internal("failed to snapshot row for audit log", error))?;
+
+    row.map(|row| row_to_json(&row)).transpose()
+}
+
+#[utoipa::path(
+    get,
+    path = "/audit",
+    tag = "audit",
+    params(AuditFilter),
+    responses(
+        (status = 200, description = "Newest first", body = Vec<AuditEntry>),
+        (status = 400, description = "`since` or `until` is not a time", body = ErrorBody),
+    )
+)]
+pub(crate) async fn list_audit_entries(
+    State(state): State<AppState>,
+    Query(filter): Query<AuditFilter>,
+) -> Result<Json<Vec<AuditEntry>>, AppError> {
+    tracing::info!(entity = ?filter.entity, "listing audit log");
+    let entity_value = filter.entity.map(Entity::as_str);
+    let limit = filter.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let since = normalize_bound(&state, filter.since.as_deref()).await?;
+    let until = normalize_bound(&state, filter.until.as_deref()).await?;
+    let records = sqlx::query!(
+        r#"
+        SELECT
+            id as "id!: i64",
+            occurred_at,
+            actor,
+            entity,
+            entity_id,
+            action,
+            before,
+            after
+        FROM audit_log
+        WHERE ($1 IS NULL OR entity = $1)
+          AND ($2 IS NULL OR entity_id = $2)
+          AND ($3 IS NULL OR occurred_at >= $3)
+          AND ($4 IS NULL OR occurred_at < $4)
+        ORDER BY id DESC
+        LIMIT $5
+        "#,
+        entity_value,
+        filter.entity_id,
+        since,
+        until,
+        limit
+    )
+    .fetch_all(&state.read_pool)
+    .await
+    .map_err(|error| AppError::internal("failed to fetch audit log", error))?;
+
+    let entries = records
+        .into_iter()
+        .map(|record| {
+            Ok(AuditEntry {
+                id: record.id,
+                occurred_at: record.occurred_at,
+                actor: record.actor,
+                entity: record.entity,
+                entity_id: record.entity_id,
+                action: record.action,
+                before: record.before.as_deref().map(parse_json).transpose()?,
+                after: record.after.as_deref().map(parse_json).transpose()?,
+            })
+        })
+        .collect::<Result<Vec<_>, AppError>>()?;
+
+    tracing::info!(count = entries.len(), "listed audit log");
+    Ok(Json(entries))
+}
+
+/// Normalises a time-range bound to the `YYYY-MM-DD HH:MM:SS` form stored in the log.
+async fn normalize_bound(
+    state: &AppState,
+    value: Option<&str>,
+) -> Result<Option<String>, AppError> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+
+    let record = sqlx::query!(
+        r#"
+        SELECT datetime($1) as "timestamp?: String"
+        "#,
+        value
+    )
+    .fetch_one(&state.read_pool)
+    .await
+    .map_err(|error| AppError::internal("failed to parse time range", error))?;
+
+    match record.timestamp {
+        Some(timestamp) => Ok(Some(timestamp)),
+        None => Err(AppError::bad_request("invalid time range")),
+    }
+}
+
+fn row_to_json(row: &SqliteRow) -> Result<Value, AppError> {
+    let mut object = Map::new();
+    for column in row.columns() {
+        let index = column.ordinal();
+        let raw = row
+            .try_get_raw(index)
+            .map_err(|error| AppError::internal("failed to read row for audit log", error))?;
+        let value = if raw.is_null() {
+            Value::Null
+        } else {
+            let type_name = raw.type_info().name().to_string();
+            match type_name.as_str() {
+                "INTEGER" => row.try_get::<i64, _>(index).map(Value::from),
+                "REAL" => row.try_get::<f64, _>(index).map(Value::from),
+                _ => row.try_get::<String, _>(index).map(Value::from),
+            }
+            .map_err(|error| AppError::internal("failed to read row for audit log", error))?
+        };
+        object.insert(column.name().to_string(), value);
+    }
+
+    Ok(Value::Object(object))
+}
+
+fn parse_json(value: &str) -> Result<Value, AppError> {
+    serde_json::from_str(value)
+        .map_err(|error| AppError::internal("invalid JSON stored in audit log", error))
+}
@@ -0,0 +1,340 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use axum::{extract::State, http::StatusCode, Json};
+use serde::Serialize;
+use sqlx::{
+    sqlite::SqliteConnectOptions, ConnectOptions, Connection, SqliteConnection, SqlitePool,
+};
+use utoipa::ToSchema;
+
+use crate::{config::BackupSettings, AppError, AppState, ErrorBody};
+
+/// Backups are named `mini-tracker-YYYYMMDD-HHMMSS.db` after the UTC time
+/// they were taken. Other files in the backup directory are left alone.
+const PREFIX: &str = "mini-tracker-";
+const EXTENSION: &str = ".db";
+
+/// How often the backup job checks whether a backup is due.
+const CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct Backup {
+    pub(crate) name: String,
+    /// UTC, `YYYY-MM-DD HH:MM:SS`.
+    created_at: String,
+    size_bytes: u64,
+    #[serde(skip)]
+    timestamp: i64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct CreatedBackup {
+    pub(crate) backup: Backup,
+    /// Older backups deleted by rotation.
+    pub(crate) removed: Vec<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/backups",
+    tag = "admin",
+    responses(
+        (status = 201, body = CreatedBackup),
+        (status = 409, description = "A backup was already taken this second", body = ErrorBody),
+    )
+)]
+pub(crate) async fn create_backup(
+    State(state): State<AppState>,
+) -> Result<(StatusCode, Json<CreatedBackup>), AppError> {
+    tracing::info!("creating backup");
+    let created = take_backup(&state.read_pool, &state.backups).await?;
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+/// Backups in the backup directory, newest first.
+#[utoipa::path(
+    get,
+    path = "/admin/backups",
+    tag = "admin",
+    responses((status = 200, description = "Newest first", body = Vec<Backup>))
+)]
+pub(crate) async fn list_backups(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<Backup>>, AppError> {
+    tracing::info!("listing backups");
+    let backups = list(&state.backups.dir).await?;
+    tracing::info!(count = backups.len(), "listed backups");
+    Ok(Json(backups))
+}
+
+/// Takes a backup whenever the newest one is older than
+/// `backups.interval_hours`, so restarting the server does not reset the clock.
+pub async fn run_backup_job(state: AppState) {
+    let interval = i64::from(state.backups.interval_hours) * 60 * 60;
+    let mut check = tokio::time::interval(CHECK_INTERVAL);
+    loop {
+        check.tick().await;
+        let newest = match list(&state.backups.dir).await {
+            Ok(backups) => backups.first().map(|backup| backup.timestamp),
+            Err(error) => {
+                tracing::error!(%error, "failed to list backups");
+                continue;
+            }
+        };
+        if newest.is_some_and(|newest| now() - newest < interval) {
+            continue;
+        }
+        match take_backup(&state.read_pool, &state.backups).await {
+            Ok(created) => tracing::info!(
+                name = %created.backup.name,
+                removed = created.removed.len(),
+                "took scheduled backup"
+            ),
+            Err(error) => tracing::error!(%error, "scheduled backup failed"),
+        }
+    }
+}
+
+/// Writes a backup, checks it and then rotates old ones out.
+pub(crate) async fn take_backup(
+    pool: &SqlitePool,
+    settings: &BackupSettings,
+) -> Result<CreatedBackup, AppError> {
+    let path = write_backup(pool, &settings.dir).await?;
+    if let Err(error) = check_integrity(&path).await {
+        let _ = tokio::fs::remove_file(&path).await;
+        return Err(error);
+    }
+
+    let name = file_name(&path);
+    let removed = rotate(
+        &settings.dir,
+        &name,
+        settings.keep_daily,
+        settings.keep_weekly,
+    )
+    .await?;
+    let backup = list(&settings.dir)
+        .await?
+        .into_iter()
+        .find(|backup| backup.name == name)
+        .ok_or_else(|| AppError::internal_message("new backup disappeared"))?;
+
+    tracing::info!(name = %backup.name, removed = removed.len(), "took backup");
+    Ok(CreatedBackup { backup, removed })
+}
+
+/// Writes a consistent copy of the database to a new timestamped file in
+/// `dir`. `VACUUM INTO` reads from a single snapshot, so the server can keep
+/// serving requests while it runs.
+async fn write_backup(pool: &SqlitePool, dir: &Path) -> Result<PathBuf, AppError> {
+    tokio::fs::create_dir_all(dir)
+        .await
+        .map_err(|error| AppError::internal("failed to create backup directory", error))?;
+
+    let stamp =
+        sqlx::query_scalar!(r#"SELECT strftime('%Y%m%d-%H%M%S', 'now') as "stamp!: String""#)
+            .fetch_one(pool)
+            .await
+            .map_err(|error| AppError::internal("failed to name backup", error))?;
+    let path = dir.join(format!("{PREFIX}{stamp}{EXTENSION}"));
+    if tokio::fs::try_exists(&path).await.unwrap_or(true) {
+        return Err(AppError::conflict("a backup was already taken this second"));
+    }
+
+    let path_value = path.to_string_lossy().into_owned();
+    sqlx::query("VACUUM INTO $1")
+        .bind(&path_value)
+        .execute(pool)
+        .await
+        .map_err(|error| AppError::internal("failed to write backup", error))?;
+    // An in-memory database "vacuums into" another in-memory one.
+    if !tokio::fs::try_exists(&path).await.unwrap_or(false) {
+        return Err(AppError::internal_message("backup file was not written"));
+    }
+
+    Ok(path)
+}
+
+/// Runs `PRAGMA integrity_check` on a database file without changing it.
+pub(crate) async fn check_integrity(path: &Path) -> Result<(), AppError> {
+    let mut conn = open_read_only(path).await?;
+    let problems: Vec<String> = sqlx::query_scalar("PRAGMA integrity_check")
+        .fetch_all(&mut conn)
+        .await
+        .map_err(|error| AppError::internal("failed to check backup integrity", error))?;
+    let _ = conn.close().await;
+
+    if problems != ["ok"] {
+        tracing::error!(path = %path.display(), ?problems, "backup failed integrity check");
+        return Err(AppError::internal_message("backup failed integrity check"));
+    }
+    Ok(())
+}
+
+/// The newest migration applied to a database file, or `None` if it has no
+/// migration table (so it is not one of ours).
+pub(crate) async fn schema_version_of(path: &Path) -> Result<Option<i64>, AppError> {
+    let mut conn = open_read_only(path).await?;
+    let has_migrations: bool = sqlx::query_scalar(
+        "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations'",
+    )
+    .fetch_one(&mut conn)
+    .await
+    .map_err(|error| AppError::internal("failed to read backup schema", error))?;
+
+    let version = if has_migrations {
+        sqlx::query_scalar("SELECT MAX(version) FROM _sqlx_migrations WHERE success = 1")
+            .fetch_one(&mut conn)
+            .await
+            .map_err(|error| AppError::internal("failed to read backup schema", error))?
+    } else {
+        None
+    };
+    let _ = conn.close().await;
+    Ok(version)
+}
+
+async fn open_read_only(path: &Path) -> Result<SqliteConnection, AppError> {
+    SqliteConnectOptions::new()
+        .filename(path)
+        .read_only(true)
+        .connect()
+        .await
+        .map_err(|error| AppError::internal("failed to open backup", error))
+}
+
+/// Backups in `dir`, newest first. A missing directory has none.
+pub(crate) async fn list(dir: &Path) -> Result<Vec<Backup>, AppError> {
+    let mut entries = match tokio::fs::read_dir(dir).await {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(AppError::internal("failed to read backup directory", error)),
+    };
+
+    let mut backups = Vec::new();
+    while let Some(entry) = entries
+        .next_entry()
+        .await
+        .map_err(|error| AppError::internal("failed to read backup directory", error))?
+    {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let Some((timestamp, created_at)) = parse_name(&name) else {
+            continue;
+        };
+        let size_bytes = entry
+            .metadata()
+            .await
+            .map_err(|error| AppError::internal("failed to read backup size", error))?
+            .len();
+        backups.push(Backup {
+            name,
+            created_at,
+            size_bytes,
+            timestamp,
+        });
+    }
+
+    backups.sort_by_key(|backup| std::cmp::Reverse(backup.timestamp));
+    Ok(backups)
+}
+
+/// Keeps `latest`, the newest backup of each of the `keep_daily` most recent
+/// days and of each of the `keep_weekly` most recent weeks (Monday to
+/// Sunday), and deletes the rest. Returns the names of the deleted files.
+async fn rotate(
+    dir: &Path,
+    latest: &str,
+    keep_daily: usize,
+    keep_weekly: usize,
+) -> Result<Vec<String>, AppError> {
+    let backups = list(dir).await?;
+    let mut days = HashSet::new();
+    let mut weeks = HashSet::new();
+    let mut removed = Vec::new();
+    for backup in backups {
+        let day = backup.timestamp.div_euclid(24 * 60 * 60);
+        // 1970-01-01 was a Thursday, so this makes weeks start on Monday.
+        let week = (day + 3).div_euclid(7);
+        let mut keep = backup.name == latest;
+        if days.len() < keep_daily && days.insert(day) {
+            keep = true;
+        }
+        if weeks.len() < keep_weekly && weeks.insert(week) {
+            keep = true;
+        }
+        if keep {
+            continue;
+        }
+
+        tokio::fs::remove_file(dir.join(&backup.name))
+            .await
+            .map_err(|error| AppError::internal("failed to remove old backup", error))?;
+        removed.push(backup.name);
+    }
+    Ok(removed)
+}
+
+pub(crate) fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Seconds since the Unix epoch and `YYYY-MM-DD HH:MM:SS` for a backup name.
+fn parse_name(name: &str) -> Option<(i64, String)> {
+    let stamp = name.strip_prefix(PREFIX)?.strip_suffix(EXTENSION)?;
+    let (date, time) = stamp.split_once('-')?;
+    if date.len() != 8 || time.len() != 6 {
+        return None;
+    }
+    let number = |text: &str| -> Option<i64> {
+        text.bytes()
+            .all(|byte| byte.is_ascii_digit())
+            .then(|| text.parse().ok())?
+    };
+    let (year, month, day) = (
+        number(&date[..4])?,
+        number(&date[4..6])?,
+        number(&date[6..])?,
+    );
+    let (hour, minute, second) = (
+        number(&time[..2])?,
+        number(&time[2..4])?,
+        number(&time[4..])?,
+    );
+    if !(1..=12).contains(&month)
+        || !(1..=31).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 59
+    {
+        return None;
+    }
+
+    let timestamp =
+        days_from_civil(year, month, day) * 24 * 60 * 60 + hour * 60 * 60 + minute * 60 + second;
+    let created_at = format!("{year:04}-{month:02}-{day:02} {hour:02}:{minute:02}:{second:02}");
+    Some((timestamp, created_at))
+}
+
+/// Days from 1970-01-01 to a date in the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() as i64)
+        .unwrap_or_default()
+}
@@ -0,0 +1,546 @@
+//! Server settings. Each layer overrides the one before it: built-in
+//! defaults, then a TOML file, then environment variables, then flags.
+
+use std::{
+    fmt,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use axum::http::{header, HeaderValue};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+
+use crate::DEFAULT_RETENTION_DAYS;
+
+/// Read when neither `--config` nor `MINI_TRACKER_CONFIG` names a file.
+pub const DEFAULT_CONFIG_FILE: &str = "mini-tracker.toml";
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Address the HTTP server listens on.
+    pub bind: SocketAddr,
+    pub database_url: Option<String>,
+    /// Size of the read connection pool; writes always share one connection.
+    pub max_connections: u32,
+    pub log_format: LogFormat,
+    /// Browser origins allowed to call the API, or `*` for any. Empty turns
+    /// CORS off, which is fine when the frontend is served from the same host.
+    pub cors_origins: Vec<String>,
+    /// Largest request body accepted, except for photo uploads.
+    pub max_body_bytes: usize,
+    /// How long a handler may take before the request fails with 503.
+    pub request_timeout_secs: u64,
+    /// Where uploaded photos and their thumbnails are stored.
+    pub upload_dir: PathBuf,
+    /// A Trunk `dist/` directory to serve the frontend from. Without one, a
+    /// build with the `embed-frontend` feature serves the copy embedded at
+    /// compile time, and any other build only serves the API.
+    pub frontend_dir: Option<PathBuf>,
+    /// How long deleted items stay in the trash before they are purged.
+    pub trash_retention_days: u32,
+    pub backups: BackupSettings,
+    pub features: Features,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            bind: SocketAddr::from(([0, 0, 0, 0], 3000)),
+            database_url: None,
+            max_connections: 5,
+            log_format: LogFormat::Text,
+            cors_origins: Vec::new(),
+            max_body_bytes: 2 * 1024 * 1024,
+            request_timeout_secs: 30,
+            upload_dir: PathBuf::from("photos"),
+            frontend_dir: None,
+            trash_retention_days: DEFAULT_RETENTION_DAYS,
+            backups: BackupSettings::default(),
+            features: Features::default(),
+        }
+    }
+}
+
+/// Where database backups go and how many are kept.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BackupSettings {
+    pub dir: PathBuf,
+    /// How often the backup job takes a backup.
+    pub interval_hours: u32,
+    /// Newest backup of each of this many most recent days is kept.
+    pub keep_daily: usize,
+    /// Newest backup of each of this many most recent weeks is kept.
+    pub keep_weekly: usize,
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::from("backups"),
+            interval_hours: 24,
+            keep_daily: 7,
+            keep_weekly: 4,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Human-readable lines.
+    #[default]
+    Text,
+    /// One JSON object per line, for log collectors.
+    Json,
+}
+
+/// Parts of the server that can be switched off.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Features {
+    /// The `/events` change stream.
+    pub events: bool,
+    /// The `/ws` collaboration socket.
+    pub presence: bool,
+    /// The `/sync` change feed and batch push.
+    pub sync: bool,
+    /// The hourly job that empties expired trash.
+    pub purge_job: bool,
+    /// The job that takes a backup every `backups.interval_hours`.
+    pub backup_job: bool,
+    /// `/api/v1/openapi.json`, its deprecated `/openapi.json` alias and the
+    /// Swagger UI page at `/docs`.
+    pub docs: bool,
+}
+
+impl Default for Features {
+    fn default() -> Self {
+        Self {
+            events: true,
+            presence: true,
+            sync: true,
+            purge_job: true,
+            backup_job: true,
+            docs: true,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Feature {
+    Events,
+    Presence,
+    Sync,
+    PurgeJob,
+    BackupJob,
+    Docs,
+}
+
+impl Features {
+    pub fn set(&mut self, feature: Feature, enabled: bool) {
+        let flag = match feature {
+            Feature::Events => &mut self.events,
+            Feature::Presence => &mut self.presence,
+            Feature::Sync => &mut self.sync,
+            Feature::PurgeJob => &mut self.purge_job,
+            Feature::BackupJob => &mut self.backup_job,
+            Feature::Docs => &mut self.docs,
+        };
+        *flag = enabled;
+    }
+}
+
+/// Flags that override the file and the environment.
+#[derive(Clone, Debug, Default, clap::Args)]
+pub struct ConfigArgs {
+    /// TOML file to read settings from [env: MINI_TRACKER_CONFIG] [default: mini-tracker.toml if present]
+    #[arg(long, global = true, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+    /// Address to listen on [env: BIND_ADDRESS] [default: 0.0.0.0:3000]
+    #[arg(long, global = true, value_name = "ADDRESS")]
+    pub bind: Option<SocketAddr>,
+    /// Database URL, `sqlite:` or `postgres:` [env: DATABASE_URL]
+    #[arg(long, global = true, value_name = "URL")]
+    pub database_url: Option<String>,
+    /// Size of the read connection pool [env: DATABASE_MAX_CONNECTIONS] [default: 5]
+    #[arg(long, global = true, value_name = "COUNT")]
+    pub max_connections: Option<u32>,
+    /// Log output format [env: LOG_FORMAT] [default: text]
+    #[arg(long, global = true, value_enum, value_name = "FORMAT")]
+    pub log_format: Option<LogFormat>,
+    /// Browser origin allowed to call the API; repeat for several [env: CORS_ORIGINS, comma-separated]
+    #[arg(long = "cors-origin", global = true, value_name = "ORIGIN")]
+    pub cors_origins: Vec<String>,
+    /// Largest request body in bytes, photo uploads aside [env: MAX_BODY_BYTES] [default: 2097152]
+    #[arg(long, global = true, value_name = "BYTES")]
+    pub max_body_bytes: Option<usize>,
+    /// Seconds a request may take [env: REQUEST_TIMEOUT_SECS] [default: 30]
+    #[arg(long, global = true, value_name = "SECONDS")]
+    pub request_timeout_secs: Option<u64>,
+    /// Directory for uploaded photos [env: PHOTO_DIR] [default: photos]
+    #[arg(long, global = true, value_name = "DIR")]
+    pub upload_dir: Option<PathBuf>,
+    /// Trunk `dist/` directory to serve the frontend from [env: FRONTEND_DIR]
+    #[arg(long, global = true, value_name = "DIR")]
+    pub frontend_dir: Option<PathBuf>,
+    /// Days deleted items stay in the trash [env: TRASH_RETENTION_DAYS] [default: 30]
+    #[arg(long, global = true, value_name = "DAYS")]
+    pub trash_retention_days: Option<u32>,
+    /// Directory for database backups [env: BACKUP_DIR] [default: backups]
+    #[arg(long, global = true, value_name = "DIR")]
+    pub backup_dir: Option<PathBuf>,
+    /// Hours between scheduled backups [env: BACKUP_INTERVAL_HOURS] [default: 24]
+    #[arg(long, global = true, value_name = "HOURS")]
+    pub backup_interval_hours: Option<u32>,
+    /// Days with a backup to keep [env: BACKUP_KEEP_DAILY] [default: 7]
+    #[arg(long, global = true, value_name = "COUNT")]
+    pub backup_keep_daily: Option<usize>,
+    /// Weeks with a backup to keep [env: BACKUP_KEEP_WEEKLY] [default: 4]
+    #[arg(long, global = true, value_name = "COUNT")]
+    pub backup_keep_weekly: Option<usize>,
+    /// Turn a feature on [env: ENABLE_FEATURES, comma-separated]
+    #[arg(long, global = true, value_enum, value_name = "FEATURE")]
+    pub enable: Vec<Feature>,
+    /// Turn a feature off [env: DISABLE_FEATURES, comma-separated]
+    #[arg(long, global = true, value_enum, value_name = "FEATURE")]
+    pub disable: Vec<Feature>,
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    ReadFile {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+    ParseFile {
+        path: PathBuf,
+        error: toml::de::Error,
+    },
+    /// A setting that could not be parsed; `source` says where it came from.
+    InvalidValue { source: String, message: String },
+    /// The settings parsed but do not make sense together.
+    Invalid(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::ReadFile { path, error } => {
+                write!(f, "could not read config file {}: {error}", path.display())
+            }
+            ConfigError::ParseFile { path, error } => {
+                write!(f, "invalid config file {}: {error}", path.display())
+            }
+            ConfigError::InvalidValue { source, message } => write!(f, "{source}: {message}"),
+            ConfigError::Invalid(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Config {
+    /// Builds the configuration from the process environment and `args`.
+    pub fn load(args: &ConfigArgs) -> Result<Self, ConfigError> {
+        Self::load_with_env(args, |name| std::env::var(name).ok())
+    }
+
+    /// Like [`Config::load`], but reads environment variables through `env`.
+    pub fn load_with_env(
+        args: &ConfigArgs,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self, ConfigError> {
+        let file = match (&args.config, env("MINI_TRACKER_CONFIG")) {
+            (Some(path), _) => Some(path.clone()),
+            (None, Some(path)) => Some(PathBuf::from(path)),
+            (None, None) => Some(PathBuf::from(DEFAULT_CONFIG_FILE)).filter(|path| path.exists()),
+        };
+        let mut config = match file {
+            Some(path) => Self::from_file(&path)?,
+            None => Self::default(),
+        };
+        config.apply_env(&env)?;
+        config.apply_args(args);
+        Ok(config)
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let text = std::fs::read_to_string(path).map_err(|error| ConfigError::ReadFile {
+            path: path.to_path_buf(),
+            error,
+        })?;
+        toml::from_str(&text).map_err(|error| ConfigError::ParseFile {
+            path: path.to_path_buf(),
+            error,
+        })
+    }
+
+    fn apply_env(&mut self, env: &impl Fn(&str) -> Option<String>) -> Result<(), ConfigError> {
+        if let Some(bind) = parse_env(env, "BIND_ADDRESS")? {
+            self.bind = bind;
+        }
+        if let Some(url) = env("DATABASE_URL") {
+            self.database_url = Some(url);
+        }
+        if let Some(count) = parse_env(env, "DATABASE_MAX_CONNECTIONS")? {
+            self.max_connections = count;
+        }
+        if let Some(value) = env("LOG_FORMAT") {
+            self.log_format = parse_value_enum("LOG_FORMAT", &value)?;
+        }
+        if let Some(value) = env("CORS_ORIGINS") {
+            self.cors_origins = split_list(&value).map(str::to_string).collect();
+        }
+        if let Some(bytes) = parse_env(env, "MAX_BODY_BYTES")? {
+            self.max_body_bytes = bytes;
+        }
+        if let Some(seconds) = parse_env(env, "REQUEST_TIMEOUT_SECS")? {
+            self.request_timeout_secs = seconds;
+        }
+        if let Some(dir) = env("PHOTO_DIR") {
+            self.upload_dir = PathBuf::from(dir);
+        }
+        if let Some(dir) = env("FRONTEND_DIR") {
+            self.frontend_dir = Some(PathBuf::from(dir));
+        }
+        if let Some(days) = parse_env(env, "TRASH_RETENTION_DAYS")? {
+            self.trash_retention_days = days;
+        }
+        if let Some(dir) = env("BACKUP_DIR") {
+            self.backups.dir = PathBuf::from(dir);
+        }
+        if let Some(hours) = parse_env(env, "BACKUP_INTERVAL_HOURS")? {
+            self.backups.interval_hours = hours;
+        }
+        if let Some(count) = parse_env(env, "BACKUP_KEEP_DAILY")? {
+            self.backups.keep_daily = count;
+        }
+        if let Some(count) = parse_env(env, "BACKUP_KEEP_WEEKLY")? {
+            self.backups.keep_weekly = count;
+        }
+        for (name, enabled) in [("ENABLE_FEATURES", true), ("DISABLE_FEATURES", false)] {
+            let Some(value) = env(name) else {
+                continue;
+            };
+            for feature in split_list(&value) {
+                self.features.set(parse_value_enum(name, feature)?, enabled);
+            }
+        }
+        Ok(())
+    }
+
+    fn apply_args(&mut self, args: &ConfigArgs) {
+        if let Some(bind) = args.bind {
+            self.bind = bind;
+        }
+        if let Some(url) = &args.database_url {
+            self.database_url = Some(url.clone());
+        }
+        if let Some(count) = args.max_connections {
+            self.max_connections = count;
+        }
+        if let Some(format) = args.log_format {
+            self.log_format = format;
+        }
+        if !args.cors_origins.is_empty() {
+            self.cors_origins = args.cors_origins.clone();
+        }
+        if let Some(bytes) = args.max_body_bytes {
+            self.max_body_bytes = bytes;
+        }
+        if let Some(seconds) = args.request_timeout_secs {
+            self.request_timeout_secs = seconds;
+        }
+        if let Some(dir) = &args.upload_dir {
+            self.upload_dir = dir.clone();
+        }
+        if let Some(dir) = &args.frontend_dir {
+            self.frontend_dir = Some(dir.clone());
+        }
+        if let Some(days) = args.trash_retention_days {
+            self.trash_retention_days = days;
+        }
+        if let Some(dir) = &args.backup_dir {
+            self.backups.dir = dir.clone();
+        }
+        if let Some(hours) = args.backup_interval_hours {
+            self.backups.interval_hours = hours;
+        }
+        if let Some(count) = args.backup_keep_daily {
+            self.backups.keep_daily = count;
+        }
+        if let Some(count) = args.backup_keep_weekly {
+            self.backups.keep_weekly = count;
+        }
+        for feature in &args.enable {
+            self.features.set(*feature, true);
+        }
+        for feature in &args.disable {
+            self.features.set(*feature, false);
+        }
+    }
+
+    /// Whether `database_url` points at PostgreSQL rather than SQLite.
+    pub fn uses_postgres(&self) -> bool {
+        self.database_url.as_deref().is_some_and(|url| {
+            url.starts_with("postgres:") || url.starts_with("postgresql:")
+        })
+    }
+
+    /// Checks the settings before anything is started, so a bad value stops
+    /// the server with a message rather than failing halfway through startup.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let Some(url) = &self.database_url else {
+            return Err(ConfigError::Invalid(
+                "no database configured; set DATABASE_URL, pass --database-url or add database_url to the config file".to_string(),
+            ));
+        };
+        if self.uses_postgres() {
+            if !cfg!(feature = "postgres") {
+                return Err(ConfigError::Invalid(format!(
+                    "database_url `{url}` is a PostgreSQL URL, but this build has no PostgreSQL support; rebuild with `--features postgres`"
+                )));
+            }
+        } else if !url.starts_with("sqlite:") {
+            return Err(ConfigError::Invalid(format!(
+                "database_url `{url}` is not supported; expected a sqlite: or postgres: URL"
+            )));
+        }
+        if self.max_connections == 0 {
+            return Err(ConfigError::Invalid(
+                "max_connections must be at least 1".to_string(),
+            ));
+        }
+        if self.max_body_bytes == 0 {
+            return Err(ConfigError::Invalid(
+                "max_body_bytes must be at least 1".to_string(),
+            ));
+        }
+        if self.request_timeout_secs == 0 {
+            return Err(ConfigError::Invalid(
+                "request_timeout_secs must be at least 1".to_string(),
+            ));
+        }
+        if self.upload_dir.as_os_str().is_empty() {
+            return Err(ConfigError::Invalid(
+                "upload_dir cannot be empty".to_string(),
+            ));
+        }
+        if let Some(dir) = self
+            .frontend_dir
+            .as_ref()
+            .filter(|dir| !dir.join("index.html").is_file())
+        {
+            return Err(ConfigError::Invalid(format!(
+                "frontend_dir {} has no index.html; run `trunk build --release` in frontend/ first",
+                dir.display()
+            )));
+        }
+        if self.backups.dir.as_os_str().is_empty() {
+            return Err(ConfigError::Invalid(
+                "backups.dir cannot be empty".to_string(),
+            ));
+        }
+        if self.backups.interval_hours == 0 {
+            return Err(ConfigError::Invalid(
+                "backups.interval_hours must be at least 1".to_string(),
+            ));
+        }
+        if self.backups.keep_daily == 0 && self.backups.keep_weekly == 0 {
+            return Err(ConfigError::Invalid(
+                "backups.keep_daily and backups.keep_weekly cannot both be 0".to_string(),
+            ));
+        }
+        if self.cors_origins.len() > 1 && self.cors_origins.iter().any(|origin| origin == "*") {
+            return Err(ConfigError::Invalid(
+                "cors_origins cannot mix `*` with specific origins".to_string(),
+            ));
+        }
+        for origin in &self.cors_origins {
+            if origin != "*" && !is_valid_origin(origin) {
+                return Err(ConfigError::Invalid(format!(
+                    "cors origin `{origin}` must be `*` or a scheme and host such as http://localhost:8080"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// The CORS layer for the configured origins, or `None` when CORS is off.
+    pub fn cors_layer(&self) -> Option<CorsLayer> {
+        if self.cors_origins.is_empty() {
+            return None;
+        }
+        let origins = if self.cors_origins.iter().any(|origin| origin == "*") {
+            AllowOrigin::from(Any)
+        } else {
+            AllowOrigin::list(
+                self.cors_origins
+                    .iter()
+                    .filter_map(|origin| HeaderValue::from_str(origin).ok()),
+            )
+        };
+        Some(
+            CorsLayer::new()
+                .allow_origin(origins)
+                .allow_methods(Any)
+                .allow_headers(Any)
+                .expose_headers([header::ETAG, crate::middleware::REQUEST_ID_HEADER]),
+        )
+    }
+}
+
+fn is_valid_origin(origin: &str) -> bool {
+    let Some((scheme, host)) = origin.split_once("://") else {
+        return false;
+    };
+    matches!(scheme, "http" | "https")
+        && !host.is_empty()
+        && !host.contains('/')
+        && HeaderValue::from_str(origin).is_ok()
+}
+
+fn split_list(value: &str) -> impl Iterator<Item = &str> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
+
+fn parse_env<T>(env: &impl Fn(&str) -> Option<String>, name: &str) -> Result<Option<T>, ConfigError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    env(name)
+        .map(|value| {
+            value
+                .trim()
+                .parse()
+                .map_err(|error| ConfigError::InvalidValue {
+                    source: name.to_string(),
+                    message: format!("`{value}` is not valid: {error}"),
+                })
+        })
+        .transpose()
+}
+
+fn parse_value_enum<T: ValueEnum>(name: &str, value: &str) -> Result<T, ConfigError> {
+    T::from_str(value.trim(), true).map_err(|_| {
+        let expected = T::value_variants()
+            .iter()
+            .filter_map(|variant| variant.to_possible_value())
+            .map(|value| value.get_name().to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        ConfigError::InvalidValue {
+            source: name.to_string(),
+            message: format!("`{value}` is not one of {expected}"),
+        }
+    })
+}
@@ -0,0 +1,78 @@
+use std::{str::FromStr, time::Duration};
+
+use sqlx::{
+    migrate::{MigrateError, Migrator},
+    sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous},
+    SqlitePool,
+};
+
+pub(crate) static MIGRATOR: Migrator = sqlx::migrate!();
+
+/// How long a connection waits for another one's lock before failing with
+/// `SQLITE_BUSY`.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The two pools the server runs on. SQLite allows one writer at a time, so
+/// every write goes through a single connection and waits its turn in the
+/// pool rather than failing with `SQLITE_BUSY`. In WAL mode readers never
+/// block the writer or each other, so reads get a pool of their own.
+pub struct Pools {
+    pub writer: SqlitePool,
+    /// Read-only connections. For an in-memory database this is the writer,
+    /// since each connection would otherwise see a database of its own.
+    pub reader: SqlitePool,
+}
+
+/// Opens a connection pool, creating the database file if it does not exist
+/// yet. Foreign keys are enforced on every connection.
+pub async fn connect(database_url: &str, max_connections: u32) -> Result<SqlitePool, sqlx::Error> {
+    SqlitePoolOptions::new()
+        .max_connections(max_connections)
+        .connect_with(connect_options(database_url)?)
+        .await
+}
+
+/// Opens the single-connection writer pool, then up to `max_readers`
+/// read-only connections once the file exists.
+pub async fn connect_pools(database_url: &str, max_readers: u32) -> Result<Pools, sqlx::Error> {
+    let writer = connect(database_url, 1).await?;
+    if database_url.contains(":memory:") {
+        return Ok(Pools {
+            reader: writer.clone(),
+            writer,
+        });
+    }
+
+    let reader = SqlitePoolOptions::new()
+        .max_connections(max_readers)
+        .connect_with(connect_options(database_url)?.read_only(true))
+        .await?;
+    Ok(Pools { writer, reader })
+}
+
+/// WAL journal so readers and the writer do not block each other, with
+/// `synchronous = NORMAL`, which is durable in WAL mode except against power
+/// loss, and a busy timeout for the locks that remain.
+fn connect_options(database_url: &str) -> Result<SqliteConnectOptions, sqlx::Error> {
+    Ok(SqliteConnectOptions::from_str(database_url)?
+        .create_if_missing(true)
+        .foreign_keys(true)
+        .journal_mode(SqliteJournalMode::Wal)
+        .synchronous(SqliteSynchronous::Normal)
+        .busy_timeout(BUSY_TIMEOUT))
+}
+
+/// Applies any migrations the database has not seen yet.
+pub async fn migrate(pool: &SqlitePool) -> Result<(), MigrateError> {
+    MIGRATOR.run(pool).await
+}
+
+/// The newest migration this build knows about; a database is on this schema
+/// once it has been applied.
+pub(crate) fn schema_version() -> i64 {
+    MIGRATOR
+        .iter()
+        .map(|migration| migration.version)
+        .max()
+        .unwrap_or_default()
+}
@@ -0,0 +1,43 @@
+//! The unversioned paths the API was served at before `/api/v1`. They answer
+//! as before, with headers telling clients where to go and by when.
+
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderValue},
+    middleware::{self, Next},
+    response::Response,
+    Router,
+};
+
+/// When the unversioned paths were deprecated (2026-10-18), as an RFC 9745
+/// `Deprecation` date.
+pub const DEPRECATED_SINCE: &str = "@1792281600";
+/// When the unversioned paths will be removed, as an RFC 8594 `Sunset` date.
+pub const SUNSET: &str = "Sun, 18 Apr 2027 00:00:00 GMT";
+
+/// The routes of `router` at the root, each response marked deprecated in
+/// favour of the same path under `successor`.
+pub(crate) fn unversioned(router: Router, successor: &'static str) -> Router {
+    router.layer(middleware::from_fn_with_state(successor, mark_deprecated))
+}
+
+async fn mark_deprecated(
+    State(successor): State<&'static str>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let path = request
+        .uri()
+        .path_and_query()
+        .map_or(request.uri().path(), |path| path.as_str());
+    let link = format!("<{successor}{path}>; rel=\"successor-version\"");
+
+    let mut response = next.run(request).await;
+    let headers = response.headers_mut();
+    headers.insert("deprecation", HeaderValue::from_static(DEPRECATED_SINCE));
+    headers.insert("sunset", HeaderValue::from_static(SUNSET));
+    if let Ok(link) = HeaderValue::from_str(&link) {
+        headers.insert(header::LINK, link);
+    }
+    response
+}
@@ -0,0 +1,239 @@
+use std::{
+    collections::VecDeque,
+    convert::Infallible,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    extract::{Query, State},
+    http::HeaderMap,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures_util::{stream, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::{self, error::RecvError};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    audit::{Action, Entity},
+    AppError, ErrorBody, Repository, UserModelListItem,
+};
+
+/// How many events a slow subscriber can fall behind before it is told to resync.
+const CHANNEL_CAPACITY: usize = 256;
+
+/// How many recent events are kept for clients reconnecting with `Last-Event-ID`.
+const REPLAY_CAPACITY: usize = 256;
+
+/// A change pushed to every `/events` subscriber. Besides these, a client that
+/// missed events receives `{"type": "resync"}` and should reload everything.
+#[derive(Clone, Serialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum ChangeEvent {
+    /// A lot was created or changed; carries the row as `GET /user-models` returns it.
+    UserModelUpserted { user_model: UserModelListItem },
+    /// A lot no longer shows up in `GET /user-models`.
+    UserModelRemoved { id: Uuid },
+    /// Anything else; clients refetch what they show of `entity`.
+    Changed {
+        entity: Entity,
+        id: String,
+        action: Action,
+    },
+}
+
+/// Sent in place of the events a client missed.
+pub(crate) const RESYNC: &str = r#"{"type":"resync"}"#;
+
+#[derive(Clone)]
+pub(crate) struct Published {
+    id: u64,
+    /// The event as JSON.
+    pub(crate) data: String,
+}
+
+#[derive(Default)]
+struct Recent {
+    last_id: u64,
+    events: VecDeque<Published>,
+}
+
+/// Fans change events out to SSE subscribers and remembers the most recent
+/// ones so reconnecting clients can catch up.
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<Published>,
+    recent: Arc<Mutex<Recent>>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self {
+            sender,
+            recent: Arc::default(),
+        }
+    }
+
+    pub(crate) fn publish(&self, event: ChangeEvent) {
+        let data = match serde_json::to_string(&event) {
+            Ok(data) => data,
+            Err(error) => {
+                tracing::error!(%error, "failed to serialize change event");
+                return;
+            }
+        };
+
+        // IDs are handed out and sent under the lock so a subscriber's replay
+        // and live stream never overlap or leave a gap.
+        let mut recent = self.lock();
+        recent.last_id += 1;
+        let published = Published {
+            id: recent.last_id,
+            data,
+        };
+        recent.events.push_back(published.clone());
+        if recent.events.len() > REPLAY_CAPACITY {
+            recent.events.pop_front();
+        }
+        // Sending only fails when nobody is listening.
+        let _ = self.sender.send(published);
+    }
+
+    pub(crate) fn changed(&self, entity: Entity, id: &str, action: Action) {
+        self.publish(ChangeEvent::Changed {
+            entity,
+            id: id.to_string(),
+            action,
+        });
+    }
+
+    /// Starts a subscription, along with the events a client that last saw
+    /// `last_id` has missed. A client too far behind gets a resync instead.
+    fn subscribe(&self, last_id: Option<u64>) -> (Vec<Published>, broadcast::Receiver<Published>) {
+        let recent = self.lock();
+        let receiver = self.sender.subscribe();
+        let Some(last_id) = last_id else {
+            return (Vec::new(), receiver);
+        };
+
+        let oldest = recent
+            .events
+            .front()
+            .map_or(recent.last_id + 1, |event| event.id);
+        if last_id > recent.last_id || last_id + 1 < oldest {
+            return (vec![resync()], receiver);
+        }
+
+        let missed = recent
+            .events
+            .iter()
+            .filter(|event| event.id > last_id)
+            .cloned()
+            .collect();
+        (missed, receiver)
+    }
+
+    /// Events published from now on, for transports without replay.
+    pub(crate) fn subscribe_live(&self) -> broadcast::Receiver<Published> {
+        self.sender.subscribe()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Recent> {
+        self.recent
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(crate) struct EventsQuery {
+    /// Fallback for clients that cannot set the `Last-Event-ID` header.
+    last_event_id: Option<u64>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/events",
+    tag = "events",
+    params(
+        ("Last-Event-ID" = Option<u64>, Header, description = "Replays the events after this one"),
+        EventsQuery,
+    ),
+    responses(
+        (status = 200, description = "Server-sent events, each carrying one change event as JSON", content_type = "text/event-stream", body = ChangeEvent),
+        (status = 400, description = "`Last-Event-ID` is not a number", body = ErrorBody),
+    )
+)]
+pub(crate) async fn stream_events(
+    State(events): State<EventBus>,
+    headers: HeaderMap,
+    Query(query): Query<EventsQuery>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+    let last_id = match headers.get("last-event-id") {
+        Some(value) => Some(
+            value
+                .to_str()
+                .ok()
+                .and_then(|value| value.trim().parse::<u64>().ok())
+                .ok_or_else(|| AppError::bad_request("invalid Last-Event-ID header"))?,
+        ),
+        None => query.last_event_id,
+    };
+    tracing::info!(?last_id, "subscribing to events");
+
+    let (missed, receiver) = events.subscribe(last_id);
+    let live = stream::unfold(receiver, |mut receiver| async move {
+        let published = match receiver.recv().await {
+            Ok(published) => published,
+            Err(RecvError::Lagged(skipped)) => {
+                tracing::warn!(skipped, "event subscriber fell behind");
+                resync()
+            }
+            Err(RecvError::Closed) => return None,
+        };
+        Some((published, receiver))
+    });
+    let events = stream::iter(missed).chain(live).map(|published| {
+        let event = Event::default().data(published.data);
+        // A resync has no ID of its own, so the client keeps its last one.
+        Ok(match published.id {
+            0 => event,
+            id => event.id(id.to_string()),
+        })
+    });
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+/// Publishes the current state of a lot, or its removal if it is no longer
+/// listed. Failures are logged rather than returned, since the change itself
+/// has already been committed.
+pub(crate) async fn publish_user_model(repository: &dyn Repository, events: &EventBus, id: &str) {
+    let id = match Uuid::parse_str(id) {
+        Ok(id) => id,
+        Err(error) => return tracing::error!(%error, id, "invalid user model id"),
+    };
+    match repository.list_user_models(None, Some(id)).await {
+        Ok(items) => match items.into_iter().next() {
+            Some(user_model) => events.publish(ChangeEvent::UserModelUpserted { user_model }),
+            None => events.publish(ChangeEvent::UserModelRemoved { id }),
+        },
+        Err(error) => tracing::error!(%error, %id, "failed to load user model for event"),
+    }
+}
+
+fn resync() -> Published {
+    Published {
+        id: 0,
+        data: RESYNC.to_string(),
+    }
+}
@@ -0,0 +1,129 @@
+//! The frontend's Trunk build, served next to the API so one binary delivers
+//! the whole app.
+
+use std::{
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
+
+use axum::{
+    extract::Request,
+    http::{header, Method, StatusCode},
+    response::{IntoResponse, Response},
+    Router,
+};
+
+use crate::Config;
+
+/// For files whose names carry Trunk's content hash: a new build gets new
+/// names, so browsers never need to ask again.
+const IMMUTABLE: &str = "public, max-age=31536000, immutable";
+/// For `index.html` and anything else that keeps its name across builds.
+const REVALIDATE: &str = "no-cache";
+
+/// Where the built frontend is read from.
+#[derive(Clone, Debug)]
+pub enum Frontend {
+    /// A Trunk `dist/` directory, read on every request.
+    Dir(PathBuf),
+    /// `frontend/dist` as it was when the binary was built.
+    #[cfg(feature = "embed-frontend")]
+    Embedded,
+}
+
+#[cfg(feature = "embed-frontend")]
+#[derive(rust_embed::RustEmbed)]
+#[folder = "../frontend/dist/"]
+struct Dist;
+
+impl Frontend {
+    /// `frontend_dir` if it is set, else the embedded copy in builds that
+    /// have one.
+    pub fn from_config(config: &Config) -> Option<Self> {
+        match &config.frontend_dir {
+            Some(dir) => Some(Frontend::Dir(dir.clone())),
+            #[cfg(feature = "embed-frontend")]
+            None => Some(Frontend::Embedded),
+            #[cfg(not(feature = "embed-frontend"))]
+            None => None,
+        }
+    }
+
+    async fn read(&self, path: &str) -> Option<Vec<u8>> {
+        match self {
+            Frontend::Dir(dir) => tokio::fs::read(dir.join(path)).await.ok(),
+            #[cfg(feature = "embed-frontend")]
+            Frontend::Embedded => Dist::get(path).map(|file| file.data.into_owned()),
+        }
+    }
+}
+
+/// `app` with the frontend answering the `GET` requests no route matches.
+/// Paths that are not files get `index.html`, so client routes survive a
+/// reload; `/api/` paths never do. Compression comes from the middleware
+/// stack, so this goes on before it.
+pub(crate) fn serve(app: Router, frontend: Frontend) -> Router {
+    let frontend = Arc::new(frontend);
+    app.fallback(move |request: Request| asset(frontend.clone(), request))
+}
+
+async fn asset(frontend: Arc<Frontend>, request: Request) -> Response {
+    let path = request.uri().path();
+    if !matches!(*request.method(), Method::GET | Method::HEAD)
+        || path == "/api"
+        || path.starts_with("/api/")
+    {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    let Some(path) = relative_path(path) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    if !path.is_empty() {
+        if let Some(contents) = frontend.read(path).await {
+            let content_type = mime_guess::from_path(path).first_or_octet_stream();
+            let cache = if is_hashed(path) { IMMUTABLE } else { REVALIDATE };
+            return file(contents, content_type.as_ref(), cache);
+        }
+        // A missing asset is an error, not a page of the app.
+        if Path::new(path).extension().is_some() {
+            return StatusCode::NOT_FOUND.into_response();
+        }
+    }
+    match frontend.read("index.html").await {
+        Some(index) => file(index, "text/html; charset=utf-8", REVALIDATE),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+fn file(contents: Vec<u8>, content_type: &str, cache: &'static str) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::CACHE_CONTROL, cache.to_string()),
+        ],
+        contents,
+    )
+        .into_response()
+}
+
+/// The request path relative to the build, or `None` if it would step
+/// outside it.
+fn relative_path(path: &str) -> Option<&str> {
+    let path = path.trim_start_matches('/');
+    Path::new(path)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)))
+        .then_some(path)
+}
+
+/// Whether Trunk put a content hash in the file name, as in
+/// `frontend-0123456789abcdef_bg.wasm`.
+fn is_hashed(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    let stem = name.split('.').next().unwrap_or(name);
+    stem.rsplit_once('-').is_some_and(|(_, hash)| {
+        let hash = hash.strip_suffix("_bg").unwrap_or(hash);
+        hash.len() >= 16 && hash.chars().all(|c| c.is_ascii_hexdigit())
+    })
+}
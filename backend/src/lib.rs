@@ -1,100 +1,465 @@
+use std::path::PathBuf;
+
 use axum::{
-    extract::State,
-    http::StatusCode,
+    extract::{DefaultBodyLimit, FromRef, Query, State},
+    http::{header, HeaderMap, StatusCode},
     response::IntoResponse,
-    routing::{get, patch, post},
+    routing::{delete, get, on, patch, post, put, MethodFilter},
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use sqlx::SqlitePool;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
+use audit::{Action, Actor, Entity};
+use tags::{TagFilter, TagSummary};
+
+pub mod admin;
+pub mod config;
+pub mod frontend;
+pub mod middleware;
+pub mod openapi;
+
+mod army_lists;
+mod audit;
+mod backups;
+mod db;
+mod deprecation;
+mod events;
+mod locations;
+mod photos;
+mod presence;
+mod projects;
+mod repository;
+mod sessions;
+mod stats;
+mod sync;
+mod tags;
+mod trash;
+mod users;
+
+pub use backups::run_backup_job;
+pub use config::{BackupSettings, Config, Features};
+pub use db::{connect, connect_pools, migrate, Pools};
+pub use deprecation::{DEPRECATED_SINCE, SUNSET};
+pub use events::EventBus;
+pub use presence::PresenceHub;
+#[cfg(feature = "postgres")]
+pub use repository::{connect_postgres, migrate_postgres, PostgresRepository};
+pub use repository::{InMemoryRepository, Repository, SharedRepository, SqliteRepository};
+pub use trash::{purge_trash, run_purge_job, DEFAULT_RETENTION_DAYS};
+
 #[derive(Clone)]
 pub struct AppState {
+    /// The single writer connection; anything that changes the database goes
+    /// through it. Every handler outside the catalog uses it directly, so the
+    /// full app always needs SQLite whatever `repository` is.
     pub pool: SqlitePool,
+    /// Read-only connections for handlers that only query.
+    pub read_pool: SqlitePool,
+    /// Games, model definitions and lots; on the same database as `pool`.
+    pub repository: SharedRepository,
+    /// Directory where uploaded photos and their thumbnails are stored.
+    pub photo_dir: PathBuf,
+    /// Change events streamed to `/events` subscribers.
+    pub events: EventBus,
+    /// Who is connected to `/ws` and which rows they have open.
+    pub presence: PresenceHub,
+    /// Where `/admin/backups` writes backups and how many it keeps.
+    pub backups: BackupSettings,
 }
 
+/// Where version 1 of the API is served. A later version gets a prefix of
+/// its own and is nested next to it in [`versioned`].
+pub const API_V1: &str = "/api/v1";
+
 pub fn app(state: AppState) -> Router {
+    app_with_features(state, &Features::default())
+}
+
+/// The API without the routes of switched-off features, behind the default
+/// [`middleware::stack`].
+pub fn app_with_features(state: AppState, features: &Features) -> Router {
+    middleware::stack(api(state, features), &Config::default())
+}
+
+/// Everything the server answers with these settings: the API, the frontend
+/// if there is one, and the middleware stack around both.
+pub fn app_with_config(state: AppState, config: &Config) -> Router {
+    with_frontend_and_middleware(api(state, &config.features), config)
+}
+
+fn api(state: AppState, features: &Features) -> Router {
+    let router = versioned(v1_routes(features).with_state(state));
+    if features.docs {
+        return router.merge(openapi::routes(features));
+    }
+    router
+}
+
+fn with_frontend_and_middleware(router: Router, config: &Config) -> Router {
+    let router = match frontend::Frontend::from_config(config) {
+        Some(frontend) => {
+            tracing::info!(?frontend, "serving the frontend");
+            frontend::serve(router, frontend)
+        }
+        None => router,
+    };
+    middleware::stack(router, config)
+}
+
+/// `/health` at the root, version 1 under [`API_V1`], and version 1 again at
+/// the old unversioned paths, with deprecation headers until they go away.
+fn versioned(v1: Router) -> Router {
     Router::new()
         .route("/health", get(health))
+        .nest(API_V1, v1.clone())
+        .merge(deprecation::unversioned(v1, API_V1))
+}
+
+/// Every version 1 route, relative to [`API_V1`].
+fn v1_routes(features: &Features) -> Router<AppState> {
+    let mut router = catalog_routes()
+        .route("/audit", get(audit::list_audit_entries))
+        .route(
+            "/admin/backups",
+            post(backups::create_backup).get(backups::list_backups),
+        )
+        .route("/stats", get(stats::collection_stats))
+        .route("/users", post(users::create_user).get(users::list_users))
+        .route("/games/:id", delete(trash::delete_game))
+        .route(
+            "/model-definitions/:id",
+            delete(trash::delete_model_definition),
+        )
+        .route(
+            "/model-definitions/:id/tags",
+            put(tags::set_model_definition_tags),
+        )
+        .route("/user-models/move", post(locations::move_user_models))
+        .route("/user-models/:id", delete(trash::delete_user_model))
+        .route("/user-models/:id/tags", put(tags::set_user_model_tags))
+        .route(
+            "/user-models/:id/photos",
+            post(photos::upload_photo)
+                .get(photos::list_photos)
+                .layer(DefaultBodyLimit::max(photos::MAX_UPLOAD_BYTES)),
+        )
+        .route(
+            "/photos/:id",
+            get(photos::get_photo).delete(photos::delete_photo),
+        )
+        .route("/photos/:id/thumbnail", get(photos::get_thumbnail))
+        .route(
+            "/locations",
+            post(locations::create_location).get(locations::list_locations),
+        )
+        .route("/locations/tree", get(locations::location_tree))
+        .route("/tags", post(tags::create_tag).get(tags::list_tags))
+        .route(
+            "/tags/:id",
+            patch(tags::update_tag).delete(tags::delete_tag),
+        )
+        .route(
+            "/army-lists",
+            post(army_lists::create_army_list).get(army_lists::list_army_lists),
+        )
+        .route(
+            "/army-lists/:id",
+            get(army_lists::get_army_list).delete(army_lists::delete_army_list),
+        )
+        .route(
+            "/army-lists/:id/entries",
+            post(army_lists::create_army_list_entry),
+        )
+        .route(
+            "/army-lists/:id/entries/:entry_id",
+            delete(army_lists::delete_army_list_entry),
+        )
+        .route(
+            "/army-lists/:id/validation",
+            get(army_lists::validate_army_list),
+        )
+        .route(
+            "/projects",
+            post(projects::create_project).get(projects::list_projects),
+        )
+        .route("/projects/:id", delete(projects::delete_project))
+        .route(
+            "/projects/:id/user-models",
+            put(projects::set_project_user_models),
+        )
+        .route("/projects/:id/progress", get(projects::project_progress))
+        .route("/sessions", get(sessions::list_sessions))
+        .route("/sessions/start", post(sessions::start_session))
+        .route("/sessions/active", get(sessions::active_session))
+        .route("/sessions/stats", get(sessions::session_stats))
+        .route("/sessions/:id/stop", post(sessions::stop_session))
+        .route("/trash", get(trash::list_trash))
+        .route("/trash/:id/restore", post(trash::restore));
+
+    if features.events {
+        router = router.route("/events", get(events::stream_events));
+    }
+    if features.presence {
+        router = router.route("/ws", get(presence::connect));
+    }
+    if features.sync {
+        router = router
+            .route("/sync/changes", get(sync::list_changes))
+            .route("/sync/push", post(sync::push_changes));
+    }
+    router
+}
+
+/// The game, model definition and lot routes, which only need a
+/// [`Repository`] and the event bus.
+fn catalog_routes<S>() -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+    SharedRepository: FromRef<S>,
+    EventBus: FromRef<S>,
+{
+    Router::new()
         .route("/games", post(create_game).get(list_games))
         .route(
             "/model-definitions",
             post(create_model_definition).get(list_model_definitions),
         )
-        .route("/user-models", post(create_user_model).get(list_user_models))
-        .route("/user-models/:id", patch(update_user_model))
-        .with_state(state)
+        .route("/model-definitions/:id", patch(update_model_definition))
+        .route(
+            "/user-models",
+            post(create_user_model).get(list_user_models),
+        )
+        .route(
+            "/user-models/:id",
+            get(get_user_model).patch(update_user_model),
+        )
+}
+
+/// What [`catalog_app`] runs on: a repository without the rest of the SQLite
+/// database behind it.
+#[derive(Clone)]
+pub struct CatalogState {
+    pub repository: SharedRepository,
+    pub events: EventBus,
+}
+
+/// Only `/health` and the game, model definition and lot routes, plus
+/// `/events` if it is switched on. This is the API served from PostgreSQL,
+/// which stores nothing else yet; every other documented route answers 501.
+pub fn catalog_app(state: CatalogState, features: &Features) -> Router {
+    middleware::stack(catalog_api(state, features), &Config::default())
+}
+
+/// [`catalog_app`] as [`app_with_config`] serves it.
+pub fn catalog_app_with_config(state: CatalogState, config: &Config) -> Router {
+    with_frontend_and_middleware(catalog_api(state, &config.features), config)
+}
+
+fn catalog_api(state: CatalogState, features: &Features) -> Router {
+    let mut router = catalog_routes();
+    if features.events {
+        router = router.route("/events", get(events::stream_events));
+    }
+    versioned(not_implemented_routes(router, features).with_state(state))
+}
+
+/// The operations [`catalog_api`] serves, by their `operationId` in
+/// [`openapi::spec`].
+const CATALOG_OPERATIONS: [&str; 11] = [
+    "health",
+    "create_game",
+    "list_games",
+    "create_model_definition",
+    "list_model_definitions",
+    "update_model_definition",
+    "create_user_model",
+    "list_user_models",
+    "get_user_model",
+    "update_user_model",
+    "stream_events",
+];
+
+/// `router` with every other documented operation answering 501, so clients
+/// can tell a route this server lacks from a wrong path.
+fn not_implemented_routes<S>(mut router: Router<S>, features: &Features) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    for (path, item) in openapi::spec(features).paths.paths {
+        let path = path
+            .split('/')
+            .map(|segment| match segment.strip_prefix('{') {
+                Some(name) => format!(":{}", name.trim_end_matches('}')),
+                None => segment.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("/");
+        let operations = [
+            (MethodFilter::GET, item.get),
+            (MethodFilter::POST, item.post),
+            (MethodFilter::PUT, item.put),
+            (MethodFilter::PATCH, item.patch),
+            (MethodFilter::DELETE, item.delete),
+        ];
+        for (method, operation) in operations {
+            let Some(id) = operation.and_then(|operation| operation.operation_id) else {
+                continue;
+            };
+            if !CATALOG_OPERATIONS.contains(&id.as_str()) {
+                router = router.route(&path, on(method, not_implemented));
+            }
+        }
+    }
+    router
+}
+
+async fn not_implemented() -> AppError {
+    AppError::not_implemented("not available with PostgreSQL yet")
+}
+
+impl FromRef<AppState> for SharedRepository {
+    fn from_ref(state: &AppState) -> Self {
+        state.repository.clone()
+    }
+}
+
+impl FromRef<AppState> for EventBus {
+    fn from_ref(state: &AppState) -> Self {
+        state.events.clone()
+    }
 }
 
+impl FromRef<CatalogState> for SharedRepository {
+    fn from_ref(state: &CatalogState) -> Self {
+        state.repository.clone()
+    }
+}
+
+impl FromRef<CatalogState> for EventBus {
+    fn from_ref(state: &CatalogState) -> Self {
+        state.events.clone()
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "health",
+    responses((status = 200, description = "The server is up", body = Object, example = json!({ "status": "ok" })))
+)]
 async fn health() -> Json<serde_json::Value> {
     Json(json!({ "status": "ok" }))
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 struct CreateGame {
     name: String,
 }
 
-#[derive(Serialize)]
-struct Game {
+#[derive(Serialize, ToSchema)]
+pub struct Game {
     id: Uuid,
     name: String,
     created_at: String,
 }
 
-#[derive(Deserialize)]
-struct CreateModelDefinition {
+#[derive(Deserialize, ToSchema)]
+pub struct CreateModelDefinition {
     name: String,
     game_id: Uuid,
+    points: Option<i64>,
+    unit_size: Option<i64>,
 }
 
-#[derive(Serialize)]
-struct ModelDefinition {
+#[derive(Serialize, ToSchema)]
+pub struct ModelDefinition {
     id: Uuid,
     name: String,
     game: GameSummary,
+    /// Points cost of one unit of `unit_size` models.
+    points: Option<i64>,
+    unit_size: Option<i64>,
+    notes: Option<String>,
+    tags: Vec<TagSummary>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct UpdateModelDefinition {
+    /// Replaces the notes; an empty string clears them.
+    notes: Option<String>,
+    points: Option<i64>,
+    unit_size: Option<i64>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 struct GameSummary {
     id: Uuid,
     name: String,
 }
 
-#[derive(Deserialize)]
-struct CreateUserModel {
+#[derive(Deserialize, ToSchema)]
+pub struct CreateUserModel {
     model_definition_id: Uuid,
     quantity: i32,
     status: Status,
+    location_id: Option<Uuid>,
 }
 
-#[derive(Deserialize)]
-struct UpdateUserModel {
-    status: Status,
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct UserModelFilter {
+    /// Restricts the list to lots stored in this location or any location nested below it.
+    location_id: Option<Uuid>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct UpdateUserModel {
+    status: Option<Status>,
+    quantity: Option<i32>,
+    /// Replaces the notes; an empty string clears them.
+    notes: Option<String>,
 }
 
-#[derive(Serialize)]
-struct UserModel {
+#[derive(Clone, Serialize, ToSchema)]
+pub struct UserModel {
     id: Uuid,
     model_definition_id: Uuid,
     quantity: i32,
     status: Status,
+    location_id: Option<Uuid>,
+    notes: Option<String>,
+    /// Bumped on every write; also sent as the `ETag`.
+    version: i64,
     created_at: String,
     updated_at: String,
 }
 
-#[derive(Serialize)]
-struct UserModelListItem {
+impl IntoResponse for UserModel {
+    fn into_response(self) -> axum::response::Response {
+        let etag = format!("\"{}\"", self.version);
+        ([(header::ETAG, etag)], Json(self)).into_response()
+    }
+}
+
+#[derive(Serialize, Clone, ToSchema)]
+pub struct UserModelListItem {
     id: Uuid,
     model_name: String,
     game_name: String,
     quantity: i32,
     status: Status,
+    location_id: Option<Uuid>,
+    notes: Option<String>,
+    tags: Vec<TagSummary>,
+    version: i64,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+/// Variants are ordered by painting progress, so `>=` means "at least this far along".
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ToSchema)]
 #[serde(rename_all = "snake_case")]
 enum Status {
     Unassembled,
@@ -125,83 +490,61 @@ impl std::str::FromStr for Status {
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/games",
+    tag = "games",
+    request_body = CreateGame,
+    responses(
+        (status = 201, body = Game),
+        (status = 409, description = "A game with this name exists", body = ErrorBody),
+    )
+)]
 async fn create_game(
-    State(state): State<AppState>,
+    State(repository): State<SharedRepository>,
+    State(events): State<EventBus>,
+    actor: Actor,
     Json(payload): Json<CreateGame>,
 ) -> Result<(StatusCode, Json<Game>), AppError> {
     tracing::info!(name = %payload.name, "creating game");
-    let id = Uuid::new_v4();
-    let id_value = id.to_string();
-
-    sqlx::query!(
-        r#"
-        INSERT INTO games (id, name, created_at)
-        VALUES ($1, $2, datetime('now'))
-        "#,
-        id_value,
-        payload.name
-    )
-    .execute(&state.pool)
-    .await
-    .map_err(map_db_error)?;
-
-    let record = sqlx::query!(
-        r#"
-        SELECT
-            id as "id!: String",
-            name,
-            created_at as "created_at!: String"
-        FROM games
-        WHERE id = $1
-        "#,
-        id_value
-    )
-    .fetch_one(&state.pool)
-    .await
-    .map_err(|error| AppError::internal("failed to load game", error))?;
-
-    let game = Game {
-        id: parse_uuid(record.id)?,
-        name: record.name,
-        created_at: record.created_at,
-    };
+    let game = repository.create_game(&actor, &payload.name).await?;
 
+    events.changed(Entity::Game, &game.id.to_string(), Action::Create);
     Ok((StatusCode::CREATED, Json(game)))
 }
 
-async fn list_games(State(state): State<AppState>) -> Result<Json<Vec<Game>>, AppError> {
+/// Games not in the trash, oldest first.
+#[utoipa::path(
+    get,
+    path = "/games",
+    tag = "games",
+    responses((status = 200, body = Vec<Game>))
+)]
+async fn list_games(
+    State(repository): State<SharedRepository>,
+) -> Result<Json<Vec<Game>>, AppError> {
     tracing::info!("listing games");
-    let records = sqlx::query!(
-        r#"
-        SELECT
-            id as "id!: String",
-            name,
-            created_at as "created_at!: String"
-        FROM games
-        ORDER BY created_at
-        "#
-    )
-    .fetch_all(&state.pool)
-    .await
-    .map_err(|error| AppError::internal("failed to fetch games", error))?;
-
-    let games = records
-        .into_iter()
-        .map(|record| {
-            Ok(Game {
-                id: parse_uuid(record.id)?,
-                name: record.name,
-                created_at: record.created_at,
-            })
-        })
-        .collect::<Result<Vec<_>, AppError>>()?;
+    let games = repository.list_games().await?;
 
     tracing::info!(count = games.len(), "listed games");
     Ok(Json(games))
 }
 
+#[utoipa::path(
+    post,
+    path = "/model-definitions",
+    tag = "model definitions",
+    request_body = CreateModelDefinition,
+    responses(
+        (status = 201, body = ModelDefinition),
+        (status = 404, description = "The game does not exist", body = ErrorBody),
+        (status = 422, description = "Negative points or a unit size below 1", body = ErrorBody),
+    )
+)]
 async fn create_model_definition(
-    State(state): State<AppState>,
+    State(repository): State<SharedRepository>,
+    State(events): State<EventBus>,
+    actor: Actor,
     Json(payload): Json<CreateModelDefinition>,
 ) -> Result<(StatusCode, Json<ModelDefinition>), AppError> {
     tracing::info!(
@@ -209,289 +552,231 @@ async fn create_model_definition(
         game_id = %payload.game_id,
         "creating model definition"
     );
-    let game_id_value = payload.game_id.to_string();
-    let game = sqlx::query!(
-        r#"
-        SELECT
-            id as "id!: String",
-            name
-        FROM games
-        WHERE id = $1
-        "#,
-        game_id_value
-    )
-    .fetch_optional(&state.pool)
-    .await
-    .map_err(|error| AppError::internal("failed to validate game", error))?;
-
-    let game = match game {
-        Some(game) => game,
-        None => return Err(AppError::not_found("game not found")),
-    };
-
-    let id = Uuid::new_v4();
-    let id_value = id.to_string();
-    let game_id_value = payload.game_id.to_string();
-    sqlx::query!(
-        r#"
-        INSERT INTO model_definitions (id, name, game_id, created_at)
-        VALUES ($1, $2, $3, datetime('now'))
-        "#,
-        id_value,
-        payload.name,
-        game_id_value
-    )
-    .execute(&state.pool)
-    .await
-    .map_err(|error| AppError::internal("failed to create model definition", error))?;
-
-    let record = sqlx::query!(
-        r#"
-        SELECT
-            id as "id!: String",
-            name,
-            game_id as "game_id!: String"
-        FROM model_definitions
-        WHERE id = $1
-        "#,
-        id_value
-    )
-    .fetch_one(&state.pool)
-    .await
-    .map_err(|error| AppError::internal("failed to load model definition", error))?;
-
-    let model_definition = ModelDefinition {
-        id: parse_uuid(record.id)?,
-        name: record.name,
-        game: GameSummary {
-            id: parse_uuid(game.id)?,
-            name: game.name,
-        },
-    };
-
+    validate_points(payload.points, payload.unit_size)?;
+    let model_definition = repository
+        .create_model_definition(&actor, &payload)
+        .await?;
+
+    events.changed(
+        Entity::ModelDefinition,
+        &model_definition.id.to_string(),
+        Action::Create,
+    );
     Ok((StatusCode::CREATED, Json(model_definition)))
 }
 
+#[utoipa::path(
+    get,
+    path = "/model-definitions",
+    tag = "model definitions",
+    params(TagFilter),
+    responses(
+        (status = 200, body = Vec<ModelDefinition>),
+        (status = 400, description = "A tag ID is not a UUID", body = ErrorBody),
+    )
+)]
 async fn list_model_definitions(
-    State(state): State<AppState>,
+    State(repository): State<SharedRepository>,
+    Query(tag_filter): Query<TagFilter>,
 ) -> Result<Json<Vec<ModelDefinition>>, AppError> {
     tracing::info!("listing model definitions");
-    let records = sqlx::query!(
-        r#"
-        SELECT
-            model_definitions.id as "id!: String",
-            model_definitions.name,
-            games.id AS "game_id!: String",
-            games.name AS game_name
-        FROM model_definitions
-        INNER JOIN games ON games.id = model_definitions.game_id
-        ORDER BY model_definitions.created_at
-        "#
-    )
-    .fetch_all(&state.pool)
-    .await
-    .map_err(|error| AppError::internal("failed to fetch model definitions", error))?;
-
-    let models = records
-        .into_iter()
-        .map(|record| {
-            Ok(ModelDefinition {
-                id: parse_uuid(record.id)?,
-                name: record.name,
-                game: GameSummary {
-                    id: parse_uuid(record.game_id)?,
-                    name: record.game_name,
-                },
-            })
-        })
-        .collect::<Result<Vec<_>, AppError>>()?;
+    let tag_matcher = tag_filter.matcher()?;
+    let mut models = repository.list_model_definitions().await?;
+    models.retain(|model| tag_matcher.matches(&model.tags));
 
     tracing::info!(count = models.len(), "listed model definitions");
     Ok(Json(models))
 }
 
+#[utoipa::path(
+    patch,
+    path = "/model-definitions/{id}",
+    tag = "model definitions",
+    params(("id" = Uuid, Path)),
+    request_body = UpdateModelDefinition,
+    responses(
+        (status = 200, body = ModelDefinition),
+        (status = 404, body = ErrorBody),
+        (status = 422, description = "Negative points or a unit size below 1", body = ErrorBody),
+    )
+)]
+async fn update_model_definition(
+    State(repository): State<SharedRepository>,
+    State(events): State<EventBus>,
+    actor: Actor,
+    axum::extract::Path(id): axum::extract::Path<Uuid>,
+    Json(payload): Json<UpdateModelDefinition>,
+) -> Result<Json<ModelDefinition>, AppError> {
+    tracing::info!(model_definition_id = %id, "updating model definition");
+    validate_points(payload.points, payload.unit_size)?;
+    let model_definition = repository
+        .update_model_definition(&actor, id, &payload)
+        .await?;
+
+    events.changed(Entity::ModelDefinition, &id.to_string(), Action::Update);
+    Ok(Json(model_definition))
+}
+
+#[utoipa::path(
+    post,
+    path = "/user-models",
+    tag = "user models",
+    request_body = CreateUserModel,
+    responses(
+        (status = 201, body = UserModel, headers(("ETag" = String, description = "The lot's version"))),
+        (status = 404, description = "The model definition or location does not exist", body = ErrorBody),
+    )
+)]
 async fn create_user_model(
-    State(state): State<AppState>,
+    State(repository): State<SharedRepository>,
+    State(events): State<EventBus>,
+    actor: Actor,
     Json(payload): Json<CreateUserModel>,
-) -> Result<(StatusCode, Json<UserModel>), AppError> {
+) -> Result<(StatusCode, UserModel), AppError> {
     tracing::info!(
         model_definition_id = %payload.model_definition_id,
         quantity = payload.quantity,
         status = payload.status.as_str(),
         "creating user model"
     );
-    let model_definition_id_value = payload.model_definition_id.to_string();
-    let exists = sqlx::query!(
-        r#"
-        SELECT id
-        FROM model_definitions
-        WHERE id = $1
-        "#,
-        model_definition_id_value
-    )
-    .fetch_optional(&state.pool)
-    .await
-    .map_err(|error| AppError::internal("failed to validate model definition", error))?;
+    let user_model = repository.create_user_model(&actor, &payload).await?;
 
-    if exists.is_none() {
-        return Err(AppError::not_found("model definition not found"));
-    }
+    events::publish_user_model(&*repository, &events, &user_model.id.to_string()).await;
+    Ok((StatusCode::CREATED, user_model))
+}
 
-    let id = Uuid::new_v4();
-    let id_value = id.to_string();
-    let status_value = payload.status.as_str();
-    sqlx::query!(
-        r#"
-        INSERT INTO user_models (id, model_definition_id, quantity, status, created_at, updated_at)
-        VALUES ($1, $2, $3, $4, datetime('now'), datetime('now'))
-        "#,
-        id_value,
-        model_definition_id_value,
-        payload.quantity,
-        status_value
+#[utoipa::path(
+    get,
+    path = "/user-models/{id}",
+    tag = "user models",
+    params(("id" = Uuid, Path)),
+    responses(
+        (status = 200, body = UserModel, headers(("ETag" = String, description = "The lot's version"))),
+        (status = 404, body = ErrorBody),
     )
-    .execute(&state.pool)
-    .await
-    .map_err(|error| AppError::internal("failed to create user model", error))?;
-
-    let record = sqlx::query!(
-        r#"
-        SELECT
-            id as "id!: String",
-            model_definition_id as "model_definition_id!: String",
-            quantity as "quantity!: i64",
-            status,
-            created_at as "created_at!: String",
-            updated_at as "updated_at!: String"
-        FROM user_models
-        WHERE id = $1
-        "#,
-        id_value
-    )
-    .fetch_one(&state.pool)
-    .await
-    .map_err(|error| AppError::internal("failed to load user model", error))?;
-
-    let status = parse_status(&record.status)?;
-    let user_model = UserModel {
-        id: parse_uuid(record.id)?,
-        model_definition_id: parse_uuid(record.model_definition_id)?,
-        quantity: parse_i32(record.quantity)?,
-        status,
-        created_at: record.created_at,
-        updated_at: record.updated_at,
-    };
-
-    Ok((StatusCode::CREATED, Json(user_model)))
+)]
+async fn get_user_model(
+    State(repository): State<SharedRepository>,
+    axum::extract::Path(id): axum::extract::Path<Uuid>,
+) -> Result<UserModel, AppError> {
+    tracing::info!(user_model_id = %id, "loading user model");
+    repository
+        .get_user_model(id)
+        .await?
+        .ok_or(AppError::not_found("user model not found"))
 }
 
+#[utoipa::path(
+    get,
+    path = "/user-models",
+    tag = "user models",
+    params(UserModelFilter, TagFilter),
+    responses(
+        (status = 200, body = Vec<UserModelListItem>),
+        (status = 400, description = "A tag ID is not a UUID", body = ErrorBody),
+    )
+)]
 async fn list_user_models(
-    State(state): State<AppState>,
+    State(repository): State<SharedRepository>,
+    Query(filter): Query<UserModelFilter>,
+    Query(tag_filter): Query<TagFilter>,
 ) -> Result<Json<Vec<UserModelListItem>>, AppError> {
-    tracing::info!("listing user models");
-    let records = sqlx::query!(
-        r#"
-        SELECT
-            user_models.id as "id!: String",
-            model_definitions.name AS model_name,
-            games.name AS game_name,
-            user_models.quantity as "quantity!: i64",
-            user_models.status
-        FROM user_models
-        INNER JOIN model_definitions ON model_definitions.id = user_models.model_definition_id
-        INNER JOIN games ON games.id = model_definitions.game_id
-        ORDER BY user_models.created_at
-        "#
-    )
-    .fetch_all(&state.pool)
-    .await
-    .map_err(|error| AppError::internal("failed to fetch user models", error))?;
-
-    let models = records
-        .into_iter()
-        .map(|record| {
-            let status = parse_status(&record.status)?;
-            Ok(UserModelListItem {
-                id: parse_uuid(record.id)?,
-                model_name: record.model_name,
-                game_name: record.game_name,
-                quantity: parse_i32(record.quantity)?,
-                status,
-            })
-        })
-        .collect::<Result<Vec<_>, AppError>>()?;
+    tracing::info!(location_id = ?filter.location_id, "listing user models");
+    let tag_matcher = tag_filter.matcher()?;
+    let mut models = repository.list_user_models(filter.location_id, None).await?;
+    models.retain(|model| tag_matcher.matches(&model.tags));
 
     tracing::info!(count = models.len(), "listed user models");
     Ok(Json(models))
 }
 
+/// With an `If-Match` header, the update only goes through if the lot is still
+/// at one of the listed versions; otherwise it fails with 412.
+#[utoipa::path(
+    patch,
+    path = "/user-models/{id}",
+    tag = "user models",
+    params(
+        ("id" = Uuid, Path),
+        ("If-Match" = Option<String>, Header, description = "ETags the update may apply to, or `*`"),
+    ),
+    request_body = UpdateUserModel,
+    responses(
+        (status = 200, body = UserModel, headers(("ETag" = String, description = "The lot's new version"))),
+        (status = 404, body = ErrorBody),
+        (status = 412, description = "The lot is no longer at a listed version", body = ErrorBody),
+        (status = 422, description = "Quantity below 1", body = ErrorBody),
+    )
+)]
 async fn update_user_model(
-    State(state): State<AppState>,
+    State(repository): State<SharedRepository>,
+    State(events): State<EventBus>,
+    actor: Actor,
     axum::extract::Path(id): axum::extract::Path<Uuid>,
+    headers: HeaderMap,
     Json(payload): Json<UpdateUserModel>,
-) -> Result<Json<UserModel>, AppError> {
+) -> Result<UserModel, AppError> {
     tracing::info!(
         user_model_id = %id,
-        status = payload.status.as_str(),
+        status = payload.status.map(Status::as_str),
+        quantity = payload.quantity,
         "updating user model"
     );
-    let status_value = payload.status.as_str();
-    let id_value = id.to_string();
-    sqlx::query!(
-        r#"
-        UPDATE user_models
-        SET status = $1,
-            updated_at = datetime('now')
-        WHERE id = $2
-        "#,
-        status_value,
-        id_value
-    )
-    .execute(&state.pool)
-    .await
-    .map_err(|error| AppError::internal("failed to update user model", error))?;
-
-    let record = sqlx::query!(
-        r#"
-        SELECT
-            id as "id!: String",
-            model_definition_id as "model_definition_id!: String",
-            quantity as "quantity!: i64",
-            status,
-            created_at as "created_at!: String",
-            updated_at as "updated_at!: String"
-        FROM user_models
-        WHERE id = $1
-        "#,
-        id_value
-    )
-    .fetch_optional(&state.pool)
-    .await
-    .map_err(|error| AppError::internal("failed to load user model", error))?;
+    if payload.quantity.is_some_and(|quantity| quantity <= 0) {
+        return Err(AppError::unprocessable("quantity must be greater than 0"));
+    }
+    let expected_versions = if_match_versions(&headers)?;
+    let user_model = repository
+        .update_user_model(&actor, id, &payload, expected_versions)
+        .await?;
 
-    let record = match record {
-        Some(record) => record,
-        None => return Err(AppError::not_found("user model not found")),
-    };
+    events::publish_user_model(&*repository, &events, &id.to_string()).await;
+    Ok(user_model)
+}
 
-    let status = parse_status(&record.status)?;
-    let user_model = UserModel {
-        id: parse_uuid(record.id)?,
-        model_definition_id: parse_uuid(record.model_definition_id)?,
-        quantity: parse_i32(record.quantity)?,
-        status,
-        created_at: record.created_at,
-        updated_at: record.updated_at,
+/// The versions an `If-Match` header accepts, or `None` when any will do
+/// (no header, or `*`). Weak tags never match, as If-Match compares strongly.
+fn if_match_versions(headers: &HeaderMap) -> Result<Option<Vec<i64>>, AppError> {
+    let Some(value) = headers.get(header::IF_MATCH) else {
+        return Ok(None);
     };
+    let value = value
+        .to_str()
+        .map_err(|_| AppError::bad_request("invalid If-Match header"))?;
+    if value.trim() == "*" {
+        return Ok(None);
+    }
 
-    Ok(Json(user_model))
+    Ok(Some(
+        value
+            .split(',')
+            .filter_map(|tag| {
+                tag.trim()
+                    .strip_prefix('"')?
+                    .strip_suffix('"')?
+                    .parse()
+                    .ok()
+            })
+            .collect(),
+    ))
 }
 
-struct AppError {
+/// Error returned by handlers and background jobs; renders as
+/// `{"error": message, "request_id": id}`.
+#[derive(Debug)]
+pub struct AppError {
     status: StatusCode,
     message: &'static str,
 }
 
+impl std::fmt::Display for AppError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        formatter.write_str(self.message)
+    }
+}
+
+impl std::error::Error for AppError {}
+
 impl AppError {
     fn conflict(message: &'static str) -> Self {
         Self {
@@ -500,7 +785,7 @@ impl AppError {
         }
     }
 
-    fn internal(message: &'static str, error: sqlx::Error) -> Self {
+    fn internal(message: &'static str, error: impl std::fmt::Display) -> Self {
         tracing::error!(%error, "{message}");
         Self {
             status: StatusCode::INTERNAL_SERVER_ERROR,
@@ -522,11 +807,72 @@ impl AppError {
             message,
         }
     }
+
+    fn bad_request(message: &'static str) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            message,
+        }
+    }
+
+    fn precondition_failed(message: &'static str) -> Self {
+        Self {
+            status: StatusCode::PRECONDITION_FAILED,
+            message,
+        }
+    }
+
+    fn payload_too_large(message: &'static str) -> Self {
+        Self {
+            status: StatusCode::PAYLOAD_TOO_LARGE,
+            message,
+        }
+    }
+
+    fn unsupported_media_type(message: &'static str) -> Self {
+        Self {
+            status: StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            message,
+        }
+    }
+
+    fn unprocessable(message: &'static str) -> Self {
+        Self {
+            status: StatusCode::UNPROCESSABLE_ENTITY,
+            message,
+        }
+    }
+
+    fn timed_out(message: &'static str) -> Self {
+        Self {
+            status: StatusCode::SERVICE_UNAVAILABLE,
+            message,
+        }
+    }
+
+    fn not_implemented(message: &'static str) -> Self {
+        Self {
+            status: StatusCode::NOT_IMPLEMENTED,
+            message,
+        }
+    }
+}
+
+/// The body of every error response.
+#[derive(Serialize, ToSchema)]
+pub(crate) struct ErrorBody {
+    error: &'static str,
+    /// The `x-request-id` of the failed request, to find it in the logs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    request_id: Option<String>,
 }
 
 impl IntoResponse for AppError {
     fn into_response(self) -> axum::response::Response {
-        let body = Json(json!({ "error": self.message }));
+        let body = Json(ErrorBody {
+            error: self.message,
+            request_id: middleware::current_request_id(),
+        });
         (self.status, body).into_response()
     }
 }
@@ -542,6 +888,17 @@ fn map_db_error(error: sqlx::Error) -> AppError {
     AppError::internal("failed to create game", error)
 }
 
+fn validate_points(points: Option<i64>, unit_size: Option<i64>) -> Result<(), AppError> {
+    if points.is_some_and(|points| points < 0) {
+        return Err(AppError::unprocessable("points cannot be negative"));
+    }
+    if unit_size.is_some_and(|unit_size| unit_size <= 0) {
+        return Err(AppError::unprocessable("unit size must be greater than 0"));
+    }
+
+    Ok(())
+}
+
 fn parse_status(value: &str) -> Result<Status, AppError> {
     value
         .parse::<Status>()
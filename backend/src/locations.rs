@@ -0,0 +1,486 @@
+use std::collections::{HashMap, HashSet};
+
+use axum::{extract::State, http::StatusCode, Json};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    audit::{self, Action, Actor, Entity},
+    events, parse_uuid, AppError, AppState, ErrorBody,
+};
+
+#[derive(Deserialize, ToSchema)]
+pub(crate) struct CreateLocation {
+    name: String,
+    kind: LocationKind,
+    parent_id: Option<Uuid>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct Location {
+    id: Uuid,
+    name: String,
+    kind: LocationKind,
+    parent_id: Option<Uuid>,
+    created_at: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct LocationTree {
+    nodes: Vec<LocationNode>,
+    unassigned_quantity: i64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct LocationNode {
+    id: Uuid,
+    name: String,
+    kind: LocationKind,
+    /// Total quantity stored in this location and all of its descendants.
+    quantity: i64,
+    #[schema(no_recursion)]
+    children: Vec<LocationNode>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub(crate) struct MoveUserModels {
+    user_model_ids: Vec<Uuid>,
+    location_id: Option<Uuid>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct MovedUserModels {
+    moved: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum LocationKind {
+    Room,
+    Shelf,
+    Box,
+}
+
+impl LocationKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            LocationKind::Room => "room",
+            LocationKind::Shelf => "shelf",
+            LocationKind::Box => "box",
+        }
+    }
+
+    /// The kind a parent must have, or `None` for top-level locations.
+    fn parent_kind(self) -> Option<LocationKind> {
+        match self {
+            LocationKind::Room => None,
+            LocationKind::Shelf => Some(LocationKind::Room),
+            LocationKind::Box => Some(LocationKind::Shelf),
+        }
+    }
+}
+
+impl std::str::FromStr for LocationKind {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "room" => Ok(LocationKind::Room),
+            "shelf" => Ok(LocationKind::Shelf),
+            "box" => Ok(LocationKind::Box),
+            _ => Err(()),
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/locations",
+    tag = "locations",
+    request_body = CreateLocation,
+    responses(
+        (status = 201, body = Location),
+        (status = 404, description = "The parent does not exist", body = ErrorBody),
+        (status = 422, description = "The parent is missing or of the wrong kind", body = ErrorBody),
+    )
+)]
+pub(crate) async fn create_location(
+    State(state): State<AppState>,
+    actor: Actor,
+    Json(payload): Json<CreateLocation>,
+) -> Result<(StatusCode, Json<Location>), AppError> {
+    tracing::info!(
+        name = %payload.name,
+        kind = payload.kind.as_str(),
+        "creating location"
+    );
+    let parent_id_value = payload.parent_id.map(|id| id.to_string());
+    match (payload.kind.parent_kind(), parent_id_value.as_deref()) {
+        (None, None) => {}
+        (None, Some(_)) => {
+            return Err(AppError::unprocessable(
+                "rooms cannot have a parent location",
+            ));
+        }
+        (Some(_), None) => {
+            return Err(AppError::unprocessable("parent location is required"));
+        }
+        (Some(expected), Some(parent_id)) => {
+            let parent = sqlx::query!(
+                r#"
+                SELECT kind
+                FROM locations
+                WHERE id = $1
+                "#,
+                parent_id
+            )
+            .fetch_optional(&state.pool)
+            .await
+            .map_err(|error| AppError::internal("failed to validate parent location", error))?;
+
+            let parent = match parent {
+                Some(parent) => parent,
+                None => return Err(AppError::not_found("parent location not found")),
+            };
+            if parse_kind(&parent.kind)? != expected {
+                return Err(AppError::unprocessable(
+                    "parent location has the wrong kind",
+                ));
+            }
+        }
+    }
+
+    let id = Uuid::new_v4();
+    let id_value = id.to_string();
+    let kind_value = payload.kind.as_str();
+    let mut transaction = state
+        .pool
+        .begin()
+        .await
+        .map_err(|error| AppError::internal("failed to start transaction", error))?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO locations (id, name, kind, parent_id, created_at)
+        VALUES ($1, $2, $3, $4, datetime('now'))
+        "#,
+        id_value,
+        payload.name,
+        kind_value,
+        parent_id_value
+    )
+    .execute(&mut *transaction)
+    .await
+    .map_err(|error| AppError::internal("failed to create location", error))?;
+
+    audit::record_row(
+        &mut transaction,
+        &actor,
+        Entity::Location,
+        &id_value,
+        Action::Create,
+        None,
+    )
+    .await?;
+
+    transaction
+        .commit()
+        .await
+        .map_err(|error| AppError::internal("failed to commit transaction", error))?;
+    state
+        .events
+        .changed(Entity::Location, &id_value, Action::Create);
+
+    let record = sqlx::query!(
+        r#"
+        SELECT
+            id as "id!: String",
+            name,
+            kind,
+            parent_id,
+            created_at as "created_at!: String"
+        FROM locations
+        WHERE id = $1
+        "#,
+        id_value
+    )
+    .fetch_one(&state.pool)
+    .await
+    .map_err(|error| AppError::internal("failed to load location", error))?;
+
+    let location = Location {
+        id: parse_uuid(record.id)?,
+        name: record.name,
+        kind: parse_kind(&record.kind)?,
+        parent_id: record.parent_id.map(parse_uuid).transpose()?,
+        created_at: record.created_at,
+    };
+
+    Ok((StatusCode::CREATED, Json(location)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/locations",
+    tag = "locations",
+    responses((status = 200, body = Vec<Location>))
+)]
+pub(crate) async fn list_locations(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<Location>>, AppError> {
+    tracing::info!("listing locations");
+    let records = sqlx::query!(
+        r#"
+        SELECT
+            id as "id!: String",
+            name,
+            kind,
+            parent_id,
+            created_at as "created_at!: String"
+        FROM locations
+        ORDER BY created_at
+        "#
+    )
+    .fetch_all(&state.read_pool)
+    .await
+    .map_err(|error| AppError::internal("failed to fetch locations", error))?;
+
+    let locations = records
+        .into_iter()
+        .map(|record| {
+            Ok(Location {
+                id: parse_uuid(record.id)?,
+                name: record.name,
+                kind: parse_kind(&record.kind)?,
+                parent_id: record.parent_id.map(parse_uuid).transpose()?,
+                created_at: record.created_at,
+            })
+        })
+        .collect::<Result<Vec<_>, AppError>>()?;
+
+    tracing::info!(count = locations.len(), "listed locations");
+    Ok(Json(locations))
+}
+
+#[utoipa::path(
+    get,
+    path = "/locations/tree",
+    tag = "locations",
+    responses((status = 200, body = LocationTree))
+)]
+pub(crate) async fn location_tree(
+    State(state): State<AppState>,
+) -> Result<Json<LocationTree>, AppError> {
+    tracing::info!("building location tree");
+    let records = sqlx::query!(
+        r#"
+        SELECT
+            id as "id!: String",
+            name,
+            kind,
+            parent_id
+        FROM locations
+        ORDER BY created_at
+        "#
+    )
+    .fetch_all(&state.read_pool)
+    .await
+    .map_err(|error| AppError::internal("failed to fetch locations", error))?;
+
+    let totals = sqlx::query!(
+        r#"
+        SELECT
+            user_models.location_id as "location_id: String",
+            SUM(user_models.quantity) as "quantity!: i64"
+        FROM user_models
+        INNER JOIN model_definitions ON model_definitions.id = user_models.model_definition_id
+        INNER JOIN games ON games.id = model_definitions.game_id
+        WHERE user_models.deleted_at IS NULL
+          AND model_definitions.deleted_at IS NULL
+          AND games.deleted_at IS NULL
+        GROUP BY user_models.location_id
+        "#
+    )
+    .fetch_all(&state.read_pool)
+    .await
+    .map_err(|error| AppError::internal("failed to sum location quantities", error))?;
+
+    let mut unassigned_quantity = 0;
+    let mut quantities = HashMap::new();
+    for total in totals {
+        match total.location_id {
+            Some(location_id) => {
+                quantities.insert(location_id, total.quantity);
+            }
+            None => unassigned_quantity = total.quantity,
+        }
+    }
+
+    let mut children: HashMap<Option<String>, Vec<LocationRecord>> = HashMap::new();
+    for record in records {
+        children
+            .entry(record.parent_id)
+            .or_default()
+            .push(LocationRecord {
+                id: record.id,
+                name: record.name,
+                kind: record.kind,
+            });
+    }
+    let nodes = build_nodes(None, &mut children, &quantities)?;
+
+    Ok(Json(LocationTree {
+        nodes,
+        unassigned_quantity,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/user-models/move",
+    tag = "locations",
+    request_body = MoveUserModels,
+    responses(
+        (status = 200, body = MovedUserModels),
+        (status = 404, description = "The location or a lot does not exist", body = ErrorBody),
+    )
+)]
+pub(crate) async fn move_user_models(
+    State(state): State<AppState>,
+    actor: Actor,
+    Json(payload): Json<MoveUserModels>,
+) -> Result<Json<MovedUserModels>, AppError> {
+    tracing::info!(
+        count = payload.user_model_ids.len(),
+        location_id = ?payload.location_id,
+        "moving user models"
+    );
+    // A lot listed twice still moves, and counts, once.
+    let mut seen = HashSet::new();
+    let user_model_ids: Vec<Uuid> = payload
+        .user_model_ids
+        .into_iter()
+        .filter(|id| seen.insert(*id))
+        .collect();
+    let location_id_value = payload.location_id.map(|id| id.to_string());
+    if let Some(location_id) = location_id_value.as_deref() {
+        let exists = sqlx::query!(
+            r#"
+            SELECT id
+            FROM locations
+            WHERE id = $1
+            "#,
+            location_id
+        )
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|error| AppError::internal("failed to validate location", error))?;
+
+        if exists.is_none() {
+            return Err(AppError::not_found("location not found"));
+        }
+    }
+
+    let mut transaction = state
+        .pool
+        .begin()
+        .await
+        .map_err(|error| AppError::internal("failed to start transaction", error))?;
+
+    for id in &user_model_ids {
+        let id_value = id.to_string();
+        // Lots in the trash, or under a trashed model definition or game, stay put.
+        let live = sqlx::query!(
+            r#"
+            SELECT user_models.id
+            FROM user_models
+            INNER JOIN model_definitions ON model_definitions.id = user_models.model_definition_id
+            INNER JOIN games ON games.id = model_definitions.game_id
+            WHERE user_models.id = $1
+              AND user_models.deleted_at IS NULL
+              AND model_definitions.deleted_at IS NULL
+              AND games.deleted_at IS NULL
+            "#,
+            id_value
+        )
+        .fetch_optional(&mut *transaction)
+        .await
+        .map_err(|error| AppError::internal("failed to validate user model", error))?;
+        let before = audit::snapshot(&mut transaction, Entity::UserModel, &id_value).await?;
+        if live.is_none() || before.is_none() {
+            return Err(AppError::not_found("user model not found"));
+        }
+
+        sqlx::query!(
+            r#"
+            UPDATE user_models
+            SET location_id = $1,
+                updated_at = datetime('now'),
+                version = version + 1
+            WHERE id = $2
+            "#,
+            location_id_value,
+            id_value
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|error| AppError::internal("failed to move user model", error))?;
+
+        audit::record_row(
+            &mut transaction,
+            &actor,
+            Entity::UserModel,
+            &id_value,
+            Action::Update,
+            before,
+        )
+        .await?;
+    }
+
+    transaction
+        .commit()
+        .await
+        .map_err(|error| AppError::internal("failed to commit transaction", error))?;
+    for id in &user_model_ids {
+        events::publish_user_model(&*state.repository, &state.events, &id.to_string()).await;
+    }
+
+    Ok(Json(MovedUserModels {
+        moved: user_model_ids.len(),
+    }))
+}
+
+struct LocationRecord {
+    id: String,
+    name: String,
+    kind: String,
+}
+
+fn build_nodes(
+    parent_id: Option<String>,
+    children: &mut HashMap<Option<String>, Vec<LocationRecord>>,
+    quantities: &HashMap<String, i64>,
+) -> Result<Vec<LocationNode>, AppError> {
+    let records = children.remove(&parent_id).unwrap_or_default();
+    records
+        .into_iter()
+        .map(|record| {
+            let nested = build_nodes(Some(record.id.clone()), children, quantities)?;
+            let own = quantities.get(&record.id).copied().unwrap_or(0);
+            Ok(LocationNode {
+                quantity: own + nested.iter().map(|node| node.quantity).sum::<i64>(),
+                id: parse_uuid(record.id)?,
+                name: record.name,
+                kind: parse_kind(&record.kind)?,
+                children: nested,
+            })
+        })
+        .collect()
+}
+
+fn parse_kind(value: &str) -> Result<LocationKind, AppError> {
+    value
+        .parse::<LocationKind>()
+        .map_err(|_| AppError::internal_message("invalid location kind stored in database"))
+}
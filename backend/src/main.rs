@@ -1,61 +1,285 @@
-use sqlx::sqlite::SqlitePoolOptions;
+use std::{
+    path::{Path, PathBuf},
+    process::ExitCode,
+    sync::Arc,
+};
+
+use axum::Router;
+use backend::{
+    admin,
+    config::{Config, ConfigArgs, LogFormat},
+    AppState, SqliteRepository,
+};
+use clap::{Parser, Subcommand};
 use tracing_subscriber::EnvFilter;
 
+#[derive(Parser)]
+#[command(version, about = "Mini Tracker API server and admin tools")]
+struct Cli {
+    #[command(flatten)]
+    config: ConfigArgs,
+    /// Print the resolved configuration as TOML and exit
+    #[arg(long)]
+    print_config: bool,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run the HTTP server (the default)
+    Serve,
+    /// Create the database if needed and apply pending migrations
+    Migrate,
+    /// Fill an empty database with a sample collection
+    Seed,
+    /// Load games, model definitions and lots from an export file
+    Import { file: PathBuf },
+    /// Write every game, model definition and lot to a JSON file
+    Export { file: PathBuf },
+    /// Back up the database into the backup directory, then rotate old backups
+    Backup,
+    /// Replace the database with a backup; stop the server first
+    Restore {
+        /// A backup name from the backup directory, or the path to one
+        backup: PathBuf,
+    },
+    /// Register a user; clients send the name as the x-actor header
+    CreateUser { name: String },
+    /// Print collection totals as JSON
+    Stats,
+}
+
 #[tokio::main]
-async fn main() {
-    init_tracing();
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
 
-    let database_url = match std::env::var("DATABASE_URL") {
-        Ok(url) => url,
-        Err(error) => {
-            tracing::error!(%error, "DATABASE_URL is not set");
-            return;
-        }
+    let config = match Config::load(&cli.config) {
+        Ok(config) => config,
+        Err(error) => return fail(error),
     };
+    if cli.print_config {
+        match toml::to_string_pretty(&config) {
+            Ok(text) => print!("{text}"),
+            Err(error) => return fail(format!("could not print configuration: {error}")),
+        }
+    }
+    if let Err(error) = config.validate() {
+        return fail(error);
+    }
+    if cli.print_config {
+        return ExitCode::SUCCESS;
+    }
 
-    let pool = match SqlitePoolOptions::new()
-        .max_connections(5)
-        .after_connect(|connection, _| {
-            Box::pin(async move {
-                sqlx::query("PRAGMA foreign_keys = ON;")
-                    .execute(connection)
-                    .await
-                    .map(|_| ())
-            })
-        })
-        .connect(&database_url)
-        .await
-    {
-        Ok(pool) => pool,
-        Err(error) => {
-            tracing::error!(%error, "failed to connect to database");
-            return;
+    let command = cli.command.unwrap_or(Command::Serve);
+    init_tracing(&command, config.log_format);
+    match run(command, config).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => fail(error),
+    }
+}
+
+async fn run(command: Command, config: Config) -> Result<(), String> {
+    if config.uses_postgres() {
+        return run_postgres(command, config).await;
+    }
+    let state = match command {
+        Command::Restore { backup } => return restore(&config, &backup).await,
+        _ => open(&config).await?,
+    };
+    match command {
+        Command::Serve => serve(state, config).await,
+        Command::Migrate => {
+            println!("database is up to date");
+            Ok(())
+        }
+        Command::Seed => {
+            let summary = admin::seed(&state)
+                .await
+                .map_err(|error| error.to_string())?;
+            println!(
+                "seeded {} games, {} model definitions and {} lots",
+                summary.games, summary.model_definitions, summary.user_models
+            );
+            Ok(())
+        }
+        Command::Import { file } => {
+            let summary = admin::import(&state, &file)
+                .await
+                .map_err(|error| error.to_string())?;
+            println!(
+                "imported {} records; {} already had newer changes and were kept",
+                summary.accepted, summary.conflicts
+            );
+            for rejected in &summary.rejected {
+                eprintln!("rejected {rejected}");
+            }
+            match summary.rejected.len() {
+                0 => Ok(()),
+                count => Err(format!("{count} records could not be imported")),
+            }
+        }
+        Command::Export { file } => {
+            let count = admin::export(&state, &file)
+                .await
+                .map_err(|error| error.to_string())?;
+            println!("exported {count} records to {}", file.display());
+            Ok(())
+        }
+        Command::Backup => {
+            let summary = admin::backup(&state)
+                .await
+                .map_err(|error| error.to_string())?;
+            println!("wrote backup {}", summary.path.display());
+            for name in &summary.removed {
+                println!("removed old backup {name}");
+            }
+            Ok(())
+        }
+        Command::Restore { .. } => unreachable!("restore runs without opening the database"),
+        Command::CreateUser { name } => {
+            let id = admin::create_user(&state, &name)
+                .await
+                .map_err(|error| error.to_string())?;
+            println!("created user {} ({id})", name.trim());
+            Ok(())
         }
+        Command::Stats => {
+            let stats = admin::stats(&state)
+                .await
+                .map_err(|error| error.to_string())?;
+            let text = serde_json::to_string_pretty(&stats)
+                .map_err(|error| format!("could not print stats: {error}"))?;
+            println!("{text}");
+            Ok(())
+        }
+    }
+}
+
+async fn restore(config: &Config, backup: &Path) -> Result<(), String> {
+    let in_backup_dir = config.backups.dir.join(backup);
+    let backup = if backup.components().count() == 1 && in_backup_dir.exists() {
+        in_backup_dir
+    } else {
+        backup.to_path_buf()
     };
 
-    if let Err(error) = sqlx::migrate!().run(&pool).await {
-        tracing::error!(%error, "failed to run database migrations");
-        return;
+    let database_url = config.database_url.as_deref().unwrap_or_default();
+    let summary = admin::restore(database_url, &backup)
+        .await
+        .map_err(|error| error.to_string())?;
+    println!(
+        "restored {} (schema version {})",
+        backup.display(),
+        summary.schema_version
+    );
+    if let Some(previous) = summary.previous {
+        println!("the replaced database was saved as {}", previous.display());
     }
+    Ok(())
+}
 
-    let app_state = backend::AppState { pool };
-    let app = backend::app(app_state);
+/// Connects to the database and brings its schema up to date.
+async fn open(config: &Config) -> Result<AppState, String> {
+    let database_url = config.database_url.as_deref().unwrap_or_default();
+    let pools = backend::connect_pools(database_url, config.max_connections)
+        .await
+        .map_err(|error| format!("could not connect to database {database_url}: {error}"))?;
 
-    let listener = match tokio::net::TcpListener::bind("0.0.0.0:3000").await {
-        Ok(listener) => listener,
-        Err(error) => {
-            tracing::error!(%error, "failed to bind server address");
-            return;
-        }
-    };
+    backend::migrate(&pools.writer)
+        .await
+        .map_err(|error| format!("could not run database migrations: {error}"))?;
+
+    Ok(AppState {
+        repository: Arc::new(SqliteRepository::new(
+            pools.writer.clone(),
+            pools.reader.clone(),
+        )),
+        pool: pools.writer,
+        read_pool: pools.reader,
+        photo_dir: config.upload_dir.clone(),
+        events: backend::EventBus::new(),
+        presence: backend::PresenceHub::new(),
+        backups: config.backups.clone(),
+    })
+}
+
+async fn serve(state: AppState, config: Config) -> Result<(), String> {
+    if config.features.purge_job {
+        tokio::spawn(backend::run_purge_job(
+            state.clone(),
+            config.trash_retention_days,
+        ));
+    }
+    if config.features.backup_job {
+        tokio::spawn(backend::run_backup_job(state.clone()));
+    }
+    listen(backend::app_with_config(state, &config), &config).await
+}
+
+/// PostgreSQL only holds games, model definitions and lots so far, so only
+/// their routes are served and the admin commands other than `migrate` are
+/// refused.
+#[cfg(feature = "postgres")]
+async fn run_postgres(command: Command, config: Config) -> Result<(), String> {
+    let database_url = config.database_url.as_deref().unwrap_or_default();
+    let pool = backend::connect_postgres(database_url, config.max_connections)
+        .await
+        .map_err(|error| format!("could not connect to database {database_url}: {error}"))?;
+    backend::migrate_postgres(&pool)
+        .await
+        .map_err(|error| format!("could not run database migrations: {error}"))?;
 
-    if let Err(error) = axum::serve(listener, app).await {
-        tracing::error!(%error, "server error");
+    match command {
+        Command::Serve => {
+            let state = backend::CatalogState {
+                repository: Arc::new(backend::PostgresRepository::new(pool)),
+                events: backend::EventBus::new(),
+            };
+            listen(backend::catalog_app_with_config(state, &config), &config).await
+        }
+        Command::Migrate => {
+            println!("database is up to date");
+            Ok(())
+        }
+        _ => Err("only `serve` and `migrate` work with a PostgreSQL database".to_string()),
     }
 }
 
-fn init_tracing() {
-    let env_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+/// `Config::validate` rejects PostgreSQL URLs when the feature is off.
+#[cfg(not(feature = "postgres"))]
+async fn run_postgres(_command: Command, _config: Config) -> Result<(), String> {
+    Err("this build has no PostgreSQL support".to_string())
+}
 
-    tracing_subscriber::fmt().with_env_filter(env_filter).init();
+async fn listen(app: Router, config: &Config) -> Result<(), String> {
+    let listener = tokio::net::TcpListener::bind(config.bind)
+        .await
+        .map_err(|error| format!("could not listen on {}: {error}", config.bind))?;
+    tracing::info!(address = %config.bind, "listening");
+
+    axum::serve(listener, app)
+        .await
+        .map_err(|error| format!("server error: {error}"))
+}
+
+fn fail(error: impl std::fmt::Display) -> ExitCode {
+    eprintln!("error: {error}");
+    ExitCode::FAILURE
+}
+
+/// The server logs every request; admin commands only log problems unless
+/// `RUST_LOG` asks for more.
+fn init_tracing(command: &Command, format: LogFormat) {
+    let default = match command {
+        Command::Serve => "info",
+        _ => "warn",
+    };
+    let env_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(default));
+
+    let builder = tracing_subscriber::fmt().with_env_filter(env_filter);
+    match format {
+        LogFormat::Text => builder.init(),
+        LogFormat::Json => builder.json().init(),
+    }
 }
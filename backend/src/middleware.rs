@@ -0,0 +1,91 @@
+//! The layers every request goes through: an ID, a tracing span and an access
+//! log line, a timeout, compression, a body size limit and CORS.
+
+use std::time::{Duration, Instant};
+
+use axum::{
+    extract::{DefaultBodyLimit, Request, State},
+    http::{HeaderName, HeaderValue},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    Router,
+};
+use tower_http::compression::CompressionLayer;
+use tracing::Instrument;
+use uuid::Uuid;
+
+use crate::{AppError, Config};
+
+/// Sent back on every response. A request that already has one, say from a
+/// proxy, keeps it.
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longer IDs from clients are replaced rather than logged.
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// The ID of the request being handled, for error bodies.
+pub(crate) fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// `router` wrapped in the stack, configured from `config`.
+pub fn stack(router: Router, config: &Config) -> Router {
+    let timeout = Duration::from_secs(config.request_timeout_secs);
+    let router = router
+        .layer(DefaultBodyLimit::max(config.max_body_bytes))
+        .layer(CompressionLayer::new())
+        .layer(middleware::from_fn_with_state(timeout, observe));
+    match config.cors_layer() {
+        Some(cors) => router.layer(cors),
+        None => router,
+    }
+}
+
+/// Gives the request an ID, runs it in a span carrying that ID, cuts it off
+/// after `timeout` and logs how it went.
+async fn observe(State(timeout): State<Duration>, request: Request, next: Next) -> Response {
+    let request_id = request
+        .headers()
+        .get(&REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|id| !id.is_empty() && id.len() <= MAX_REQUEST_ID_LEN)
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    let span = tracing::info_span!(
+        "request",
+        request_id = %request_id,
+        method = %request.method(),
+        path = %request.uri().path(),
+    );
+
+    let started = Instant::now();
+    let handled = tokio::time::timeout(timeout, next.run(request));
+    let mut response = REQUEST_ID
+        .scope(request_id.clone(), async move {
+            match handled.await {
+                Ok(response) => response,
+                Err(_) => {
+                    tracing::warn!(timeout_secs = timeout.as_secs(), "request timed out");
+                    AppError::timed_out("request timed out").into_response()
+                }
+            }
+        })
+        .instrument(span.clone())
+        .await;
+
+    span.in_scope(|| {
+        tracing::info!(
+            status = response.status().as_u16(),
+            latency_ms = started.elapsed().as_millis() as u64,
+            "finished request"
+        )
+    });
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
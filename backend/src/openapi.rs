@@ -0,0 +1,119 @@
+//! The OpenAPI document for [`crate::app`], generated from the handlers'
+//! `#[utoipa::path]` attributes, and the routes that serve it. Paths are
+//! relative to [`API_V1`], the document's server.
+
+use axum::{routing::get, Json, Router};
+use utoipa::{openapi::Server, OpenApi};
+use utoipa_swagger_ui::SwaggerUi;
+
+use crate::{deprecation, Features, API_V1};
+
+/// Where the document is served.
+pub const SPEC_PATH: &str = "/api/v1/openapi.json";
+/// Where the document was served before the API moved under [`API_V1`]. It
+/// stays there, deprecated, like the other unversioned paths.
+const UNVERSIONED_SPEC_PATH: &str = "/openapi.json";
+/// Where Swagger UI is served.
+pub const DOCS_PATH: &str = "/docs";
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Mini Tracker API",
+        description = "Writes record who made them from the optional `x-actor` header. Every response has an `x-request-id` header, and errors are `{\"error\": message, \"request_id\": id}`."
+    ),
+    paths(
+        crate::health,
+        crate::create_game,
+        crate::list_games,
+        crate::trash::delete_game,
+        crate::create_model_definition,
+        crate::list_model_definitions,
+        crate::update_model_definition,
+        crate::trash::delete_model_definition,
+        crate::tags::set_model_definition_tags,
+        crate::create_user_model,
+        crate::list_user_models,
+        crate::get_user_model,
+        crate::update_user_model,
+        crate::trash::delete_user_model,
+        crate::tags::set_user_model_tags,
+        crate::locations::move_user_models,
+        crate::photos::upload_photo,
+        crate::photos::list_photos,
+        crate::photos::get_photo,
+        crate::photos::get_thumbnail,
+        crate::photos::delete_photo,
+        crate::locations::create_location,
+        crate::locations::list_locations,
+        crate::locations::location_tree,
+        crate::tags::create_tag,
+        crate::tags::list_tags,
+        crate::tags::update_tag,
+        crate::tags::delete_tag,
+        crate::army_lists::create_army_list,
+        crate::army_lists::list_army_lists,
+        crate::army_lists::get_army_list,
+        crate::army_lists::delete_army_list,
+        crate::army_lists::create_army_list_entry,
+        crate::army_lists::delete_army_list_entry,
+        crate::army_lists::validate_army_list,
+        crate::projects::create_project,
+        crate::projects::list_projects,
+        crate::projects::delete_project,
+        crate::projects::set_project_user_models,
+        crate::projects::project_progress,
+        crate::sessions::start_session,
+        crate::sessions::stop_session,
+        crate::sessions::active_session,
+        crate::sessions::list_sessions,
+        crate::sessions::session_stats,
+        crate::trash::list_trash,
+        crate::trash::restore,
+        crate::audit::list_audit_entries,
+        crate::stats::collection_stats,
+        crate::users::create_user,
+        crate::users::list_users,
+        crate::backups::create_backup,
+        crate::backups::list_backups,
+        crate::events::stream_events,
+        crate::presence::connect,
+        crate::sync::list_changes,
+        crate::sync::push_changes,
+    )
+)]
+struct ApiDoc;
+
+/// The document for the routes [`crate::app_with_features`] serves with
+/// these features.
+pub fn spec(features: &Features) -> utoipa::openapi::OpenApi {
+    let mut spec = ApiDoc::openapi();
+    spec.info.license = None;
+    spec.servers = Some(vec![Server::new(API_V1)]);
+    if let Some(health) = spec.paths.paths.get_mut("/health") {
+        health.servers = Some(vec![Server::new("/")]);
+    }
+    let switched_off = [
+        (features.events, "/events"),
+        (features.presence, "/ws"),
+        (features.sync, "/sync/"),
+    ];
+    spec.paths.paths.retain(|path, _| {
+        !switched_off
+            .iter()
+            .any(|(enabled, prefix)| !enabled && path.starts_with(prefix))
+    });
+    spec
+}
+
+/// The document at [`SPEC_PATH`] and its old path, and Swagger UI at
+/// [`DOCS_PATH`].
+pub(crate) fn routes(features: &Features) -> Router {
+    let document = spec(features);
+    let unversioned = Router::new().route(
+        UNVERSIONED_SPEC_PATH,
+        get(move || async move { Json(document) }),
+    );
+    Router::from(SwaggerUi::new(DOCS_PATH).url(SPEC_PATH, spec(features)))
+        .merge(deprecation::unversioned(unversioned, API_V1))
+}
@@ -0,0 +1,501 @@
+use std::path::{Path as FsPath, PathBuf};
+
+use axum::{
+    body::Bytes,
+    extract::{multipart::MultipartError, Multipart, Path, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use image::ImageFormat;
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    audit::{self, Action, Actor, Entity},
+    parse_uuid, AppError, AppState, ErrorBody,
+};
+
+/// Largest accepted original photo.
+pub(crate) const MAX_PHOTO_BYTES: usize = 10 * 1024 * 1024;
+
+/// Request body limit for uploads: the photo plus room for the multipart framing.
+pub(crate) const MAX_UPLOAD_BYTES: usize = MAX_PHOTO_BYTES + 64 * 1024;
+
+/// Thumbnails fit inside a square of this many pixels.
+const THUMBNAIL_SIZE: u32 = 320;
+
+const CACHE_FOREVER: &str = "private, max-age=31536000, immutable";
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct Photo {
+    id: Uuid,
+    user_model_id: Uuid,
+    file_name: String,
+    content_type: String,
+    size_bytes: i64,
+    width: i64,
+    height: i64,
+    created_at: String,
+}
+
+struct Upload {
+    file_name: String,
+    bytes: Bytes,
+}
+
+/// The form `POST /user-models/{id}/photos` takes.
+// Only describes the form for the OpenAPI document; `read_upload` parses it.
+#[derive(ToSchema)]
+#[allow(dead_code)]
+struct PhotoForm {
+    /// A JPEG, PNG or WebP image.
+    #[schema(value_type = String, content_media_type = "application/octet-stream")]
+    photo: Vec<u8>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/user-models/{id}/photos",
+    tag = "photos",
+    params(("id" = Uuid, Path)),
+    request_body(content = PhotoForm, content_type = "multipart/form-data"),
+    responses(
+        (status = 201, body = Photo),
+        (status = 404, description = "The lot does not exist", body = ErrorBody),
+        (status = 413, description = "The photo is larger than 10 MiB", body = ErrorBody),
+        (status = 415, description = "The photo is not a JPEG, PNG or WebP image", body = ErrorBody),
+        (status = 422, description = "The form has no usable `photo` field", body = ErrorBody),
+    )
+)]
+pub(crate) async fn upload_photo(
+    State(state): State<AppState>,
+    actor: Actor,
+    Path(user_model_id): Path<Uuid>,
+    multipart: Multipart,
+) -> Result<(StatusCode, Json<Photo>), AppError> {
+    tracing::info!(user_model_id = %user_model_id, "uploading photo");
+    let user_model_id_value = user_model_id.to_string();
+    let exists = sqlx::query!(
+        r#"
+        SELECT user_models.id
+        FROM user_models
+        INNER JOIN model_definitions ON model_definitions.id = user_models.model_definition_id
+        INNER JOIN games ON games.id = model_definitions.game_id
+        WHERE user_models.id = $1
+          AND user_models.deleted_at IS NULL
+          AND model_definitions.deleted_at IS NULL
+          AND games.deleted_at IS NULL
+        "#,
+        user_model_id_value
+    )
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|error| AppError::internal("failed to validate user model", error))?;
+
+    if exists.is_none() {
+        return Err(AppError::not_found("user model not found"));
+    }
+
+    let upload = read_upload(multipart).await?;
+    let (content_type, extension) = match image::guess_format(&upload.bytes) {
+        Ok(ImageFormat::Jpeg) => ("image/jpeg", "jpg"),
+        Ok(ImageFormat::Png) => ("image/png", "png"),
+        Ok(ImageFormat::WebP) => ("image/webp", "webp"),
+        _ => {
+            return Err(AppError::unsupported_media_type(
+                "photo must be a JPEG, PNG or WebP image",
+            ));
+        }
+    };
+
+    let bytes = upload.bytes.clone();
+    let (width, height, thumbnail) = tokio::task::spawn_blocking(move || render_thumbnail(&bytes))
+        .await
+        .map_err(|error| AppError::internal("failed to generate thumbnail", error))??;
+
+    let id = Uuid::new_v4();
+    let id_value = id.to_string();
+    let original_path = original_path(&state.photo_dir, &id_value, extension);
+    let thumbnail_path = thumbnail_path(&state.photo_dir, &id_value);
+    tokio::fs::create_dir_all(&state.photo_dir)
+        .await
+        .map_err(|error| AppError::internal("failed to create photo directory", error))?;
+    tokio::fs::write(&original_path, &upload.bytes)
+        .await
+        .map_err(|error| AppError::internal("failed to store photo", error))?;
+    tokio::fs::write(&thumbnail_path, &thumbnail)
+        .await
+        .map_err(|error| AppError::internal("failed to store thumbnail", error))?;
+
+    let size_bytes = upload.bytes.len() as i64;
+    let width = i64::from(width);
+    let height = i64::from(height);
+    let inserted = async {
+        let mut transaction = state
+            .pool
+            .begin()
+            .await
+            .map_err(|error| AppError::internal("failed to start transaction", error))?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO photos (
+                id, user_model_id, file_name, content_type, size_bytes, width, height, created_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, datetime('now'))
+            "#,
+            id_value,
+            user_model_id_value,
+            upload.file_name,
+            content_type,
+            size_bytes,
+            width,
+            height
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|error| AppError::internal("failed to create photo", error))?;
+
+        audit::record_row(
+            &mut transaction,
+            &actor,
+            Entity::Photo,
+            &id_value,
+            Action::Create,
+            None,
+        )
+        .await?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(|error| AppError::internal("failed to commit transaction", error))
+    }
+    .await;
+
+    if let Err(error) = inserted {
+        remove_files(&[original_path, thumbnail_path]).await;
+        return Err(error);
+    }
+    state
+        .events
+        .changed(Entity::Photo, &id_value, Action::Create);
+
+    let record = sqlx::query!(
+        r#"
+        SELECT
+            id as "id!: String",
+            user_model_id as "user_model_id!: String",
+            file_name,
+            content_type,
+            size_bytes as "size_bytes!: i64",
+            width as "width!: i64",
+            height as "height!: i64",
+            created_at as "created_at!: String"
+        FROM photos
+        WHERE id = $1
+        "#,
+        id_value
+    )
+    .fetch_one(&state.pool)
+    .await
+    .map_err(|error| AppError::internal("failed to load photo", error))?;
+
+    let photo = Photo {
+        id: parse_uuid(record.id)?,
+        user_model_id: parse_uuid(record.user_model_id)?,
+        file_name: record.file_name,
+        content_type: record.content_type,
+        size_bytes: record.size_bytes,
+        width: record.width,
+        height: record.height,
+        created_at: record.created_at,
+    };
+
+    Ok((StatusCode::CREATED, Json(photo)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/user-models/{id}/photos",
+    tag = "photos",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = Vec<Photo>))
+)]
+pub(crate) async fn list_photos(
+    State(state): State<AppState>,
+    Path(user_model_id): Path<Uuid>,
+) -> Result<Json<Vec<Photo>>, AppError> {
+    tracing::info!(user_model_id = %user_model_id, "listing photos");
+    let user_model_id_value = user_model_id.to_string();
+    let records = sqlx::query!(
+        r#"
+        SELECT
+            id as "id!: String",
+            user_model_id as "user_model_id!: String",
+            file_name,
+            content_type,
+            size_bytes as "size_bytes!: i64",
+            width as "width!: i64",
+            height as "height!: i64",
+            created_at as "created_at!: String"
+        FROM photos
+        WHERE user_model_id = $1
+        ORDER BY created_at
+        "#,
+        user_model_id_value
+    )
+    .fetch_all(&state.read_pool)
+    .await
+    .map_err(|error| AppError::internal("failed to fetch photos", error))?;
+
+    let photos = records
+        .into_iter()
+        .map(|record| {
+            Ok(Photo {
+                id: parse_uuid(record.id)?,
+                user_model_id: parse_uuid(record.user_model_id)?,
+                file_name: record.file_name,
+                content_type: record.content_type,
+                size_bytes: record.size_bytes,
+                width: record.width,
+                height: record.height,
+                created_at: record.created_at,
+            })
+        })
+        .collect::<Result<Vec<_>, AppError>>()?;
+
+    tracing::info!(count = photos.len(), "listed photos");
+    Ok(Json(photos))
+}
+
+#[utoipa::path(
+    get,
+    path = "/photos/{id}",
+    tag = "photos",
+    params(("id" = Uuid, Path)),
+    responses(
+        (
+            status = 200,
+            description = "The original image",
+            content(("image/jpeg"), ("image/png"), ("image/webp"))
+        ),
+        (status = 404, body = ErrorBody),
+    )
+)]
+pub(crate) async fn get_photo(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let id_value = id.to_string();
+    let content_type = load_content_type(&state, &id_value).await?;
+    let path = original_path(&state.photo_dir, &id_value, extension_for(&content_type));
+    let bytes = read_file(&path).await?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type),
+            (header::CACHE_CONTROL, CACHE_FOREVER.to_string()),
+        ],
+        bytes,
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/photos/{id}/thumbnail",
+    tag = "photos",
+    params(("id" = Uuid, Path)),
+    responses(
+        (
+            status = 200,
+            description = "A JPEG at most 320 pixels on each side",
+            content(("image/jpeg"))
+        ),
+        (status = 404, body = ErrorBody),
+    )
+)]
+pub(crate) async fn get_thumbnail(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let id_value = id.to_string();
+    load_content_type(&state, &id_value).await?;
+    let bytes = read_file(&thumbnail_path(&state.photo_dir, &id_value)).await?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "image/jpeg"),
+            (header::CACHE_CONTROL, CACHE_FOREVER),
+        ],
+        bytes,
+    ))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/photos/{id}",
+    tag = "photos",
+    params(("id" = Uuid, Path)),
+    responses((status = 204), (status = 404, body = ErrorBody))
+)]
+pub(crate) async fn delete_photo(
+    State(state): State<AppState>,
+    actor: Actor,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    tracing::info!(photo_id = %id, "deleting photo");
+    let id_value = id.to_string();
+    let content_type = load_content_type(&state, &id_value).await?;
+    let mut transaction = state
+        .pool
+        .begin()
+        .await
+        .map_err(|error| AppError::internal("failed to start transaction", error))?;
+
+    let before = audit::snapshot(&mut transaction, Entity::Photo, &id_value).await?;
+    sqlx::query!(
+        r#"
+        DELETE FROM photos
+        WHERE id = $1
+        "#,
+        id_value
+    )
+    .execute(&mut *transaction)
+    .await
+    .map_err(|error| AppError::internal("failed to delete photo", error))?;
+
+    audit::record_row(
+        &mut transaction,
+        &actor,
+        Entity::Photo,
+        &id_value,
+        Action::Delete,
+        before,
+    )
+    .await?;
+
+    transaction
+        .commit()
+        .await
+        .map_err(|error| AppError::internal("failed to commit transaction", error))?;
+    state
+        .events
+        .changed(Entity::Photo, &id_value, Action::Delete);
+
+    remove_files(&stored_files(&state.photo_dir, &id_value, &content_type)).await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn read_upload(mut multipart: Multipart) -> Result<Upload, AppError> {
+    while let Some(mut field) = multipart.next_field().await.map_err(map_multipart_error)? {
+        if field.name() != Some("photo") {
+            continue;
+        }
+
+        let file_name = field.file_name().unwrap_or("photo").to_string();
+        let mut bytes = Vec::new();
+        while let Some(chunk) = field.chunk().await.map_err(map_multipart_error)? {
+            if bytes.len() + chunk.len() > MAX_PHOTO_BYTES {
+                return Err(AppError::payload_too_large("photo is too large"));
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        if bytes.is_empty() {
+            return Err(AppError::unprocessable("photo is empty"));
+        }
+
+        return Ok(Upload {
+            file_name,
+            bytes: Bytes::from(bytes),
+        });
+    }
+
+    Err(AppError::unprocessable("missing photo field"))
+}
+
+/// Decodes the upload and returns its dimensions along with a JPEG thumbnail.
+fn render_thumbnail(bytes: &[u8]) -> Result<(u32, u32, Vec<u8>), AppError> {
+    let image = image::load_from_memory(bytes)
+        .map_err(|_| AppError::unprocessable("photo could not be decoded"))?;
+    let thumbnail = image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE).to_rgb8();
+    let mut encoded = std::io::Cursor::new(Vec::new());
+    thumbnail
+        .write_to(&mut encoded, ImageFormat::Jpeg)
+        .map_err(|error| AppError::internal("failed to encode thumbnail", error))?;
+
+    Ok((image.width(), image.height(), encoded.into_inner()))
+}
+
+async fn load_content_type(state: &AppState, id: &str) -> Result<String, AppError> {
+    let record = sqlx::query!(
+        r#"
+        SELECT content_type
+        FROM photos
+        WHERE id = $1
+        "#,
+        id
+    )
+    .fetch_optional(&state.read_pool)
+    .await
+    .map_err(|error| AppError::internal("failed to load photo", error))?;
+
+    match record {
+        Some(record) => Ok(record.content_type),
+        None => Err(AppError::not_found("photo not found")),
+    }
+}
+
+async fn read_file(path: &FsPath) -> Result<Vec<u8>, AppError> {
+    match tokio::fs::read(path).await {
+        Ok(bytes) => Ok(bytes),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+            Err(AppError::not_found("photo file not found"))
+        }
+        Err(error) => Err(AppError::internal("failed to read photo", error)),
+    }
+}
+
+pub(crate) async fn remove_files(paths: &[PathBuf]) {
+    for path in paths {
+        if let Err(error) = tokio::fs::remove_file(path).await {
+            tracing::warn!(%error, path = %path.display(), "failed to remove photo file");
+        }
+    }
+}
+
+/// The original upload and its thumbnail for a stored photo.
+pub(crate) fn stored_files(dir: &FsPath, id: &str, content_type: &str) -> [PathBuf; 2] {
+    [
+        original_path(dir, id, extension_for(content_type)),
+        thumbnail_path(dir, id),
+    ]
+}
+
+fn original_path(dir: &FsPath, id: &str, extension: &str) -> PathBuf {
+    dir.join(format!("{id}.{extension}"))
+}
+
+fn thumbnail_path(dir: &FsPath, id: &str) -> PathBuf {
+    dir.join(format!("{id}.thumb.jpg"))
+}
+
+fn extension_for(content_type: &str) -> &'static str {
+    match content_type {
+        "image/png" => "png",
+        "image/webp" => "webp",
+        _ => "jpg",
+    }
+}
+
+fn map_multipart_error(error: MultipartError) -> AppError {
+    if error.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        return AppError::payload_too_large("photo is too large");
+    }
+
+    AppError::bad_request("invalid multipart body")
+}
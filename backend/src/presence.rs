@@ -0,0 +1,278 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    response::Response,
+};
+use futures_util::{
+    stream::{SplitSink, SplitStream},
+    SinkExt, StreamExt,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::{
+    sync::{broadcast::error::RecvError, watch},
+    time::Instant,
+};
+use uuid::Uuid;
+
+use crate::{events, AppState};
+
+/// How often the server pings each client.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// A client that sends nothing, not even a pong, for this long is dropped.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(45);
+
+/// How long a new connection has to introduce itself.
+const HELLO_TIMEOUT: Duration = Duration::from_secs(10);
+
+const MAX_NAME_LENGTH: usize = 40;
+
+/// Avatar colors, handed out to clients in turn.
+const COLORS: [&str; 8] = [
+    "#e6194b", "#3cb44b", "#4363d8", "#f58231", "#911eb4", "#42d4f4", "#f032e6", "#9a6324",
+];
+
+/// Someone connected to `/ws`.
+#[derive(Clone, Serialize)]
+pub(crate) struct Member {
+    client_id: Uuid,
+    name: String,
+    color: &'static str,
+    /// The lot whose row they have open, if any.
+    user_model_id: Option<Uuid>,
+}
+
+/// Messages a client sends.
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+enum ClientMessage {
+    /// Must be the first message on a connection.
+    Hello {
+        name: String,
+    },
+    RowFocus {
+        user_model_id: Option<Uuid>,
+    },
+}
+
+/// Messages the server sends.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+enum ServerMessage<'a> {
+    /// Answers `hello` with the ID the client is known by in `presence`.
+    Hello {
+        client_id: Uuid,
+    },
+    /// Everyone currently connected, sent whenever anyone joins, leaves or
+    /// focuses a row.
+    Presence {
+        members: &'a [Member],
+    },
+    /// A change event, as streamed by `/events`.
+    Change {
+        event: Value,
+    },
+    Error {
+        message: &'a str,
+    },
+}
+
+/// Tracks who is connected and which row each of them has open.
+#[derive(Clone)]
+pub struct PresenceHub {
+    members: Arc<watch::Sender<Vec<Member>>>,
+    joined: Arc<AtomicUsize>,
+}
+
+impl PresenceHub {
+    pub fn new() -> Self {
+        Self {
+            members: Arc::new(watch::Sender::new(Vec::new())),
+            joined: Arc::default(),
+        }
+    }
+
+    fn join(&self, name: String) -> Uuid {
+        let client_id = Uuid::new_v4();
+        let color = COLORS[self.joined.fetch_add(1, Ordering::Relaxed) % COLORS.len()];
+        self.members.send_modify(|members| {
+            members.push(Member {
+                client_id,
+                name,
+                color,
+                user_model_id: None,
+            });
+        });
+        client_id
+    }
+
+    fn focus(&self, client_id: Uuid, user_model_id: Option<Uuid>) {
+        self.members.send_if_modified(|members| {
+            match members
+                .iter_mut()
+                .find(|member| member.client_id == client_id)
+            {
+                Some(member) if member.user_model_id != user_model_id => {
+                    member.user_model_id = user_model_id;
+                    true
+                }
+                _ => false,
+            }
+        });
+    }
+
+    fn leave(&self, client_id: Uuid) {
+        self.members
+            .send_modify(|members| members.retain(|member| member.client_id != client_id));
+    }
+}
+
+impl Default for PresenceHub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/ws",
+    tag = "presence",
+    responses((status = 101, description = "Upgrades to the collaboration WebSocket"))
+)]
+pub(crate) async fn connect(State(state): State<AppState>, upgrade: WebSocketUpgrade) -> Response {
+    upgrade.on_upgrade(move |socket| run(socket, state))
+}
+
+async fn run(socket: WebSocket, state: AppState) {
+    let (mut sender, mut receiver) = socket.split();
+
+    let name = match tokio::time::timeout(HELLO_TIMEOUT, receiver.next()).await {
+        Ok(Some(Ok(Message::Text(text)))) => match serde_json::from_str(&text) {
+            Ok(ClientMessage::Hello { name }) => {
+                name.trim().chars().take(MAX_NAME_LENGTH).collect()
+            }
+            _ => String::new(),
+        },
+        _ => return,
+    };
+    if name.is_empty() {
+        let _ = send(
+            &mut sender,
+            &ServerMessage::Error {
+                message: "expected hello with a name",
+            },
+        )
+        .await;
+        return;
+    }
+
+    let client_id = state.presence.join(name);
+    tracing::info!(%client_id, "collaborator connected");
+    if send(&mut sender, &ServerMessage::Hello { client_id })
+        .await
+        .is_ok()
+    {
+        serve(&state, client_id, &mut sender, &mut receiver).await;
+    }
+
+    state.presence.leave(client_id);
+    tracing::info!(%client_id, "collaborator disconnected");
+}
+
+async fn serve(
+    state: &AppState,
+    client_id: Uuid,
+    sender: &mut SplitSink<WebSocket, Message>,
+    receiver: &mut SplitStream<WebSocket>,
+) {
+    let mut members = state.presence.members.subscribe();
+    members.mark_changed();
+    let mut changes = state.events.subscribe_live();
+    let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
+    let mut last_seen = Instant::now();
+
+    loop {
+        let sent = tokio::select! {
+            message = receiver.next() => {
+                let Some(Ok(message)) = message else {
+                    return;
+                };
+                last_seen = Instant::now();
+                match message {
+                    Message::Text(text) => match serde_json::from_str(&text) {
+                        Ok(ClientMessage::RowFocus { user_model_id }) => {
+                            state.presence.focus(client_id, user_model_id);
+                            Ok(())
+                        }
+                        Ok(ClientMessage::Hello { .. }) => {
+                            send(sender, &ServerMessage::Error { message: "already said hello" }).await
+                        }
+                        Err(_) => {
+                            send(sender, &ServerMessage::Error { message: "invalid message" }).await
+                        }
+                    },
+                    Message::Close(_) => return,
+                    _ => Ok(()),
+                }
+            }
+            changed = members.changed() => {
+                if changed.is_err() {
+                    return;
+                }
+                let snapshot = members.borrow_and_update().clone();
+                send(sender, &ServerMessage::Presence { members: &snapshot }).await
+            }
+            published = changes.recv() => {
+                let data = match published {
+                    Ok(published) => published.data,
+                    Err(RecvError::Lagged(_)) => events::RESYNC.to_string(),
+                    Err(RecvError::Closed) => return,
+                };
+                match serde_json::from_str(&data) {
+                    Ok(event) => send(sender, &ServerMessage::Change { event }).await,
+                    Err(error) => {
+                        tracing::error!(%error, "failed to forward change event");
+                        Ok(())
+                    }
+                }
+            }
+            _ = heartbeat.tick() => {
+                if last_seen.elapsed() > CLIENT_TIMEOUT {
+                    tracing::info!(%client_id, "collaborator timed out");
+                    return;
+                }
+                sender
+                    .send(Message::Ping(Vec::new()))
+                    .await
+                    .map_err(|error| error.to_string())
+            }
+        };
+
+        if let Err(error) = sent {
+            tracing::warn!(%client_id, %error, "failed to write to collaborator");
+            return;
+        }
+    }
+}
+
+async fn send(
+    sender: &mut SplitSink<WebSocket, Message>,
+    message: &ServerMessage<'_>,
+) -> Result<(), String> {
+    let text = serde_json::to_string(message).map_err(|error| error.to_string())?;
+    sender
+        .send(Message::Text(text))
+        .await
+        .map_err(|error| error.to_string())
+}
@@ -0,0 +1,534 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::{Sqlite, SqliteConnection, SqlitePool, Transaction};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    audit::{self, Action, Actor, Entity},
+    parse_i32, parse_status, parse_uuid, AppError, AppState, ErrorBody, Status,
+};
+
+#[derive(Deserialize, ToSchema)]
+pub(crate) struct CreateProject {
+    name: String,
+    target_status: Status,
+    /// Deadline as a `YYYY-MM-DD` date.
+    due_date: Option<String>,
+    #[serde(default)]
+    user_model_ids: Vec<Uuid>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub(crate) struct SetProjectUserModels {
+    user_model_ids: Vec<Uuid>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct Project {
+    id: Uuid,
+    name: String,
+    target_status: Status,
+    due_date: Option<String>,
+    user_model_ids: Vec<Uuid>,
+    created_at: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct ProjectProgress {
+    project_id: Uuid,
+    target_status: Status,
+    due_date: Option<String>,
+    total_quantity: i64,
+    completed_quantity: i64,
+    remaining_quantity: i64,
+    /// True once the due date has passed with models still remaining.
+    overdue: bool,
+    lots: Vec<ProjectLot>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct ProjectLot {
+    user_model_id: Uuid,
+    model_name: String,
+    quantity: i32,
+    status: Status,
+    completed: bool,
+}
+
+#[utoipa::path(
+    post,
+    path = "/projects",
+    tag = "projects",
+    request_body = CreateProject,
+    responses(
+        (status = 201, body = Project),
+        (status = 404, description = "A lot does not exist", body = ErrorBody),
+        (status = 422, description = "The name is blank or the due date is not a date", body = ErrorBody),
+    )
+)]
+pub(crate) async fn create_project(
+    State(state): State<AppState>,
+    actor: Actor,
+    Json(payload): Json<CreateProject>,
+) -> Result<(StatusCode, Json<Project>), AppError> {
+    tracing::info!(
+        name = %payload.name,
+        target_status = payload.target_status.as_str(),
+        lots = payload.user_model_ids.len(),
+        "creating project"
+    );
+    let name = payload.name.trim().to_string();
+    if name.is_empty() {
+        return Err(AppError::unprocessable("project name is required"));
+    }
+    if let Some(due_date) = &payload.due_date {
+        validate_due_date(due_date)?;
+    }
+
+    let id = Uuid::new_v4();
+    let id_value = id.to_string();
+    let target_status = payload.target_status.as_str();
+    let mut transaction = state
+        .pool
+        .begin()
+        .await
+        .map_err(|error| AppError::internal("failed to start transaction", error))?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO projects (id, name, target_status, due_date, created_at)
+        VALUES ($1, $2, $3, $4, datetime('now'))
+        "#,
+        id_value,
+        name,
+        target_status,
+        payload.due_date
+    )
+    .execute(&mut *transaction)
+    .await
+    .map_err(|error| AppError::internal("failed to create project", error))?;
+
+    replace_lots(&mut transaction, &id_value, &payload.user_model_ids).await?;
+    audit::record_row(
+        &mut transaction,
+        &actor,
+        Entity::Project,
+        &id_value,
+        Action::Create,
+        None,
+    )
+    .await?;
+
+    transaction
+        .commit()
+        .await
+        .map_err(|error| AppError::internal("failed to commit transaction", error))?;
+    state
+        .events
+        .changed(Entity::Project, &id_value, Action::Create);
+
+    let project = load_projects(&state.pool, Some(&id_value))
+        .await?
+        .pop()
+        .ok_or_else(|| AppError::internal_message("failed to load project"))?;
+
+    Ok((StatusCode::CREATED, Json(project)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/projects",
+    tag = "projects",
+    responses((status = 200, body = Vec<Project>))
+)]
+pub(crate) async fn list_projects(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<Project>>, AppError> {
+    tracing::info!("listing projects");
+    let projects = load_projects(&state.read_pool, None).await?;
+    tracing::info!(count = projects.len(), "listed projects");
+    Ok(Json(projects))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/projects/{id}",
+    tag = "projects",
+    params(("id" = Uuid, Path)),
+    responses((status = 204), (status = 404, body = ErrorBody))
+)]
+pub(crate) async fn delete_project(
+    State(state): State<AppState>,
+    actor: Actor,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    tracing::info!(project_id = %id, "deleting project");
+    let id_value = id.to_string();
+    let mut transaction = state
+        .pool
+        .begin()
+        .await
+        .map_err(|error| AppError::internal("failed to start transaction", error))?;
+
+    let before = audit::snapshot(&mut transaction, Entity::Project, &id_value).await?;
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM projects
+        WHERE id = $1
+        "#,
+        id_value
+    )
+    .execute(&mut *transaction)
+    .await
+    .map_err(|error| AppError::internal("failed to delete project", error))?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found("project not found"));
+    }
+
+    audit::record_row(
+        &mut transaction,
+        &actor,
+        Entity::Project,
+        &id_value,
+        Action::Delete,
+        before,
+    )
+    .await?;
+
+    transaction
+        .commit()
+        .await
+        .map_err(|error| AppError::internal("failed to commit transaction", error))?;
+    state
+        .events
+        .changed(Entity::Project, &id_value, Action::Delete);
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    put,
+    path = "/projects/{id}/user-models",
+    tag = "projects",
+    params(("id" = Uuid, Path)),
+    request_body = SetProjectUserModels,
+    responses(
+        (status = 200, body = Project),
+        (status = 404, description = "The project or a lot does not exist", body = ErrorBody),
+    )
+)]
+pub(crate) async fn set_project_user_models(
+    State(state): State<AppState>,
+    actor: Actor,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<SetProjectUserModels>,
+) -> Result<Json<Project>, AppError> {
+    tracing::info!(
+        project_id = %id,
+        count = payload.user_model_ids.len(),
+        "setting project lots"
+    );
+    let id_value = id.to_string();
+    let mut transaction = state
+        .pool
+        .begin()
+        .await
+        .map_err(|error| AppError::internal("failed to start transaction", error))?;
+
+    let exists = sqlx::query!(
+        r#"
+        SELECT id
+        FROM projects
+        WHERE id = $1
+        "#,
+        id_value
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .map_err(|error| AppError::internal("failed to validate project", error))?;
+
+    if exists.is_none() {
+        return Err(AppError::not_found("project not found"));
+    }
+
+    let before = project_lot_ids(&mut transaction, &id_value).await?;
+    replace_lots(&mut transaction, &id_value, &payload.user_model_ids).await?;
+    let after = project_lot_ids(&mut transaction, &id_value).await?;
+    audit::record(
+        &mut transaction,
+        &actor,
+        Entity::Project,
+        &id_value,
+        Action::Update,
+        Some(json!({ "user_model_ids": before })),
+        Some(json!({ "user_model_ids": after })),
+    )
+    .await?;
+
+    transaction
+        .commit()
+        .await
+        .map_err(|error| AppError::internal("failed to commit transaction", error))?;
+    state
+        .events
+        .changed(Entity::Project, &id_value, Action::Update);
+
+    let project = load_projects(&state.pool, Some(&id_value))
+        .await?
+        .pop()
+        .ok_or_else(|| AppError::internal_message("failed to load project"))?;
+
+    Ok(Json(project))
+}
+
+#[utoipa::path(
+    get,
+    path = "/projects/{id}/progress",
+    tag = "projects",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = ProjectProgress), (status = 404, body = ErrorBody))
+)]
+pub(crate) async fn project_progress(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ProjectProgress>, AppError> {
+    tracing::info!(project_id = %id, "computing project progress");
+    let id_value = id.to_string();
+    let project = sqlx::query!(
+        r#"
+        SELECT
+            target_status,
+            due_date,
+            COALESCE(due_date < date('now'), 0) as "past_due!: bool"
+        FROM projects
+        WHERE id = $1
+        "#,
+        id_value
+    )
+    .fetch_optional(&state.read_pool)
+    .await
+    .map_err(|error| AppError::internal("failed to load project", error))?;
+
+    let Some(project) = project else {
+        return Err(AppError::not_found("project not found"));
+    };
+    let target_status = parse_status(&project.target_status)?;
+
+    let records = sqlx::query!(
+        r#"
+        SELECT
+            user_models.id as "id!: String",
+            model_definitions.name AS model_name,
+            user_models.quantity,
+            user_models.status
+        FROM project_user_models
+        INNER JOIN user_models ON user_models.id = project_user_models.user_model_id
+        INNER JOIN model_definitions ON model_definitions.id = user_models.model_definition_id
+        INNER JOIN games ON games.id = model_definitions.game_id
+        WHERE project_user_models.project_id = $1
+          AND user_models.deleted_at IS NULL
+          AND model_definitions.deleted_at IS NULL
+          AND games.deleted_at IS NULL
+        ORDER BY model_definitions.name
+        "#,
+        id_value
+    )
+    .fetch_all(&state.read_pool)
+    .await
+    .map_err(|error| AppError::internal("failed to fetch project lots", error))?;
+
+    let lots = records
+        .into_iter()
+        .map(|record| {
+            let status = parse_status(&record.status)?;
+            Ok(ProjectLot {
+                user_model_id: parse_uuid(record.id)?,
+                model_name: record.model_name,
+                quantity: parse_i32(record.quantity)?,
+                status,
+                completed: status >= target_status,
+            })
+        })
+        .collect::<Result<Vec<_>, AppError>>()?;
+
+    let total_quantity = lots.iter().map(|lot| i64::from(lot.quantity)).sum::<i64>();
+    let completed_quantity = lots
+        .iter()
+        .filter(|lot| lot.completed)
+        .map(|lot| i64::from(lot.quantity))
+        .sum::<i64>();
+    let remaining_quantity = total_quantity - completed_quantity;
+
+    Ok(Json(ProjectProgress {
+        project_id: id,
+        target_status,
+        due_date: project.due_date,
+        total_quantity,
+        completed_quantity,
+        remaining_quantity,
+        overdue: project.past_due && remaining_quantity > 0,
+        lots,
+    }))
+}
+
+async fn replace_lots(
+    transaction: &mut Transaction<'_, Sqlite>,
+    project_id: &str,
+    user_model_ids: &[Uuid],
+) -> Result<(), AppError> {
+    sqlx::query!(
+        r#"
+        DELETE FROM project_user_models
+        WHERE project_id = $1
+        "#,
+        project_id
+    )
+    .execute(&mut **transaction)
+    .await
+    .map_err(|error| AppError::internal("failed to clear project lots", error))?;
+
+    for user_model_id in user_model_ids {
+        let user_model_id_value = user_model_id.to_string();
+        sqlx::query!(
+            r#"
+            INSERT OR IGNORE INTO project_user_models (project_id, user_model_id)
+            VALUES ($1, $2)
+            "#,
+            project_id,
+            user_model_id_value
+        )
+        .execute(&mut **transaction)
+        .await
+        .map_err(map_lot_error)?;
+    }
+
+    Ok(())
+}
+
+/// User model IDs currently linked to a project, for audit snapshots.
+async fn project_lot_ids(
+    conn: &mut SqliteConnection,
+    project_id: &str,
+) -> Result<Vec<String>, AppError> {
+    let records = sqlx::query!(
+        r#"
+        SELECT user_model_id as "user_model_id!: String"
+        FROM project_user_models
+        WHERE project_id = $1
+        ORDER BY user_model_id
+        "#,
+        project_id
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|error| AppError::internal("failed to fetch project lots", error))?;
+
+    Ok(records
+        .into_iter()
+        .map(|record| record.user_model_id)
+        .collect())
+}
+
+async fn load_projects(pool: &SqlitePool, id: Option<&str>) -> Result<Vec<Project>, AppError> {
+    let records = sqlx::query!(
+        r#"
+        SELECT
+            id as "id!: String",
+            name,
+            target_status,
+            due_date,
+            created_at as "created_at!: String"
+        FROM projects
+        WHERE $1 IS NULL OR id = $1
+        ORDER BY due_date IS NULL, due_date, created_at
+        "#,
+        id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|error| AppError::internal("failed to fetch projects", error))?;
+
+    let links = sqlx::query!(
+        r#"
+        SELECT
+            project_id as "project_id!: String",
+            user_model_id as "user_model_id!: String"
+        FROM project_user_models
+        WHERE $1 IS NULL OR project_id = $1
+        "#,
+        id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|error| AppError::internal("failed to fetch project lots", error))?;
+
+    let mut lots: HashMap<String, Vec<Uuid>> = HashMap::new();
+    for link in links {
+        lots.entry(link.project_id)
+            .or_default()
+            .push(parse_uuid(link.user_model_id)?);
+    }
+
+    records
+        .into_iter()
+        .map(|record| {
+            Ok(Project {
+                user_model_ids: lots.remove(&record.id).unwrap_or_default(),
+                id: parse_uuid(record.id)?,
+                name: record.name,
+                target_status: parse_status(&record.target_status)?,
+                due_date: record.due_date,
+                created_at: record.created_at,
+            })
+        })
+        .collect()
+}
+
+/// Accepts only real calendar dates written as `YYYY-MM-DD`.
+fn validate_due_date(due_date: &str) -> Result<(), AppError> {
+    let invalid = || AppError::unprocessable("due date must be a YYYY-MM-DD date");
+    let parts = due_date.split('-').collect::<Vec<_>>();
+    let [year, month, day] = parts.as_slice() else {
+        return Err(invalid());
+    };
+    if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+        return Err(invalid());
+    }
+    let year = year.parse::<u32>().map_err(|_| invalid())?;
+    let month = month.parse::<u32>().map_err(|_| invalid())?;
+    let day = day.parse::<u32>().map_err(|_| invalid())?;
+
+    let leap_year = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap_year => 29,
+        2 => 28,
+        _ => return Err(invalid()),
+    };
+    if day == 0 || day > days_in_month {
+        return Err(invalid());
+    }
+
+    Ok(())
+}
+
+fn map_lot_error(error: sqlx::Error) -> AppError {
+    if let sqlx::Error::Database(db_error) = &error {
+        let message = db_error.message();
+        if message.contains("FOREIGN KEY constraint failed") {
+            return AppError::not_found("user model not found");
+        }
+    }
+
+    AppError::internal("failed to add project lot", error)
+}
@@ -0,0 +1,85 @@
+//! Storage for games, model definitions and lots. The handlers for those
+//! routes only talk to a [`Repository`], so the same handlers run on SQLite
+//! or, with the `postgres` feature, on PostgreSQL. [`InMemoryRepository`]
+//! runs them without a database at all, for tests.
+//!
+//! Only this catalog goes through a repository. Every other handler
+//! (locations, tags, photos, sessions, army lists, the trash and the admin
+//! routes) queries SQLite through [`AppState::pool`](crate::AppState::pool).
+
+use std::sync::Arc;
+
+use axum::async_trait;
+use uuid::Uuid;
+
+use crate::{
+    audit::Actor, AppError, CreateModelDefinition, CreateUserModel, Game, ModelDefinition,
+    UpdateModelDefinition, UpdateUserModel, UserModel, UserModelListItem,
+};
+
+mod memory;
+#[cfg(feature = "postgres")]
+mod postgres;
+mod sqlite;
+
+pub use memory::InMemoryRepository;
+#[cfg(feature = "postgres")]
+pub use postgres::{connect_postgres, migrate_postgres, PostgresRepository};
+pub use sqlite::SqliteRepository;
+
+/// How handlers hold a repository.
+pub type SharedRepository = Arc<dyn Repository>;
+
+/// Every write records an audit entry on the same transaction as the change.
+#[async_trait]
+pub trait Repository: Send + Sync {
+    /// Fails with 409 if a game with the same name exists.
+    async fn create_game(&self, actor: &Actor, name: &str) -> Result<Game, AppError>;
+
+    /// Games not in the trash, oldest first.
+    async fn list_games(&self) -> Result<Vec<Game>, AppError>;
+
+    /// Fails with 404 if the game does not exist or is in the trash.
+    async fn create_model_definition(
+        &self,
+        actor: &Actor,
+        definition: &CreateModelDefinition,
+    ) -> Result<ModelDefinition, AppError>;
+
+    /// Model definitions not in the trash, oldest first, with their tags.
+    async fn list_model_definitions(&self) -> Result<Vec<ModelDefinition>, AppError>;
+
+    async fn update_model_definition(
+        &self,
+        actor: &Actor,
+        id: Uuid,
+        changes: &UpdateModelDefinition,
+    ) -> Result<ModelDefinition, AppError>;
+
+    /// Fails with 404 if the model definition or the location does not exist.
+    async fn create_user_model(
+        &self,
+        actor: &Actor,
+        user_model: &CreateUserModel,
+    ) -> Result<UserModel, AppError>;
+
+    async fn get_user_model(&self, id: Uuid) -> Result<Option<UserModel>, AppError>;
+
+    /// Lots as `GET /user-models` lists them, optionally limited to a
+    /// location subtree or a single lot.
+    async fn list_user_models(
+        &self,
+        location_id: Option<Uuid>,
+        id: Option<Uuid>,
+    ) -> Result<Vec<UserModelListItem>, AppError>;
+
+    /// With `expected_versions`, the update only goes through if the lot is
+    /// still at one of them; otherwise it fails with 412.
+    async fn update_user_model(
+        &self,
+        actor: &Actor,
+        id: Uuid,
+        changes: &UpdateUserModel,
+        expected_versions: Option<Vec<i64>>,
+    ) -> Result<UserModel, AppError>;
+}
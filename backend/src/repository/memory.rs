@@ -0,0 +1,317 @@
+use std::{
+    sync::{Mutex, MutexGuard},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use axum::async_trait;
+use uuid::Uuid;
+
+use crate::{
+    audit::Actor, AppError, CreateModelDefinition, CreateUserModel, Game, GameSummary,
+    ModelDefinition, UpdateModelDefinition, UpdateUserModel, UserModel, UserModelListItem,
+};
+
+use super::Repository;
+
+/// Keeps everything in memory, for handler tests that should not need a
+/// database. There are no locations, tags, trash or audit log here: a lot
+/// given a location fails with 404, and the location filter matches nothing.
+#[derive(Default)]
+pub struct InMemoryRepository {
+    data: Mutex<Data>,
+}
+
+/// Rows in insertion order, which is also creation order.
+#[derive(Default)]
+struct Data {
+    games: Vec<StoredGame>,
+    model_definitions: Vec<StoredModelDefinition>,
+    user_models: Vec<UserModel>,
+}
+
+struct StoredGame {
+    id: Uuid,
+    name: String,
+    created_at: String,
+}
+
+struct StoredModelDefinition {
+    id: Uuid,
+    name: String,
+    game_id: Uuid,
+    points: Option<i64>,
+    unit_size: Option<i64>,
+    notes: Option<String>,
+}
+
+impl InMemoryRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn data(&self) -> Result<MutexGuard<'_, Data>, AppError> {
+        self.data
+            .lock()
+            .map_err(|_| AppError::internal_message("in-memory repository is poisoned"))
+    }
+}
+
+impl Data {
+    fn game(&self, id: Uuid) -> Option<&StoredGame> {
+        self.games.iter().find(|game| game.id == id)
+    }
+
+    fn model_definition(
+        &self,
+        stored: &StoredModelDefinition,
+    ) -> Result<ModelDefinition, AppError> {
+        let game = self
+            .game(stored.game_id)
+            .ok_or_else(|| AppError::internal_message("model definition without a game"))?;
+        Ok(ModelDefinition {
+            id: stored.id,
+            name: stored.name.clone(),
+            game: GameSummary {
+                id: game.id,
+                name: game.name.clone(),
+            },
+            points: stored.points,
+            unit_size: stored.unit_size,
+            notes: stored.notes.clone(),
+            tags: Vec::new(),
+        })
+    }
+}
+
+impl StoredGame {
+    fn to_game(&self) -> Game {
+        Game {
+            id: self.id,
+            name: self.name.clone(),
+            created_at: self.created_at.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl Repository for InMemoryRepository {
+    async fn create_game(&self, _actor: &Actor, name: &str) -> Result<Game, AppError> {
+        let mut data = self.data()?;
+        if data.games.iter().any(|game| game.name == name) {
+            return Err(AppError::conflict("game name already exists"));
+        }
+
+        let game = StoredGame {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            created_at: now(),
+        };
+        let created = game.to_game();
+        data.games.push(game);
+        Ok(created)
+    }
+
+    async fn list_games(&self) -> Result<Vec<Game>, AppError> {
+        let data = self.data()?;
+        Ok(data.games.iter().map(StoredGame::to_game).collect())
+    }
+
+    async fn create_model_definition(
+        &self,
+        _actor: &Actor,
+        definition: &CreateModelDefinition,
+    ) -> Result<ModelDefinition, AppError> {
+        let mut data = self.data()?;
+        if data.game(definition.game_id).is_none() {
+            return Err(AppError::not_found("game not found"));
+        }
+
+        let stored = StoredModelDefinition {
+            id: Uuid::new_v4(),
+            name: definition.name.clone(),
+            game_id: definition.game_id,
+            points: definition.points,
+            unit_size: definition.unit_size,
+            notes: None,
+        };
+        let created = data.model_definition(&stored)?;
+        data.model_definitions.push(stored);
+        Ok(created)
+    }
+
+    async fn list_model_definitions(&self) -> Result<Vec<ModelDefinition>, AppError> {
+        let data = self.data()?;
+        data.model_definitions
+            .iter()
+            .map(|stored| data.model_definition(stored))
+            .collect()
+    }
+
+    async fn update_model_definition(
+        &self,
+        _actor: &Actor,
+        id: Uuid,
+        changes: &UpdateModelDefinition,
+    ) -> Result<ModelDefinition, AppError> {
+        let mut data = self.data()?;
+        let Some(index) = data
+            .model_definitions
+            .iter()
+            .position(|stored| stored.id == id)
+        else {
+            return Err(AppError::not_found("model definition not found"));
+        };
+
+        let stored = &mut data.model_definitions[index];
+        if let Some(notes) = changes.notes.as_deref() {
+            stored.notes = non_empty(notes);
+        }
+        stored.points = changes.points.or(stored.points);
+        stored.unit_size = changes.unit_size.or(stored.unit_size);
+        data.model_definition(&data.model_definitions[index])
+    }
+
+    async fn create_user_model(
+        &self,
+        _actor: &Actor,
+        user_model: &CreateUserModel,
+    ) -> Result<UserModel, AppError> {
+        let mut data = self.data()?;
+        if !data
+            .model_definitions
+            .iter()
+            .any(|stored| stored.id == user_model.model_definition_id)
+        {
+            return Err(AppError::not_found("model definition not found"));
+        }
+        if user_model.location_id.is_some() {
+            return Err(AppError::not_found("location not found"));
+        }
+
+        let created_at = now();
+        let created = UserModel {
+            id: Uuid::new_v4(),
+            model_definition_id: user_model.model_definition_id,
+            quantity: user_model.quantity,
+            status: user_model.status,
+            location_id: None,
+            notes: None,
+            version: 1,
+            updated_at: created_at.clone(),
+            created_at,
+        };
+        data.user_models.push(created.clone());
+        Ok(created)
+    }
+
+    async fn get_user_model(&self, id: Uuid) -> Result<Option<UserModel>, AppError> {
+        let data = self.data()?;
+        Ok(data
+            .user_models
+            .iter()
+            .find(|model| model.id == id)
+            .cloned())
+    }
+
+    async fn list_user_models(
+        &self,
+        location_id: Option<Uuid>,
+        id: Option<Uuid>,
+    ) -> Result<Vec<UserModelListItem>, AppError> {
+        if location_id.is_some() {
+            return Ok(Vec::new());
+        }
+
+        let data = self.data()?;
+        data.user_models
+            .iter()
+            .filter(|model| id.is_none_or(|id| model.id == id))
+            .map(|model| {
+                let definition = data
+                    .model_definitions
+                    .iter()
+                    .find(|stored| stored.id == model.model_definition_id)
+                    .ok_or_else(|| AppError::internal_message("user model without a definition"))?;
+                let definition = data.model_definition(definition)?;
+                Ok(UserModelListItem {
+                    id: model.id,
+                    model_name: definition.name,
+                    game_name: definition.game.name,
+                    quantity: model.quantity,
+                    status: model.status,
+                    location_id: model.location_id,
+                    notes: model.notes.clone(),
+                    tags: Vec::new(),
+                    version: model.version,
+                })
+            })
+            .collect()
+    }
+
+    async fn update_user_model(
+        &self,
+        _actor: &Actor,
+        id: Uuid,
+        changes: &UpdateUserModel,
+        expected_versions: Option<Vec<i64>>,
+    ) -> Result<UserModel, AppError> {
+        let mut data = self.data()?;
+        let Some(model) = data.user_models.iter_mut().find(|model| model.id == id) else {
+            return Err(AppError::not_found("user model not found"));
+        };
+        if expected_versions.is_some_and(|versions| !versions.contains(&model.version)) {
+            return Err(AppError::precondition_failed("user model has been changed"));
+        }
+
+        model.status = changes.status.unwrap_or(model.status);
+        model.quantity = changes.quantity.unwrap_or(model.quantity);
+        if let Some(notes) = changes.notes.as_deref() {
+            model.notes = non_empty(notes);
+        }
+        model.updated_at = now();
+        model.version += 1;
+        Ok(model.clone())
+    }
+}
+
+/// Notes as the SQL backends store them: trimmed, with empty meaning none.
+fn non_empty(notes: &str) -> Option<String> {
+    let notes = notes.trim();
+    (!notes.is_empty()).then(|| notes.to_string())
+}
+
+/// The current UTC time in SQLite's `datetime('now')` format.
+fn now() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() as i64)
+        .unwrap_or_default();
+    let (year, month, day) = civil_from_days(seconds.div_euclid(24 * 60 * 60));
+    let time = seconds.rem_euclid(24 * 60 * 60);
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}",
+        time / 3600,
+        time / 60 % 60,
+        time % 60
+    )
+}
+
+/// The date a number of days after 1970-01-01 falls on, in the proleptic
+/// Gregorian calendar.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
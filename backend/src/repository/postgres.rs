@@ -0,0 +1,638 @@
+//! The `query!` macros check against a single database at compile time, and
+//! that is the SQLite one, so the queries here are checked by running the
+//! integration tests against PostgreSQL instead.
+
+use axum::async_trait;
+use sqlx::{
+    migrate::{MigrateError, Migrator},
+    postgres::PgPoolOptions,
+    FromRow, PgConnection, PgPool,
+};
+use uuid::Uuid;
+
+use crate::{
+    audit::{Action, Actor, Entity},
+    parse_i32, parse_status, parse_uuid, AppError, CreateModelDefinition, CreateUserModel, Game,
+    GameSummary, ModelDefinition, Status, UpdateModelDefinition, UpdateUserModel, UserModel,
+    UserModelListItem,
+};
+
+use super::Repository;
+
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations/postgres");
+
+/// Timestamps are stored in UTC and shown the way SQLite's `datetime()` writes them.
+const NOW: &str = "(clock_timestamp() AT TIME ZONE 'utc')";
+
+pub async fn connect_postgres(
+    database_url: &str,
+    max_connections: u32,
+) -> Result<PgPool, sqlx::Error> {
+    PgPoolOptions::new()
+        .max_connections(max_connections)
+        .connect(database_url)
+        .await
+}
+
+/// Applies any migrations from `migrations/postgres` the database has not seen yet.
+pub async fn migrate_postgres(pool: &PgPool) -> Result<(), MigrateError> {
+    MIGRATOR.run(pool).await
+}
+
+pub struct PostgresRepository {
+    pool: PgPool,
+}
+
+impl PostgresRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[derive(FromRow)]
+struct GameRow {
+    id: String,
+    name: String,
+    created_at: String,
+}
+
+#[derive(FromRow)]
+struct ModelDefinitionRow {
+    id: String,
+    name: String,
+    points: Option<i64>,
+    unit_size: Option<i64>,
+    notes: Option<String>,
+    game_id: String,
+    game_name: String,
+}
+
+#[derive(FromRow)]
+struct UserModelRow {
+    id: String,
+    model_definition_id: String,
+    quantity: i64,
+    status: String,
+    location_id: Option<String>,
+    notes: Option<String>,
+    version: i64,
+    created_at: String,
+    updated_at: String,
+}
+
+#[derive(FromRow)]
+struct UserModelListRow {
+    id: String,
+    model_name: String,
+    game_name: String,
+    quantity: i64,
+    status: String,
+    location_id: Option<String>,
+    notes: Option<String>,
+    version: i64,
+}
+
+const MODEL_DEFINITION_COLUMNS: &str = "
+    model_definitions.id,
+    model_definitions.name,
+    model_definitions.points,
+    model_definitions.unit_size,
+    model_definitions.notes,
+    games.id AS game_id,
+    games.name AS game_name
+";
+
+const USER_MODEL_COLUMNS: &str = "
+    id,
+    model_definition_id,
+    quantity,
+    status,
+    location_id,
+    notes,
+    version,
+    to_char(created_at, 'YYYY-MM-DD HH24:MI:SS') AS created_at,
+    to_char(updated_at, 'YYYY-MM-DD HH24:MI:SS') AS updated_at
+";
+
+/// Keeps lots whose model definition or game is in the trash out of reach.
+const LIVE_PARENTS: &str = "
+    model_definition_id IN (
+        SELECT model_definitions.id
+        FROM model_definitions
+        INNER JOIN games ON games.id = model_definitions.game_id
+        WHERE model_definitions.deleted_at IS NULL AND games.deleted_at IS NULL
+    )
+";
+
+#[async_trait]
+impl Repository for PostgresRepository {
+    async fn create_game(&self, actor: &Actor, name: &str) -> Result<Game, AppError> {
+        let id_value = Uuid::new_v4().to_string();
+        let mut transaction = self
+            .pool
+            .begin()
+            .await
+            .map_err(|error| AppError::internal("failed to start transaction", error))?;
+
+        sqlx::query(&format!(
+            "INSERT INTO games (id, name, created_at) VALUES ($1, $2, {NOW})"
+        ))
+        .bind(&id_value)
+        .bind(name)
+        .execute(&mut *transaction)
+        .await
+        .map_err(|error| match &error {
+            sqlx::Error::Database(db_error) if db_error.is_unique_violation() => {
+                AppError::conflict("game name already exists")
+            }
+            _ => AppError::internal("failed to create game", error),
+        })?;
+
+        record(&mut transaction, actor, Entity::Game, &id_value, Action::Create, None).await?;
+
+        let record = sqlx::query_as::<_, GameRow>(
+            "SELECT id, name, to_char(created_at, 'YYYY-MM-DD HH24:MI:SS') AS created_at
+             FROM games
+             WHERE id = $1",
+        )
+        .bind(&id_value)
+        .fetch_one(&mut *transaction)
+        .await
+        .map_err(|error| AppError::internal("failed to load game", error))?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(|error| AppError::internal("failed to commit transaction", error))?;
+
+        to_game(record)
+    }
+
+    async fn list_games(&self) -> Result<Vec<Game>, AppError> {
+        sqlx::query_as::<_, GameRow>(
+            "SELECT id, name, to_char(created_at, 'YYYY-MM-DD HH24:MI:SS') AS created_at
+             FROM games
+             WHERE deleted_at IS NULL
+             ORDER BY games.created_at",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|error| AppError::internal("failed to fetch games", error))?
+        .into_iter()
+        .map(to_game)
+        .collect()
+    }
+
+    async fn create_model_definition(
+        &self,
+        actor: &Actor,
+        definition: &CreateModelDefinition,
+    ) -> Result<ModelDefinition, AppError> {
+        let game_id_value = definition.game_id.to_string();
+        let id_value = Uuid::new_v4().to_string();
+        let mut transaction = self
+            .pool
+            .begin()
+            .await
+            .map_err(|error| AppError::internal("failed to start transaction", error))?;
+
+        let game: Option<(String,)> =
+            sqlx::query_as("SELECT id FROM games WHERE id = $1 AND deleted_at IS NULL")
+                .bind(&game_id_value)
+                .fetch_optional(&mut *transaction)
+                .await
+                .map_err(|error| AppError::internal("failed to validate game", error))?;
+        if game.is_none() {
+            return Err(AppError::not_found("game not found"));
+        }
+
+        sqlx::query(&format!(
+            "INSERT INTO model_definitions (id, name, game_id, points, unit_size, created_at)
+             VALUES ($1, $2, $3, $4, $5, {NOW})"
+        ))
+        .bind(&id_value)
+        .bind(&definition.name)
+        .bind(&game_id_value)
+        .bind(definition.points)
+        .bind(definition.unit_size)
+        .execute(&mut *transaction)
+        .await
+        .map_err(|error| AppError::internal("failed to create model definition", error))?;
+
+        record(
+            &mut transaction,
+            actor,
+            Entity::ModelDefinition,
+            &id_value,
+            Action::Create,
+            None,
+        )
+        .await?;
+
+        let record = load_model_definition(&mut transaction, &id_value)
+            .await?
+            .ok_or_else(|| AppError::internal_message("created model definition disappeared"))?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(|error| AppError::internal("failed to commit transaction", error))?;
+
+        Ok(record)
+    }
+
+    async fn list_model_definitions(&self) -> Result<Vec<ModelDefinition>, AppError> {
+        sqlx::query_as::<_, ModelDefinitionRow>(&format!(
+            "SELECT {MODEL_DEFINITION_COLUMNS}
+             FROM model_definitions
+             INNER JOIN games ON games.id = model_definitions.game_id
+             WHERE model_definitions.deleted_at IS NULL AND games.deleted_at IS NULL
+             ORDER BY model_definitions.created_at"
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|error| AppError::internal("failed to fetch model definitions", error))?
+        .into_iter()
+        .map(to_model_definition)
+        .collect()
+    }
+
+    async fn update_model_definition(
+        &self,
+        actor: &Actor,
+        id: Uuid,
+        changes: &UpdateModelDefinition,
+    ) -> Result<ModelDefinition, AppError> {
+        let id_value = id.to_string();
+        let notes_value = changes.notes.as_deref().map(str::trim);
+        let mut transaction = self
+            .pool
+            .begin()
+            .await
+            .map_err(|error| AppError::internal("failed to start transaction", error))?;
+
+        let Some(before) = snapshot(&mut transaction, Entity::ModelDefinition, &id_value).await?
+        else {
+            return Err(AppError::not_found("model definition not found"));
+        };
+
+        let result = sqlx::query(
+            "UPDATE model_definitions
+             SET notes = CASE WHEN $1::TEXT IS NULL THEN notes ELSE NULLIF($1, '') END,
+                 points = COALESCE($2, points),
+                 unit_size = COALESCE($3, unit_size)
+             WHERE id = $4 AND deleted_at IS NULL",
+        )
+        .bind(notes_value)
+        .bind(changes.points)
+        .bind(changes.unit_size)
+        .bind(&id_value)
+        .execute(&mut *transaction)
+        .await
+        .map_err(|error| AppError::internal("failed to update model definition", error))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("model definition not found"));
+        }
+
+        record(
+            &mut transaction,
+            actor,
+            Entity::ModelDefinition,
+            &id_value,
+            Action::Update,
+            Some(before),
+        )
+        .await?;
+
+        let record = load_model_definition(&mut transaction, &id_value)
+            .await?
+            .ok_or(AppError::not_found("model definition not found"))?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(|error| AppError::internal("failed to commit transaction", error))?;
+
+        Ok(record)
+    }
+
+    async fn create_user_model(
+        &self,
+        actor: &Actor,
+        user_model: &CreateUserModel,
+    ) -> Result<UserModel, AppError> {
+        if user_model.location_id.is_some() {
+            return Err(AppError::not_found("location not found"));
+        }
+
+        let model_definition_id_value = user_model.model_definition_id.to_string();
+        let id_value = Uuid::new_v4().to_string();
+        let mut transaction = self
+            .pool
+            .begin()
+            .await
+            .map_err(|error| AppError::internal("failed to start transaction", error))?;
+
+        let exists: Option<(String,)> =
+            sqlx::query_as(
+                "SELECT model_definitions.id
+                 FROM model_definitions
+                 INNER JOIN games ON games.id = model_definitions.game_id
+                 WHERE model_definitions.id = $1
+                   AND model_definitions.deleted_at IS NULL
+                   AND games.deleted_at IS NULL",
+            )
+                .bind(&model_definition_id_value)
+                .fetch_optional(&mut *transaction)
+                .await
+                .map_err(|error| {
+                    AppError::internal("failed to validate model definition", error)
+                })?;
+        if exists.is_none() {
+            return Err(AppError::not_found("model definition not found"));
+        }
+
+        sqlx::query(&format!(
+            "INSERT INTO user_models (
+                 id, model_definition_id, quantity, status, created_at, updated_at
+             )
+             VALUES ($1, $2, $3, $4, {NOW}, {NOW})"
+        ))
+        .bind(&id_value)
+        .bind(&model_definition_id_value)
+        .bind(i64::from(user_model.quantity))
+        .bind(user_model.status.as_str())
+        .execute(&mut *transaction)
+        .await
+        .map_err(|error| AppError::internal("failed to create user model", error))?;
+
+        record(
+            &mut transaction,
+            actor,
+            Entity::UserModel,
+            &id_value,
+            Action::Create,
+            None,
+        )
+        .await?;
+
+        let user_model = load_user_model(&mut transaction, &id_value)
+            .await?
+            .ok_or_else(|| AppError::internal_message("created user model disappeared"))?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(|error| AppError::internal("failed to commit transaction", error))?;
+
+        Ok(user_model)
+    }
+
+    async fn get_user_model(&self, id: Uuid) -> Result<Option<UserModel>, AppError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|error| AppError::internal("failed to load user model", error))?;
+        load_user_model(&mut conn, &id.to_string()).await
+    }
+
+    async fn list_user_models(
+        &self,
+        location_id: Option<Uuid>,
+        id: Option<Uuid>,
+    ) -> Result<Vec<UserModelListItem>, AppError> {
+        if location_id.is_some() {
+            return Ok(Vec::new());
+        }
+
+        let records = sqlx::query_as::<_, UserModelListRow>(
+            "SELECT
+                 user_models.id,
+                 model_definitions.name AS model_name,
+                 games.name AS game_name,
+                 user_models.quantity,
+                 user_models.status,
+                 user_models.location_id,
+                 user_models.notes,
+                 user_models.version
+             FROM user_models
+             INNER JOIN model_definitions
+                 ON model_definitions.id = user_models.model_definition_id
+             INNER JOIN games ON games.id = model_definitions.game_id
+             WHERE ($1::TEXT IS NULL OR user_models.id = $1)
+               AND user_models.deleted_at IS NULL
+               AND model_definitions.deleted_at IS NULL
+               AND games.deleted_at IS NULL
+             ORDER BY user_models.created_at",
+        )
+        .bind(id.map(|id| id.to_string()))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|error| AppError::internal("failed to fetch user models", error))?;
+
+        records
+            .into_iter()
+            .map(|record| {
+                Ok(UserModelListItem {
+                    id: parse_uuid(record.id)?,
+                    model_name: record.model_name,
+                    game_name: record.game_name,
+                    quantity: parse_i32(record.quantity)?,
+                    status: parse_status(&record.status)?,
+                    location_id: record.location_id.map(parse_uuid).transpose()?,
+                    notes: record.notes,
+                    tags: Vec::new(),
+                    version: record.version,
+                })
+            })
+            .collect()
+    }
+
+    async fn update_user_model(
+        &self,
+        actor: &Actor,
+        id: Uuid,
+        changes: &UpdateUserModel,
+        expected_versions: Option<Vec<i64>>,
+    ) -> Result<UserModel, AppError> {
+        let id_value = id.to_string();
+        let notes_value = changes.notes.as_deref().map(str::trim);
+        let mut transaction = self
+            .pool
+            .begin()
+            .await
+            .map_err(|error| AppError::internal("failed to start transaction", error))?;
+
+        // Locks the row, so the version cannot move before the update below.
+        let current: Option<(i64, String)> = sqlx::query_as(&format!(
+            "SELECT version, to_jsonb(user_models)::TEXT
+             FROM user_models
+             WHERE id = $1 AND deleted_at IS NULL AND {LIVE_PARENTS}
+             FOR UPDATE"
+        ))
+        .bind(&id_value)
+        .fetch_optional(&mut *transaction)
+        .await
+        .map_err(|error| AppError::internal("failed to snapshot row for audit log", error))?;
+        let Some((current_version, before)) = current else {
+            return Err(AppError::not_found("user model not found"));
+        };
+        if expected_versions.is_some_and(|versions| !versions.contains(&current_version)) {
+            return Err(AppError::precondition_failed("user model has been changed"));
+        }
+
+        sqlx::query(&format!(
+            "UPDATE user_models
+             SET status = COALESCE($1, status),
+                 quantity = COALESCE($2, quantity),
+                 notes = CASE WHEN $3::TEXT IS NULL THEN notes ELSE NULLIF($3, '') END,
+                 updated_at = {NOW},
+                 version = version + 1
+             WHERE id = $4 AND deleted_at IS NULL"
+        ))
+        .bind(changes.status.map(Status::as_str))
+        .bind(changes.quantity.map(i64::from))
+        .bind(notes_value)
+        .bind(&id_value)
+        .execute(&mut *transaction)
+        .await
+        .map_err(|error| AppError::internal("failed to update user model", error))?;
+
+        record(
+            &mut transaction,
+            actor,
+            Entity::UserModel,
+            &id_value,
+            Action::Update,
+            Some(before),
+        )
+        .await?;
+
+        let user_model = load_user_model(&mut transaction, &id_value)
+            .await?
+            .ok_or(AppError::not_found("user model not found"))?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(|error| AppError::internal("failed to commit transaction", error))?;
+
+        Ok(user_model)
+    }
+}
+
+/// Appends an audit entry with the row's current state as `after`, on the
+/// same transaction as the change.
+async fn record(
+    conn: &mut PgConnection,
+    actor: &Actor,
+    entity: Entity,
+    entity_id: &str,
+    action: Action,
+    before: Option<String>,
+) -> Result<(), AppError> {
+    let table = entity.table();
+    sqlx::query(&format!(
+        "INSERT INTO audit_log (occurred_at, actor, entity, entity_id, action, before, after)
+         SELECT {NOW}, $1, $2, $3, $4, $5::JSONB, to_jsonb({table})
+         FROM {table}
+         WHERE id = $3"
+    ))
+    .bind(&actor.0)
+    .bind(entity.as_str())
+    .bind(entity_id)
+    .bind(action.as_str())
+    .bind(before)
+    .execute(&mut *conn)
+    .await
+    .map_err(|error| AppError::internal("failed to write audit log", error))?;
+
+    Ok(())
+}
+
+/// A row as JSON text, or `None` if it does not exist.
+async fn snapshot(
+    conn: &mut PgConnection,
+    entity: Entity,
+    entity_id: &str,
+) -> Result<Option<String>, AppError> {
+    let table = entity.table();
+    sqlx::query_scalar(&format!(
+        "SELECT to_jsonb({table})::TEXT FROM {table} WHERE id = $1"
+    ))
+    .bind(entity_id)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|error| AppError::internal("failed to snapshot row for audit log", error))
+}
+
+async fn load_model_definition(
+    conn: &mut PgConnection,
+    id: &str,
+) -> Result<Option<ModelDefinition>, AppError> {
+    sqlx::query_as::<_, ModelDefinitionRow>(&format!(
+        "SELECT {MODEL_DEFINITION_COLUMNS}
+         FROM model_definitions
+         INNER JOIN games ON games.id = model_definitions.game_id
+         WHERE model_definitions.id = $1"
+    ))
+    .bind(id)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|error| AppError::internal("failed to load model definition", error))?
+    .map(to_model_definition)
+    .transpose()
+}
+
+async fn load_user_model(conn: &mut PgConnection, id: &str) -> Result<Option<UserModel>, AppError> {
+    let record = sqlx::query_as::<_, UserModelRow>(&format!(
+        "SELECT {USER_MODEL_COLUMNS} FROM user_models
+         WHERE id = $1 AND deleted_at IS NULL AND {LIVE_PARENTS}"
+    ))
+    .bind(id)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|error| AppError::internal("failed to load user model", error))?;
+
+    let Some(record) = record else {
+        return Ok(None);
+    };
+
+    Ok(Some(UserModel {
+        id: parse_uuid(record.id)?,
+        model_definition_id: parse_uuid(record.model_definition_id)?,
+        quantity: parse_i32(record.quantity)?,
+        status: parse_status(&record.status)?,
+        location_id: record.location_id.map(parse_uuid).transpose()?,
+        notes: record.notes,
+        version: record.version,
+        created_at: record.created_at,
+        updated_at: record.updated_at,
+    }))
+}
+
+fn to_game(record: GameRow) -> Result<Game, AppError> {
+    Ok(Game {
+        id: parse_uuid(record.id)?,
+        name: record.name,
+        created_at: record.created_at,
+    })
+}
+
+/// Tags live in SQLite only, so definitions here have none.
+fn to_model_definition(record: ModelDefinitionRow) -> Result<ModelDefinition, AppError> {
+    Ok(ModelDefinition {
+        id: parse_uuid(record.id)?,
+        name: record.name,
+        game: GameSummary {
+            id: parse_uuid(record.game_id)?,
+            name: record.game_name,
+        },
+        points: record.points,
+        unit_size: record.unit_size,
+        notes: record.notes,
+        tags: Vec::new(),
+    })
+}
@@ -0,0 +1,633 @@
+use axum::async_trait;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::{
+    audit::{self, Action, Actor, Entity},
+    map_db_error, parse_i32, parse_status, parse_uuid, tags, AppError, CreateModelDefinition,
+    CreateUserModel, Game, GameSummary, ModelDefinition, Status, UpdateModelDefinition,
+    UpdateUserModel, UserModel, UserModelListItem,
+};
+
+use super::Repository;
+
+/// Writes go through `writer`, the single writer connection; reads that do
+/// not follow a write use `reader`.
+pub struct SqliteRepository {
+    writer: SqlitePool,
+    reader: SqlitePool,
+}
+
+impl SqliteRepository {
+    pub fn new(writer: SqlitePool, reader: SqlitePool) -> Self {
+        Self { writer, reader }
+    }
+}
+
+#[async_trait]
+impl Repository for SqliteRepository {
+    async fn create_game(&self, actor: &Actor, name: &str) -> Result<Game, AppError> {
+        let id_value = Uuid::new_v4().to_string();
+        let mut transaction = self
+            .writer
+            .begin()
+            .await
+            .map_err(|error| AppError::internal("failed to start transaction", error))?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO games (id, name, created_at)
+            VALUES ($1, $2, datetime('now'))
+            "#,
+            id_value,
+            name
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(map_db_error)?;
+
+        audit::record_row(
+            &mut transaction,
+            actor,
+            Entity::Game,
+            &id_value,
+            Action::Create,
+            None,
+        )
+        .await?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(|error| AppError::internal("failed to commit transaction", error))?;
+
+        let record = sqlx::query!(
+            r#"
+            SELECT
+                id as "id!: String",
+                name,
+                created_at as "created_at!: String"
+            FROM games
+            WHERE id = $1
+            "#,
+            id_value
+        )
+        .fetch_one(&self.writer)
+        .await
+        .map_err(|error| AppError::internal("failed to load game", error))?;
+
+        Ok(Game {
+            id: parse_uuid(record.id)?,
+            name: record.name,
+            created_at: record.created_at,
+        })
+    }
+
+    async fn list_games(&self) -> Result<Vec<Game>, AppError> {
+        let records = sqlx::query!(
+            r#"
+            SELECT
+                id as "id!: String",
+                name,
+                created_at as "created_at!: String"
+            FROM games
+            WHERE deleted_at IS NULL
+            ORDER BY created_at
+            "#
+        )
+        .fetch_all(&self.reader)
+        .await
+        .map_err(|error| AppError::internal("failed to fetch games", error))?;
+
+        records
+            .into_iter()
+            .map(|record| {
+                Ok(Game {
+                    id: parse_uuid(record.id)?,
+                    name: record.name,
+                    created_at: record.created_at,
+                })
+            })
+            .collect()
+    }
+
+    async fn create_model_definition(
+        &self,
+        actor: &Actor,
+        definition: &CreateModelDefinition,
+    ) -> Result<ModelDefinition, AppError> {
+        let game_id_value = definition.game_id.to_string();
+        let game = sqlx::query!(
+            r#"
+            SELECT
+                id as "id!: String",
+                name
+            FROM games
+            WHERE id = $1 AND deleted_at IS NULL
+            "#,
+            game_id_value
+        )
+        .fetch_optional(&self.writer)
+        .await
+        .map_err(|error| AppError::internal("failed to validate game", error))?;
+
+        let game = match game {
+            Some(game) => game,
+            None => return Err(AppError::not_found("game not found")),
+        };
+
+        let id_value = Uuid::new_v4().to_string();
+        let mut transaction = self
+            .writer
+            .begin()
+            .await
+            .map_err(|error| AppError::internal("failed to start transaction", error))?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO model_definitions (id, name, game_id, points, unit_size, created_at)
+            VALUES ($1, $2, $3, $4, $5, datetime('now'))
+            "#,
+            id_value,
+            definition.name,
+            game_id_value,
+            definition.points,
+            definition.unit_size
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|error| AppError::internal("failed to create model definition", error))?;
+
+        audit::record_row(
+            &mut transaction,
+            actor,
+            Entity::ModelDefinition,
+            &id_value,
+            Action::Create,
+            None,
+        )
+        .await?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(|error| AppError::internal("failed to commit transaction", error))?;
+
+        let record = sqlx::query!(
+            r#"
+            SELECT
+                id as "id!: String",
+                name,
+                game_id as "game_id!: String",
+                points,
+                unit_size,
+                notes
+            FROM model_definitions
+            WHERE id = $1
+            "#,
+            id_value
+        )
+        .fetch_one(&self.writer)
+        .await
+        .map_err(|error| AppError::internal("failed to load model definition", error))?;
+
+        Ok(ModelDefinition {
+            id: parse_uuid(record.id)?,
+            name: record.name,
+            game: GameSummary {
+                id: parse_uuid(game.id)?,
+                name: game.name,
+            },
+            points: record.points,
+            unit_size: record.unit_size,
+            notes: record.notes,
+            tags: Vec::new(),
+        })
+    }
+
+    async fn list_model_definitions(&self) -> Result<Vec<ModelDefinition>, AppError> {
+        let records = sqlx::query!(
+            r#"
+            SELECT
+                model_definitions.id as "id!: String",
+                model_definitions.name,
+                model_definitions.points,
+                model_definitions.unit_size,
+                model_definitions.notes,
+                games.id AS "game_id!: String",
+                games.name AS game_name
+            FROM model_definitions
+            INNER JOIN games ON games.id = model_definitions.game_id
+            WHERE model_definitions.deleted_at IS NULL AND games.deleted_at IS NULL
+            ORDER BY model_definitions.created_at
+            "#
+        )
+        .fetch_all(&self.reader)
+        .await
+        .map_err(|error| AppError::internal("failed to fetch model definitions", error))?;
+
+        let mut tags = tags::model_definition_tags(&self.reader).await?;
+        records
+            .into_iter()
+            .map(|record| {
+                let tags = tags.remove(&record.id).unwrap_or_default();
+                Ok(ModelDefinition {
+                    id: parse_uuid(record.id)?,
+                    name: record.name,
+                    game: GameSummary {
+                        id: parse_uuid(record.game_id)?,
+                        name: record.game_name,
+                    },
+                    points: record.points,
+                    unit_size: record.unit_size,
+                    notes: record.notes,
+                    tags,
+                })
+            })
+            .collect()
+    }
+
+    async fn update_model_definition(
+        &self,
+        actor: &Actor,
+        id: Uuid,
+        changes: &UpdateModelDefinition,
+    ) -> Result<ModelDefinition, AppError> {
+        let id_value = id.to_string();
+        let notes_value = changes.notes.as_deref().map(str::trim);
+        let mut transaction = self
+            .writer
+            .begin()
+            .await
+            .map_err(|error| AppError::internal("failed to start transaction", error))?;
+
+        let before = audit::snapshot(&mut transaction, Entity::ModelDefinition, &id_value).await?;
+        if before.is_none() {
+            return Err(AppError::not_found("model definition not found"));
+        }
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE model_definitions
+            SET notes = CASE WHEN $1 IS NULL THEN notes ELSE NULLIF($1, '') END,
+                points = COALESCE($2, points),
+                unit_size = COALESCE($3, unit_size)
+            WHERE id = $4 AND deleted_at IS NULL
+            "#,
+            notes_value,
+            changes.points,
+            changes.unit_size,
+            id_value
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|error| AppError::internal("failed to update model definition", error))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("model definition not found"));
+        }
+
+        audit::record_row(
+            &mut transaction,
+            actor,
+            Entity::ModelDefinition,
+            &id_value,
+            Action::Update,
+            before,
+        )
+        .await?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(|error| AppError::internal("failed to commit transaction", error))?;
+
+        let record = sqlx::query!(
+            r#"
+            SELECT
+                model_definitions.id as "id!: String",
+                model_definitions.name,
+                model_definitions.points,
+                model_definitions.unit_size,
+                model_definitions.notes,
+                games.id AS "game_id!: String",
+                games.name AS game_name
+            FROM model_definitions
+            INNER JOIN games ON games.id = model_definitions.game_id
+            WHERE model_definitions.id = $1
+            "#,
+            id_value
+        )
+        .fetch_optional(&self.writer)
+        .await
+        .map_err(|error| AppError::internal("failed to load model definition", error))?;
+
+        let record = match record {
+            Some(record) => record,
+            None => return Err(AppError::not_found("model definition not found")),
+        };
+
+        let mut tags = tags::model_definition_tags(&self.writer).await?;
+        Ok(ModelDefinition {
+            tags: tags.remove(&record.id).unwrap_or_default(),
+            id: parse_uuid(record.id)?,
+            name: record.name,
+            game: GameSummary {
+                id: parse_uuid(record.game_id)?,
+                name: record.game_name,
+            },
+            points: record.points,
+            unit_size: record.unit_size,
+            notes: record.notes,
+        })
+    }
+
+    async fn create_user_model(
+        &self,
+        actor: &Actor,
+        user_model: &CreateUserModel,
+    ) -> Result<UserModel, AppError> {
+        let model_definition_id_value = user_model.model_definition_id.to_string();
+        let exists = sqlx::query!(
+            r#"
+            SELECT model_definitions.id
+            FROM model_definitions
+            INNER JOIN games ON games.id = model_definitions.game_id
+            WHERE model_definitions.id = $1
+              AND model_definitions.deleted_at IS NULL
+              AND games.deleted_at IS NULL
+            "#,
+            model_definition_id_value
+        )
+        .fetch_optional(&self.writer)
+        .await
+        .map_err(|error| AppError::internal("failed to validate model definition", error))?;
+
+        if exists.is_none() {
+            return Err(AppError::not_found("model definition not found"));
+        }
+
+        let location_id_value = user_model.location_id.map(|id| id.to_string());
+        if let Some(location_id) = location_id_value.as_deref() {
+            let exists = sqlx::query!(
+                r#"
+                SELECT id
+                FROM locations
+                WHERE id = $1
+                "#,
+                location_id
+            )
+            .fetch_optional(&self.writer)
+            .await
+            .map_err(|error| AppError::internal("failed to validate location", error))?;
+
+            if exists.is_none() {
+                return Err(AppError::not_found("location not found"));
+            }
+        }
+
+        let id_value = Uuid::new_v4().to_string();
+        let status_value = user_model.status.as_str();
+        let mut transaction = self
+            .writer
+            .begin()
+            .await
+            .map_err(|error| AppError::internal("failed to start transaction", error))?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO user_models (
+                id, model_definition_id, quantity, status, location_id, created_at, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, datetime('now'), datetime('now'))
+            "#,
+            id_value,
+            model_definition_id_value,
+            user_model.quantity,
+            status_value,
+            location_id_value
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|error| AppError::internal("failed to create user model", error))?;
+
+        audit::record_row(
+            &mut transaction,
+            actor,
+            Entity::UserModel,
+            &id_value,
+            Action::Create,
+            None,
+        )
+        .await?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(|error| AppError::internal("failed to commit transaction", error))?;
+
+        load_user_model(&self.writer, &id_value)
+            .await?
+            .ok_or_else(|| AppError::internal_message("created user model disappeared"))
+    }
+
+    async fn get_user_model(&self, id: Uuid) -> Result<Option<UserModel>, AppError> {
+        load_user_model(&self.reader, &id.to_string()).await
+    }
+
+    async fn list_user_models(
+        &self,
+        location_id: Option<Uuid>,
+        id: Option<Uuid>,
+    ) -> Result<Vec<UserModelListItem>, AppError> {
+        let location_id = location_id.map(|id| id.to_string());
+        let id = id.map(|id| id.to_string());
+        let records = sqlx::query!(
+            r#"
+            WITH RECURSIVE scope(id) AS (
+                SELECT id FROM locations WHERE id = $1
+                UNION ALL
+                SELECT locations.id
+                FROM locations
+                INNER JOIN scope ON locations.parent_id = scope.id
+            )
+            SELECT
+                user_models.id as "id!: String",
+                model_definitions.name AS model_name,
+                games.name AS game_name,
+                user_models.quantity as "quantity!: i64",
+                user_models.status,
+                user_models.location_id,
+                user_models.notes,
+                user_models.version
+            FROM user_models
+            INNER JOIN model_definitions ON model_definitions.id = user_models.model_definition_id
+            INNER JOIN games ON games.id = model_definitions.game_id
+            WHERE ($1 IS NULL OR user_models.location_id IN (SELECT id FROM scope))
+              AND ($2 IS NULL OR user_models.id = $2)
+              AND user_models.deleted_at IS NULL
+              AND model_definitions.deleted_at IS NULL
+              AND games.deleted_at IS NULL
+            ORDER BY user_models.created_at
+            "#,
+            location_id,
+            id
+        )
+        .fetch_all(&self.reader)
+        .await
+        .map_err(|error| AppError::internal("failed to fetch user models", error))?;
+
+        let mut tags = tags::user_model_tags(&self.reader).await?;
+        records
+            .into_iter()
+            .map(|record| {
+                let status = parse_status(&record.status)?;
+                let tags = tags.remove(&record.id).unwrap_or_default();
+                Ok(UserModelListItem {
+                    id: parse_uuid(record.id)?,
+                    model_name: record.model_name,
+                    game_name: record.game_name,
+                    quantity: parse_i32(record.quantity)?,
+                    status,
+                    location_id: record.location_id.map(parse_uuid).transpose()?,
+                    notes: record.notes,
+                    tags,
+                    version: record.version,
+                })
+            })
+            .collect()
+    }
+
+    async fn update_user_model(
+        &self,
+        actor: &Actor,
+        id: Uuid,
+        changes: &UpdateUserModel,
+        expected_versions: Option<Vec<i64>>,
+    ) -> Result<UserModel, AppError> {
+        let status_value = changes.status.map(Status::as_str);
+        let notes_value = changes.notes.as_deref().map(str::trim);
+        let id_value = id.to_string();
+        let mut transaction = self
+            .writer
+            .begin()
+            .await
+            .map_err(|error| AppError::internal("failed to start transaction", error))?;
+
+        let live = sqlx::query!(
+            r#"
+            SELECT user_models.id
+            FROM user_models
+            INNER JOIN model_definitions ON model_definitions.id = user_models.model_definition_id
+            INNER JOIN games ON games.id = model_definitions.game_id
+            WHERE user_models.id = $1
+              AND model_definitions.deleted_at IS NULL
+              AND games.deleted_at IS NULL
+            "#,
+            id_value
+        )
+        .fetch_optional(&mut *transaction)
+        .await
+        .map_err(|error| AppError::internal("failed to validate user model", error))?;
+
+        let before = audit::snapshot(&mut transaction, Entity::UserModel, &id_value).await?;
+        let Some(current_version) = before
+            .as_ref()
+            .filter(|before| live.is_some() && before["deleted_at"].is_null())
+            .and_then(|before| before["version"].as_i64())
+        else {
+            return Err(AppError::not_found("user model not found"));
+        };
+        if expected_versions.is_some_and(|versions| !versions.contains(&current_version)) {
+            return Err(AppError::precondition_failed("user model has been changed"));
+        }
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE user_models
+            SET status = COALESCE($1, status),
+                quantity = COALESCE($2, quantity),
+                notes = CASE WHEN $3 IS NULL THEN notes ELSE NULLIF($3, '') END,
+                updated_at = datetime('now'),
+                version = version + 1
+            WHERE id = $4 AND deleted_at IS NULL AND version = $5
+            "#,
+            status_value,
+            changes.quantity,
+            notes_value,
+            id_value,
+            current_version
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|error| AppError::internal("failed to update user model", error))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::precondition_failed("user model has been changed"));
+        }
+
+        audit::record_row(
+            &mut transaction,
+            actor,
+            Entity::UserModel,
+            &id_value,
+            Action::Update,
+            before,
+        )
+        .await?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(|error| AppError::internal("failed to commit transaction", error))?;
+
+        load_user_model(&self.writer, &id_value)
+            .await?
+            .ok_or(AppError::not_found("user model not found"))
+    }
+}
+
+async fn load_user_model(pool: &SqlitePool, id: &str) -> Result<Option<UserModel>, AppError> {
+    let record = sqlx::query!(
+        r#"
+        SELECT
+            user_models.id as "id!: String",
+            user_models.model_definition_id as "model_definition_id!: String",
+            user_models.quantity as "quantity!: i64",
+            user_models.status,
+            user_models.location_id,
+            user_models.notes,
+            user_models.version,
+            user_models.created_at as "created_at!: String",
+            user_models.updated_at as "updated_at!: String"
+        FROM user_models
+        INNER JOIN model_definitions ON model_definitions.id = user_models.model_definition_id
+        INNER JOIN games ON games.id = model_definitions.game_id
+        WHERE user_models.id = $1
+          AND user_models.deleted_at IS NULL
+          AND model_definitions.deleted_at IS NULL
+          AND games.deleted_at IS NULL
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|error| AppError::internal("failed to load user model", error))?;
+
+    let Some(record) = record else {
+        return Ok(None);
+    };
+
+    let status = parse_status(&record.status)?;
+    Ok(Some(UserModel {
+        id: parse_uuid(record.id)?,
+        model_definition_id: parse_uuid(record.model_definition_id)?,
+        quantity: parse_i32(record.quantity)?,
+        status,
+        location_id: record.location_id.map(parse_uuid).transpose()?,
+        notes: record.notes,
+        version: record.version,
+        created_at: record.created_at,
+        updated_at: record.updated_at,
+    }))
+}
@@ -0,0 +1,580 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    audit::{self, Action, Actor, Entity},
+    parse_uuid, AppError, AppState, ErrorBody,
+};
+
+#[derive(Deserialize, ToSchema)]
+pub(crate) struct StartSession {
+    #[serde(default)]
+    user_model_ids: Vec<Uuid>,
+    note: Option<String>,
+    /// Backdates the start; defaults to now.
+    started_at: Option<String>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub(crate) struct StopSession {
+    /// Replaces the note when present.
+    note: Option<String>,
+    /// Defaults to now.
+    stopped_at: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct Session {
+    id: Uuid,
+    started_at: String,
+    stopped_at: Option<String>,
+    note: Option<String>,
+    user_model_ids: Vec<Uuid>,
+    /// `None` while the session is still running.
+    duration_hours: Option<f64>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct SessionStats {
+    by_model: Vec<ModelHours>,
+    by_game: Vec<GameHours>,
+    by_month: Vec<MonthHours>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct ModelHours {
+    user_model_id: Uuid,
+    model_name: String,
+    hours: f64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct GameHours {
+    game_id: Uuid,
+    game_name: String,
+    hours: f64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct MonthHours {
+    /// `YYYY-MM` of the session start.
+    month: String,
+    hours: f64,
+}
+
+#[utoipa::path(
+    post,
+    path = "/sessions/start",
+    tag = "sessions",
+    request_body = StartSession,
+    responses(
+        (status = 201, body = Session),
+        (status = 404, description = "A lot does not exist", body = ErrorBody),
+        (status = 409, description = "A session is running or one ends after the start", body = ErrorBody),
+        (status = 422, description = "`started_at` is not a date and time", body = ErrorBody),
+    )
+)]
+pub(crate) async fn start_session(
+    State(state): State<AppState>,
+    actor: Actor,
+    Json(payload): Json<StartSession>,
+) -> Result<(StatusCode, Json<Session>), AppError> {
+    tracing::info!(lots = payload.user_model_ids.len(), "starting session");
+    let started_at = normalize_timestamp(&state.pool, payload.started_at.as_deref()).await?;
+
+    let mut transaction = state
+        .pool
+        .begin()
+        .await
+        .map_err(|error| AppError::internal("failed to start transaction", error))?;
+
+    // Until it is stopped, the new session runs from `started_at` on, so any
+    // session that ends after that overlaps it.
+    let overlapping = sqlx::query!(
+        r#"
+        SELECT stopped_at
+        FROM sessions
+        WHERE stopped_at IS NULL OR stopped_at > $1
+        ORDER BY stopped_at IS NULL DESC
+        LIMIT 1
+        "#,
+        started_at
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .map_err(|error| AppError::internal("failed to check for overlapping sessions", error))?;
+
+    match overlapping {
+        Some(record) if record.stopped_at.is_none() => {
+            return Err(AppError::conflict("a session is already running"));
+        }
+        Some(_) => {
+            return Err(AppError::conflict("session overlaps another session"));
+        }
+        None => {}
+    }
+
+    let id = Uuid::new_v4();
+    let id_value = id.to_string();
+    let note = payload.note.filter(|note| !note.trim().is_empty());
+    sqlx::query!(
+        r#"
+        INSERT INTO sessions (id, started_at, note)
+        VALUES ($1, $2, $3)
+        "#,
+        id_value,
+        started_at,
+        note
+    )
+    .execute(&mut *transaction)
+    .await
+    .map_err(map_session_error)?;
+
+    for user_model_id in &payload.user_model_ids {
+        let user_model_id_value = user_model_id.to_string();
+        let live = sqlx::query!(
+            r#"
+            SELECT user_models.id
+            FROM user_models
+            INNER JOIN model_definitions ON model_definitions.id = user_models.model_definition_id
+            INNER JOIN games ON games.id = model_definitions.game_id
+            WHERE user_models.id = $1
+              AND user_models.deleted_at IS NULL
+              AND model_definitions.deleted_at IS NULL
+              AND games.deleted_at IS NULL
+            "#,
+            user_model_id_value
+        )
+        .fetch_optional(&mut *transaction)
+        .await
+        .map_err(|error| AppError::internal("failed to validate user model", error))?;
+        if live.is_none() {
+            return Err(AppError::not_found("user model not found"));
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT OR IGNORE INTO session_user_models (session_id, user_model_id)
+            VALUES ($1, $2)
+            "#,
+            id_value,
+            user_model_id_value
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(map_session_error)?;
+    }
+
+    audit::record_row(
+        &mut transaction,
+        &actor,
+        Entity::Session,
+        &id_value,
+        Action::Create,
+        None,
+    )
+    .await?;
+
+    transaction
+        .commit()
+        .await
+        .map_err(|error| AppError::internal("failed to commit transaction", error))?;
+    state
+        .events
+        .changed(Entity::Session, &id_value, Action::Create);
+
+    let session = load_sessions(&state.pool, Some(&id_value))
+        .await?
+        .pop()
+        .ok_or_else(|| AppError::internal_message("failed to load session"))?;
+
+    Ok((StatusCode::CREATED, Json(session)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/sessions/{id}/stop",
+    tag = "sessions",
+    params(("id" = Uuid, Path)),
+    request_body = StopSession,
+    responses(
+        (status = 200, body = Session),
+        (status = 404, body = ErrorBody),
+        (status = 409, description = "The session is already stopped or would overlap another", body = ErrorBody),
+        (status = 422, description = "`stopped_at` is not a date and time, or before the start", body = ErrorBody),
+    )
+)]
+pub(crate) async fn stop_session(
+    State(state): State<AppState>,
+    actor: Actor,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<StopSession>,
+) -> Result<Json<Session>, AppError> {
+    tracing::info!(session_id = %id, "stopping session");
+    let id_value = id.to_string();
+    let stopped_at = normalize_timestamp(&state.pool, payload.stopped_at.as_deref()).await?;
+    let note = payload
+        .note
+        .map(|note| note.trim().to_string())
+        .unwrap_or_default();
+    let mut transaction = state
+        .pool
+        .begin()
+        .await
+        .map_err(|error| AppError::internal("failed to start transaction", error))?;
+
+    let record = sqlx::query!(
+        r#"
+        SELECT started_at, stopped_at
+        FROM sessions
+        WHERE id = $1
+        "#,
+        id_value
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .map_err(|error| AppError::internal("failed to load session", error))?;
+
+    let Some(record) = record else {
+        return Err(AppError::not_found("session not found"));
+    };
+    if record.stopped_at.is_some() {
+        return Err(AppError::conflict("session is already stopped"));
+    }
+    if stopped_at < record.started_at {
+        return Err(AppError::unprocessable(
+            "session cannot stop before it started",
+        ));
+    }
+
+    let overlapping = sqlx::query!(
+        r#"
+        SELECT id
+        FROM sessions
+        WHERE id != $1
+            AND started_at < $2
+            AND (stopped_at IS NULL OR stopped_at > $3)
+        LIMIT 1
+        "#,
+        id_value,
+        stopped_at,
+        record.started_at
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .map_err(|error| AppError::internal("failed to check for overlapping sessions", error))?;
+    if overlapping.is_some() {
+        return Err(AppError::conflict("session overlaps another session"));
+    }
+
+    let before = audit::snapshot(&mut transaction, Entity::Session, &id_value).await?;
+    let result = sqlx::query!(
+        r#"
+        UPDATE sessions
+        SET stopped_at = $1,
+            note = CASE WHEN $2 = '' THEN note ELSE $2 END
+        WHERE id = $3 AND stopped_at IS NULL
+        "#,
+        stopped_at,
+        note,
+        id_value
+    )
+    .execute(&mut *transaction)
+    .await
+    .map_err(|error| AppError::internal("failed to stop session", error))?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::conflict("session is already stopped"));
+    }
+
+    audit::record_row(
+        &mut transaction,
+        &actor,
+        Entity::Session,
+        &id_value,
+        Action::Update,
+        before,
+    )
+    .await?;
+
+    transaction
+        .commit()
+        .await
+        .map_err(|error| AppError::internal("failed to commit transaction", error))?;
+    state
+        .events
+        .changed(Entity::Session, &id_value, Action::Update);
+
+    let session = load_sessions(&state.pool, Some(&id_value))
+        .await?
+        .pop()
+        .ok_or_else(|| AppError::internal_message("failed to load session"))?;
+
+    Ok(Json(session))
+}
+
+/// Returns the running session, or `null` when the stopwatch is idle.
+#[utoipa::path(
+    get,
+    path = "/sessions/active",
+    tag = "sessions",
+    responses((status = 200, description = "The running session, or `null`", body = Option<Session>))
+)]
+pub(crate) async fn active_session(
+    State(state): State<AppState>,
+) -> Result<Json<Option<Session>>, AppError> {
+    let record = sqlx::query!(
+        r#"
+        SELECT id as "id!: String"
+        FROM sessions
+        WHERE stopped_at IS NULL
+        "#
+    )
+    .fetch_optional(&state.read_pool)
+    .await
+    .map_err(|error| AppError::internal("failed to load active session", error))?;
+
+    let Some(record) = record else {
+        return Ok(Json(None));
+    };
+
+    let session = load_sessions(&state.read_pool, Some(&record.id)).await?.pop();
+    Ok(Json(session))
+}
+
+#[utoipa::path(
+    get,
+    path = "/sessions",
+    tag = "sessions",
+    responses((status = 200, body = Vec<Session>))
+)]
+pub(crate) async fn list_sessions(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<Session>>, AppError> {
+    tracing::info!("listing sessions");
+    let sessions = load_sessions(&state.read_pool, None).await?;
+    tracing::info!(count = sessions.len(), "listed sessions");
+    Ok(Json(sessions))
+}
+
+/// Hours spent painting, counting only finished sessions. A session linked to
+/// several lots is split evenly between them, so per-model and per-game totals
+/// add up to the same hours as the monthly totals (minus unlinked sessions).
+#[utoipa::path(
+    get,
+    path = "/sessions/stats",
+    tag = "sessions",
+    responses((status = 200, description = "Hours spent, from stopped sessions", body = SessionStats))
+)]
+pub(crate) async fn session_stats(
+    State(state): State<AppState>,
+) -> Result<Json<SessionStats>, AppError> {
+    tracing::info!("computing session stats");
+    let model_records = sqlx::query!(
+        r#"
+        WITH durations AS (
+            SELECT
+                sessions.id,
+                (julianday(sessions.stopped_at) - julianday(sessions.started_at)) * 24.0
+                    / (SELECT COUNT(*) FROM session_user_models WHERE session_id = sessions.id)
+                    AS hours
+            FROM sessions
+            WHERE sessions.stopped_at IS NOT NULL
+        )
+        SELECT
+            user_models.id as "user_model_id!: String",
+            model_definitions.name AS model_name,
+            SUM(durations.hours) as "hours!: f64"
+        FROM durations
+        INNER JOIN session_user_models ON session_user_models.session_id = durations.id
+        INNER JOIN user_models ON user_models.id = session_user_models.user_model_id
+        INNER JOIN model_definitions ON model_definitions.id = user_models.model_definition_id
+        INNER JOIN games ON games.id = model_definitions.game_id
+        WHERE user_models.deleted_at IS NULL
+          AND model_definitions.deleted_at IS NULL
+          AND games.deleted_at IS NULL
+        GROUP BY user_models.id, model_definitions.name
+        ORDER BY 3 DESC
+        "#
+    )
+    .fetch_all(&state.read_pool)
+    .await
+    .map_err(|error| AppError::internal("failed to aggregate hours per model", error))?;
+
+    let game_records = sqlx::query!(
+        r#"
+        WITH durations AS (
+            SELECT
+                sessions.id,
+                (julianday(sessions.stopped_at) - julianday(sessions.started_at)) * 24.0
+                    / (SELECT COUNT(*) FROM session_user_models WHERE session_id = sessions.id)
+                    AS hours
+            FROM sessions
+            WHERE sessions.stopped_at IS NOT NULL
+        )
+        SELECT
+            games.id as "game_id!: String",
+            games.name AS game_name,
+            SUM(durations.hours) as "hours!: f64"
+        FROM durations
+        INNER JOIN session_user_models ON session_user_models.session_id = durations.id
+        INNER JOIN user_models ON user_models.id = session_user_models.user_model_id
+        INNER JOIN model_definitions ON model_definitions.id = user_models.model_definition_id
+        INNER JOIN games ON games.id = model_definitions.game_id
+        WHERE user_models.deleted_at IS NULL
+          AND model_definitions.deleted_at IS NULL
+          AND games.deleted_at IS NULL
+        GROUP BY games.id, games.name
+        ORDER BY 3 DESC
+        "#
+    )
+    .fetch_all(&state.read_pool)
+    .await
+    .map_err(|error| AppError::internal("failed to aggregate hours per game", error))?;
+
+    let month_records = sqlx::query!(
+        r#"
+        SELECT
+            strftime('%Y-%m', started_at) as "month!: String",
+            SUM((julianday(stopped_at) - julianday(started_at)) * 24.0) as "hours!: f64"
+        FROM sessions
+        WHERE stopped_at IS NOT NULL
+        GROUP BY 1
+        ORDER BY 1
+        "#
+    )
+    .fetch_all(&state.read_pool)
+    .await
+    .map_err(|error| AppError::internal("failed to aggregate hours per month", error))?;
+
+    let by_model = model_records
+        .into_iter()
+        .map(|record| {
+            Ok(ModelHours {
+                user_model_id: parse_uuid(record.user_model_id)?,
+                model_name: record.model_name,
+                hours: record.hours,
+            })
+        })
+        .collect::<Result<Vec<_>, AppError>>()?;
+
+    let by_game = game_records
+        .into_iter()
+        .map(|record| {
+            Ok(GameHours {
+                game_id: parse_uuid(record.game_id)?,
+                game_name: record.game_name,
+                hours: record.hours,
+            })
+        })
+        .collect::<Result<Vec<_>, AppError>>()?;
+
+    let by_month = month_records
+        .into_iter()
+        .map(|record| MonthHours {
+            month: record.month,
+            hours: record.hours,
+        })
+        .collect();
+
+    Ok(Json(SessionStats {
+        by_model,
+        by_game,
+        by_month,
+    }))
+}
+
+async fn load_sessions(pool: &SqlitePool, id: Option<&str>) -> Result<Vec<Session>, AppError> {
+    let records = sqlx::query!(
+        r#"
+        SELECT
+            id as "id!: String",
+            started_at,
+            stopped_at,
+            note,
+            (julianday(stopped_at) - julianday(started_at)) * 24.0 as "duration_hours?: f64"
+        FROM sessions
+        WHERE $1 IS NULL OR id = $1
+        ORDER BY started_at DESC
+        "#,
+        id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|error| AppError::internal("failed to fetch sessions", error))?;
+
+    let links = sqlx::query!(
+        r#"
+        SELECT
+            session_id as "session_id!: String",
+            user_model_id as "user_model_id!: String"
+        FROM session_user_models
+        WHERE $1 IS NULL OR session_id = $1
+        "#,
+        id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|error| AppError::internal("failed to fetch session lots", error))?;
+
+    let mut lots: HashMap<String, Vec<Uuid>> = HashMap::new();
+    for link in links {
+        lots.entry(link.session_id)
+            .or_default()
+            .push(parse_uuid(link.user_model_id)?);
+    }
+
+    records
+        .into_iter()
+        .map(|record| {
+            Ok(Session {
+                user_model_ids: lots.remove(&record.id).unwrap_or_default(),
+                id: parse_uuid(record.id)?,
+                started_at: record.started_at,
+                stopped_at: record.stopped_at,
+                note: record.note,
+                duration_hours: record.duration_hours,
+            })
+        })
+        .collect()
+}
+
+/// Normalises a client timestamp to SQLite's `YYYY-MM-DD HH:MM:SS` (UTC) so
+/// stored values compare correctly as text; `None` means now.
+async fn normalize_timestamp(pool: &SqlitePool, value: Option<&str>) -> Result<String, AppError> {
+    let record = sqlx::query!(
+        r#"
+        SELECT datetime(COALESCE($1, 'now')) as "timestamp?: String"
+        "#,
+        value
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|error| AppError::internal("failed to parse timestamp", error))?;
+
+    record
+        .timestamp
+        .ok_or_else(|| AppError::unprocessable("timestamp must be an ISO 8601 date and time"))
+}
+
+fn map_session_error(error: sqlx::Error) -> AppError {
+    if let sqlx::Error::Database(db_error) = &error {
+        let message = db_error.message();
+        if message.contains("UNIQUE constraint failed") {
+            return AppError::conflict("a session is already running");
+        }
+        if message.contains("FOREIGN KEY constraint failed") {
+            return AppError::not_found("user model not found");
+        }
+    }
+
+    AppError::internal("failed to start session", error)
+}
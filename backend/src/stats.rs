@@ -0,0 +1,99 @@
+use axum::{extract::State, Json};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::{AppError, AppState};
+
+/// Totals for the whole collection; trashed items only count towards `in_trash`.
+#[derive(Serialize, ToSchema)]
+pub(crate) struct CollectionStats {
+    games: i64,
+    model_definitions: i64,
+    /// Number of lots.
+    user_models: i64,
+    /// Number of models across all lots.
+    models: i64,
+    models_by_status: ModelsByStatus,
+    in_trash: i64,
+    users: i64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct ModelsByStatus {
+    unassembled: i64,
+    assembled: i64,
+    painted: i64,
+}
+
+#[utoipa::path(
+    get,
+    path = "/stats",
+    tag = "stats",
+    responses((status = 200, body = CollectionStats))
+)]
+pub(crate) async fn collection_stats(
+    State(state): State<AppState>,
+) -> Result<Json<CollectionStats>, AppError> {
+    tracing::info!("computing collection stats");
+    let record = sqlx::query!(
+        r#"
+        WITH live_user_models AS (
+            SELECT user_models.quantity, user_models.status
+            FROM user_models
+            INNER JOIN model_definitions ON model_definitions.id = user_models.model_definition_id
+            INNER JOIN games ON games.id = model_definitions.game_id
+            WHERE user_models.deleted_at IS NULL
+              AND model_definitions.deleted_at IS NULL
+              AND games.deleted_at IS NULL
+        )
+        SELECT
+            (SELECT COUNT(*) FROM games WHERE deleted_at IS NULL) as "games!: i64",
+            (
+                SELECT COUNT(*)
+                FROM model_definitions
+                INNER JOIN games ON games.id = model_definitions.game_id
+                WHERE model_definitions.deleted_at IS NULL AND games.deleted_at IS NULL
+            ) as "model_definitions!: i64",
+            (SELECT COUNT(*) FROM live_user_models) as "user_models!: i64",
+            (SELECT COALESCE(SUM(quantity), 0) FROM live_user_models) as "models!: i64",
+            (
+                SELECT COALESCE(SUM(quantity), 0)
+                FROM live_user_models
+                WHERE status = 'unassembled'
+            ) as "unassembled!: i64",
+            (
+                SELECT COALESCE(SUM(quantity), 0)
+                FROM live_user_models
+                WHERE status = 'assembled'
+            ) as "assembled!: i64",
+            (
+                SELECT COALESCE(SUM(quantity), 0)
+                FROM live_user_models
+                WHERE status = 'painted'
+            ) as "painted!: i64",
+            (
+                (SELECT COUNT(*) FROM games WHERE deleted_at IS NOT NULL)
+                + (SELECT COUNT(*) FROM model_definitions WHERE deleted_at IS NOT NULL)
+                + (SELECT COUNT(*) FROM user_models WHERE deleted_at IS NOT NULL)
+            ) as "in_trash!: i64",
+            (SELECT COUNT(*) FROM users) as "users!: i64"
+        "#
+    )
+    .fetch_one(&state.read_pool)
+    .await
+    .map_err(|error| AppError::internal("failed to compute collection stats", error))?;
+
+    Ok(Json(CollectionStats {
+        games: record.games,
+        model_definitions: record.model_definitions,
+        user_models: record.user_models,
+        models: record.models,
+        models_by_status: ModelsByStatus {
+            unassembled: record.unassembled,
+            assembled: record.assembled,
+            painted: record.painted,
+        },
+        in_trash: record.in_trash,
+        users: record.users,
+    }))
+}
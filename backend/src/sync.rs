@@ -0,0 +1,629 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{Acquire, SqliteConnection};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    audit::{self, Action, Actor, Entity},
+    events, parse_uuid, validate_points, AppError, AppState, ErrorBody, Status,
+};
+
+const DEFAULT_LIMIT: i64 = 500;
+const MAX_LIMIT: i64 = 1000;
+
+/// Most changes a single `POST /sync/push` may carry.
+pub(crate) const MAX_BATCH: usize = 500;
+
+/// Entities offline clients keep a copy of.
+const SYNCED: [Entity; 3] = [Entity::Game, Entity::ModelDefinition, Entity::UserModel];
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(crate) struct ChangesQuery {
+    /// The `cursor` of the previous page; leave it out to start from the beginning.
+    pub(crate) since: Option<i64>,
+    pub(crate) limit: Option<i64>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct ChangeFeed {
+    pub(crate) changes: Vec<Change>,
+    /// Pass as `since` to fetch whatever comes next.
+    pub(crate) cursor: i64,
+    pub(crate) has_more: bool,
+}
+
+/// The latest state of one record.
+#[derive(Serialize, ToSchema)]
+pub(crate) struct Change {
+    pub(crate) entity: Entity,
+    pub(crate) id: Uuid,
+    /// The record's place in the change sequence; send it back as `base_version`.
+    version: i64,
+    pub(crate) changed_at: String,
+    /// Tombstone: the record is in the trash or has been purged.
+    pub(crate) deleted: bool,
+    /// The row as stored, or `None` once it has been purged.
+    pub(crate) data: Option<Value>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub(crate) struct PushRequest {
+    pub(crate) changes: Vec<PushChange>,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub(crate) struct PushChange {
+    pub(crate) entity: Entity,
+    /// Chosen by the client for new records.
+    pub(crate) id: Uuid,
+    /// The `version` the client last saw, or `None` for a record it created.
+    pub(crate) base_version: Option<i64>,
+    /// When the change was made on the client; settles conflicts.
+    pub(crate) modified_at: String,
+    /// Moves the record to the trash instead of writing `data`.
+    #[serde(default)]
+    pub(crate) deleted: bool,
+    /// The record's fields, in the same shape `GET /sync/changes` returns them.
+    #[serde(default)]
+    pub(crate) data: Value,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct PushResponse {
+    pub(crate) results: Vec<PushResult>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub(crate) enum PushResult {
+    Accepted {
+        entity: Entity,
+        id: Uuid,
+        version: i64,
+    },
+    /// The server's copy won; it is returned so the client can adopt it.
+    Conflict {
+        entity: Entity,
+        id: Uuid,
+        current: Change,
+    },
+    Rejected {
+        entity: Entity,
+        id: Uuid,
+        error: &'static str,
+    },
+}
+
+#[derive(Deserialize)]
+struct GameData {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct ModelDefinitionData {
+    name: String,
+    game_id: Uuid,
+    points: Option<i64>,
+    unit_size: Option<i64>,
+    notes: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct UserModelData {
+    model_definition_id: Uuid,
+    quantity: i32,
+    status: Status,
+    location_id: Option<Uuid>,
+    notes: Option<String>,
+}
+
+struct ChangeRow {
+    seq: i64,
+    entity: String,
+    entity_id: String,
+    changed_at: String,
+    purged: bool,
+}
+
+enum Outcome {
+    /// Written, or already in the requested state when `action` is `None`.
+    Applied {
+        action: Option<Action>,
+        version: i64,
+    },
+    Conflict(Change),
+}
+
+#[utoipa::path(
+    get,
+    path = "/sync/changes",
+    tag = "sync",
+    params(ChangesQuery),
+    responses(
+        (status = 200, body = ChangeFeed),
+        (status = 400, description = "`since` is negative", body = ErrorBody),
+    )
+)]
+pub(crate) async fn list_changes(
+    State(state): State<AppState>,
+    Query(query): Query<ChangesQuery>,
+) -> Result<Json<ChangeFeed>, AppError> {
+    tracing::info!(since = ?query.since, "listing sync changes");
+    let since = query.since.unwrap_or(0);
+    if since < 0 {
+        return Err(AppError::bad_request("since cannot be negative"));
+    }
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let fetch = limit + 1;
+
+    // One transaction, so the rows match the sequence numbers they are listed under.
+    let mut transaction = state
+        .read_pool
+        .begin()
+        .await
+        .map_err(|error| AppError::internal("failed to start transaction", error))?;
+
+    let mut rows = sqlx::query_as!(
+        ChangeRow,
+        r#"
+        SELECT
+            seq as "seq!: i64",
+            entity,
+            entity_id,
+            changed_at,
+            purged as "purged!: bool"
+        FROM sync_changes
+        WHERE seq > $1
+        ORDER BY seq
+        LIMIT $2
+        "#,
+        since,
+        fetch
+    )
+    .fetch_all(&mut *transaction)
+    .await
+    .map_err(|error| AppError::internal("failed to fetch sync changes", error))?;
+
+    let has_more = rows.len() as i64 > limit;
+    rows.truncate(limit as usize);
+    let mut changes = Vec::with_capacity(rows.len());
+    for row in rows {
+        changes.push(to_change(&mut transaction, row).await?);
+    }
+
+    let cursor = changes.last().map_or(since, |change| change.version);
+    tracing::info!(count = changes.len(), cursor, "listed sync changes");
+    Ok(Json(ChangeFeed {
+        changes,
+        cursor,
+        has_more,
+    }))
+}
+
+/// Applies a batch of offline changes, each on its own savepoint so one bad
+/// record does not hold back the rest.
+///
+/// Conflicts are settled last-write-wins. A change based on the current
+/// `version` always applies. A change based on an older one applies only if
+/// it was made after the server's copy last changed; otherwise the server's
+/// copy wins and comes back as the conflict. Ties go to the server.
+#[utoipa::path(
+    post,
+    path = "/sync/push",
+    tag = "sync",
+    request_body = PushRequest,
+    responses(
+        (status = 200, description = "One result per change, in order", body = PushResponse),
+        (status = 413, description = "More than 500 changes", body = ErrorBody),
+    )
+)]
+pub(crate) async fn push_changes(
+    State(state): State<AppState>,
+    actor: Actor,
+    Json(payload): Json<PushRequest>,
+) -> Result<Json<PushResponse>, AppError> {
+    tracing::info!(count = payload.changes.len(), "pushing sync changes");
+    if payload.changes.len() > MAX_BATCH {
+        return Err(AppError::payload_too_large("too many changes in one push"));
+    }
+
+    let mut transaction = state
+        .pool
+        .begin()
+        .await
+        .map_err(|error| AppError::internal("failed to start transaction", error))?;
+
+    let mut results = Vec::with_capacity(payload.changes.len());
+    let mut applied = Vec::new();
+    for change in &payload.changes {
+        let (entity, id) = (change.entity, change.id);
+        let mut savepoint = transaction
+            .begin()
+            .await
+            .map_err(|error| AppError::internal("failed to start savepoint", error))?;
+
+        match push_one(&mut savepoint, &actor, change).await {
+            Ok(Outcome::Applied { action, version }) => {
+                savepoint
+                    .commit()
+                    .await
+                    .map_err(|error| AppError::internal("failed to release savepoint", error))?;
+                if let Some(action) = action {
+                    applied.push((entity, id.to_string(), action));
+                }
+                results.push(PushResult::Accepted {
+                    entity,
+                    id,
+                    version,
+                });
+            }
+            Ok(Outcome::Conflict(current)) => {
+                savepoint
+                    .rollback()
+                    .await
+                    .map_err(|error| AppError::internal("failed to roll back savepoint", error))?;
+                results.push(PushResult::Conflict {
+                    entity,
+                    id,
+                    current,
+                });
+            }
+            Err(error) if error.status.is_client_error() => {
+                savepoint
+                    .rollback()
+                    .await
+                    .map_err(|error| AppError::internal("failed to roll back savepoint", error))?;
+                results.push(PushResult::Rejected {
+                    entity,
+                    id,
+                    error: error.message,
+                });
+            }
+            Err(error) => return Err(error),
+        }
+    }
+
+    transaction
+        .commit()
+        .await
+        .map_err(|error| AppError::internal("failed to commit transaction", error))?;
+
+    for (entity, id, action) in &applied {
+        match entity {
+            Entity::UserModel => {
+                events::publish_user_model(&*state.repository, &state.events, id).await
+            }
+            _ => state.events.changed(*entity, id, *action),
+        }
+    }
+
+    tracing::info!(applied = applied.len(), "pushed sync changes");
+    Ok(Json(PushResponse { results }))
+}
+
+async fn push_one(
+    conn: &mut SqliteConnection,
+    actor: &Actor,
+    change: &PushChange,
+) -> Result<Outcome, AppError> {
+    let entity = change.entity;
+    if !SYNCED.contains(&entity) {
+        return Err(AppError::unprocessable("entity cannot be synced"));
+    }
+
+    let modified_at = sqlx::query_scalar!(
+        r#"
+        SELECT julianday($1) as "modified_at?: f64"
+        "#,
+        change.modified_at
+    )
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|error| AppError::internal("failed to parse modified_at", error))?
+    .ok_or(AppError::unprocessable("invalid modified_at"))?;
+
+    let entity_value = entity.as_str();
+    let id_value = change.id.to_string();
+    let current = sqlx::query!(
+        r#"
+        SELECT
+            seq as "seq!: i64",
+            julianday(changed_at) as "changed_at!: f64"
+        FROM sync_changes
+        WHERE entity = $1 AND entity_id = $2
+        "#,
+        entity_value,
+        id_value
+    )
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|error| AppError::internal("failed to check sync version", error))?;
+
+    if let Some(current) = current {
+        let stale = change.base_version != Some(current.seq);
+        if stale && modified_at <= current.changed_at {
+            let current = load_change(conn, entity, &id_value)
+                .await?
+                .ok_or_else(|| AppError::internal_message("sync change disappeared"))?;
+            return Ok(Outcome::Conflict(current));
+        }
+    }
+
+    let before = audit::snapshot(conn, entity, &id_value).await?;
+    let action = if change.deleted {
+        trash(conn, entity, &id_value).await?
+    } else {
+        match entity {
+            Entity::Game => upsert_game(conn, &id_value, data(&change.data)?).await?,
+            Entity::ModelDefinition => {
+                upsert_model_definition(conn, &id_value, data(&change.data)?).await?
+            }
+            _ => upsert_user_model(conn, &id_value, data(&change.data)?).await?,
+        }
+        let in_trash = before
+            .as_ref()
+            .is_some_and(|before| !before["deleted_at"].is_null());
+        Some(match &before {
+            None => Action::Create,
+            Some(_) if in_trash => Action::Restore,
+            Some(_) => Action::Update,
+        })
+    };
+
+    if let Some(action) = action {
+        audit::record_row(conn, actor, entity, &id_value, action, before).await?;
+    }
+
+    let version = load_change(conn, entity, &id_value)
+        .await?
+        .ok_or(AppError::not_found("record not found"))?
+        .version;
+    Ok(Outcome::Applied { action, version })
+}
+
+fn data<T: serde::de::DeserializeOwned>(value: &Value) -> Result<T, AppError> {
+    T::deserialize(value).map_err(|_| AppError::unprocessable("invalid data for entity"))
+}
+
+/// Moves a record to the trash, returning `None` if it was already there.
+async fn trash(
+    conn: &mut SqliteConnection,
+    entity: Entity,
+    id: &str,
+) -> Result<Option<Action>, AppError> {
+    let query = format!(
+        "UPDATE {} SET deleted_at = datetime('now') WHERE id = $1 AND deleted_at IS NULL",
+        entity.table()
+    );
+    let result = sqlx::query(&query)
+        .bind(id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|error| AppError::internal("failed to move item to trash", error))?;
+
+    Ok((result.rows_affected() > 0).then_some(Action::Delete))
+}
+
+async fn upsert_game(
+    conn: &mut SqliteConnection,
+    id: &str,
+    data: GameData,
+) -> Result<(), AppError> {
+    sqlx::query!(
+        r#"
+        INSERT INTO games (id, name, created_at)
+        VALUES ($1, $2, datetime('now'))
+        ON CONFLICT (id) DO UPDATE
+        SET name = excluded.name,
+            deleted_at = NULL
+        "#,
+        id,
+        data.name
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|error| match &error {
+        sqlx::Error::Database(db_error)
+            if db_error
+                .message()
+                .contains("UNIQUE constraint failed: games.name") =>
+        {
+            AppError::conflict("game name already exists")
+        }
+        _ => AppError::internal("failed to sync game", error),
+    })?;
+
+    Ok(())
+}
+
+async fn upsert_model_definition(
+    conn: &mut SqliteConnection,
+    id: &str,
+    data: ModelDefinitionData,
+) -> Result<(), AppError> {
+    validate_points(data.points, data.unit_size)?;
+    let game_id_value = data.game_id.to_string();
+    let game = sqlx::query!(
+        r#"
+        SELECT id
+        FROM games
+        WHERE id = $1 AND deleted_at IS NULL
+        "#,
+        game_id_value
+    )
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|error| AppError::internal("failed to validate game", error))?;
+
+    if game.is_none() {
+        return Err(AppError::not_found("game not found"));
+    }
+
+    let notes_value = data.notes.as_deref().map(str::trim);
+    sqlx::query!(
+        r#"
+        INSERT INTO model_definitions (id, name, game_id, points, unit_size, notes, created_at)
+        VALUES ($1, $2, $3, $4, $5, NULLIF($6, ''), datetime('now'))
+        ON CONFLICT (id) DO UPDATE
+        SET name = excluded.name,
+            game_id = excluded.game_id,
+            points = excluded.points,
+            unit_size = excluded.unit_size,
+            notes = excluded.notes,
+            deleted_at = NULL
+        "#,
+        id,
+        data.name,
+        game_id_value,
+        data.points,
+        data.unit_size,
+        notes_value
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|error| AppError::internal("failed to sync model definition", error))?;
+
+    Ok(())
+}
+
+async fn upsert_user_model(
+    conn: &mut SqliteConnection,
+    id: &str,
+    data: UserModelData,
+) -> Result<(), AppError> {
+    if data.quantity <= 0 {
+        return Err(AppError::unprocessable("quantity must be greater than 0"));
+    }
+
+    let model_definition_id_value = data.model_definition_id.to_string();
+    let exists = sqlx::query!(
+        r#"
+        SELECT model_definitions.id
+        FROM model_definitions
+        INNER JOIN games ON games.id = model_definitions.game_id
+        WHERE model_definitions.id = $1
+          AND model_definitions.deleted_at IS NULL
+          AND games.deleted_at IS NULL
+        "#,
+        model_definition_id_value
+    )
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|error| AppError::internal("failed to validate model definition", error))?;
+
+    if exists.is_none() {
+        return Err(AppError::not_found("model definition not found"));
+    }
+
+    let location_id_value = data.location_id.map(|id| id.to_string());
+    if let Some(location_id) = location_id_value.as_deref() {
+        let exists = sqlx::query!(
+            r#"
+            SELECT id
+            FROM locations
+            WHERE id = $1
+            "#,
+            location_id
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|error| AppError::internal("failed to validate location", error))?;
+
+        if exists.is_none() {
+            return Err(AppError::not_found("location not found"));
+        }
+    }
+
+    let status_value = data.status.as_str();
+    let notes_value = data.notes.as_deref().map(str::trim);
+    sqlx::query!(
+        r#"
+        INSERT INTO user_models (
+            id, model_definition_id, quantity, status, location_id, notes, created_at, updated_at
+        )
+        VALUES ($1, $2, $3, $4, $5, NULLIF($6, ''), datetime('now'), datetime('now'))
+        ON CONFLICT (id) DO UPDATE
+        SET model_definition_id = excluded.model_definition_id,
+            quantity = excluded.quantity,
+            status = excluded.status,
+            location_id = excluded.location_id,
+            notes = excluded.notes,
+            updated_at = excluded.updated_at,
+            version = user_models.version + 1,
+            deleted_at = NULL
+        "#,
+        id,
+        model_definition_id_value,
+        data.quantity,
+        status_value,
+        location_id_value,
+        notes_value
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|error| AppError::internal("failed to sync user model", error))?;
+
+    Ok(())
+}
+
+async fn load_change(
+    conn: &mut SqliteConnection,
+    entity: Entity,
+    id: &str,
+) -> Result<Option<Change>, AppError> {
+    let entity_value = entity.as_str();
+    let row = sqlx::query_as!(
+        ChangeRow,
+        r#"
+        SELECT
+            seq as "seq!: i64",
+            entity,
+            entity_id,
+            changed_at,
+            purged as "purged!: bool"
+        FROM sync_changes
+        WHERE entity = $1 AND entity_id = $2
+        "#,
+        entity_value,
+        id
+    )
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|error| AppError::internal("failed to load sync change", error))?;
+
+    match row {
+        Some(row) => to_change(conn, row).await.map(Some),
+        None => Ok(None),
+    }
+}
+
+async fn to_change(conn: &mut SqliteConnection, row: ChangeRow) -> Result<Change, AppError> {
+    let entity = SYNCED
+        .into_iter()
+        .find(|entity| entity.as_str() == row.entity)
+        .ok_or_else(|| AppError::internal_message("invalid entity stored in sync log"))?;
+
+    let data = if row.purged {
+        None
+    } else {
+        audit::snapshot(conn, entity, &row.entity_id).await?
+    };
+    let deleted = data
+        .as_ref()
+        .is_none_or(|data| !data["deleted_at"].is_null());
+
+    Ok(Change {
+        entity,
+        id: parse_uuid(row.entity_id)?,
+        version: row.seq,
+        changed_at: row.changed_at,
+        deleted,
+        data,
+    })
+}
@@ -0,0 +1,670 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::{SqliteConnection, SqlitePool};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    audit::{self, Action, Actor, Entity},
+    events, parse_uuid, AppError, AppState, ErrorBody,
+};
+
+#[derive(Deserialize, ToSchema)]
+pub(crate) struct CreateTag {
+    name: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub(crate) struct UpdateTag {
+    name: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub(crate) struct SetTags {
+    tag_ids: Vec<Uuid>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct Tag {
+    id: Uuid,
+    name: String,
+    created_at: String,
+}
+
+#[derive(Serialize, Clone, ToSchema)]
+pub(crate) struct TagSummary {
+    id: Uuid,
+    name: String,
+}
+
+/// Query parameters shared by list endpoints that can be filtered by tag.
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(crate) struct TagFilter {
+    /// Comma-separated tag IDs.
+    tag_ids: Option<String>,
+    #[serde(default)]
+    tag_mode: TagMode,
+}
+
+#[derive(Deserialize, Clone, Copy, Default, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum TagMode {
+    /// Items must carry every requested tag.
+    #[default]
+    All,
+    /// Items must carry at least one requested tag.
+    Any,
+}
+
+pub(crate) struct TagMatcher {
+    tag_ids: Vec<Uuid>,
+    mode: TagMode,
+}
+
+impl TagFilter {
+    pub(crate) fn matcher(&self) -> Result<TagMatcher, AppError> {
+        let tag_ids = match self.tag_ids.as_deref() {
+            Some(value) => value
+                .split(',')
+                .map(str::trim)
+                .filter(|id| !id.is_empty())
+                .map(|id| Uuid::parse_str(id).map_err(|_| AppError::bad_request("invalid tag id")))
+                .collect::<Result<Vec<_>, _>>()?,
+            None => Vec::new(),
+        };
+
+        Ok(TagMatcher {
+            tag_ids,
+            mode: self.tag_mode,
+        })
+    }
+}
+
+impl TagMatcher {
+    pub(crate) fn matches(&self, tags: &[TagSummary]) -> bool {
+        if self.tag_ids.is_empty() {
+            return true;
+        }
+
+        let has = |id: &Uuid| tags.iter().any(|tag| tag.id == *id);
+        match self.mode {
+            TagMode::All => self.tag_ids.iter().all(has),
+            TagMode::Any => self.tag_ids.iter().any(has),
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/tags",
+    tag = "tags",
+    request_body = CreateTag,
+    responses(
+        (status = 201, body = Tag),
+        (status = 409, description = "A tag with this name exists", body = ErrorBody),
+        (status = 422, description = "The name is blank", body = ErrorBody),
+    )
+)]
+pub(crate) async fn create_tag(
+    State(state): State<AppState>,
+    actor: Actor,
+    Json(payload): Json<CreateTag>,
+) -> Result<(StatusCode, Json<Tag>), AppError> {
+    tracing::info!(name = %payload.name, "creating tag");
+    let name = validate_name(&payload.name)?;
+    let id = Uuid::new_v4();
+    let id_value = id.to_string();
+    let mut transaction = state
+        .pool
+        .begin()
+        .await
+        .map_err(|error| AppError::internal("failed to start transaction", error))?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO tags (id, name, created_at)
+        VALUES ($1, $2, datetime('now'))
+        "#,
+        id_value,
+        name
+    )
+    .execute(&mut *transaction)
+    .await
+    .map_err(|error| map_tag_error(error, "failed to create tag"))?;
+
+    audit::record_row(
+        &mut transaction,
+        &actor,
+        Entity::Tag,
+        &id_value,
+        Action::Create,
+        None,
+    )
+    .await?;
+
+    transaction
+        .commit()
+        .await
+        .map_err(|error| AppError::internal("failed to commit transaction", error))?;
+    state.events.changed(Entity::Tag, &id_value, Action::Create);
+
+    let tag = load_tag(&state.pool, &id_value)
+        .await?
+        .ok_or_else(|| AppError::internal_message("failed to load tag"))?;
+
+    Ok((StatusCode::CREATED, Json(tag)))
+}
+
+#[utoipa::path(get, path = "/tags", tag = "tags", responses((status = 200, body = Vec<Tag>)))]
+pub(crate) async fn list_tags(State(state): State<AppState>) -> Result<Json<Vec<Tag>>, AppError> {
+    tracing::info!("listing tags");
+    let records = sqlx::query!(
+        r#"
+        SELECT
+            id as "id!: String",
+            name,
+            created_at as "created_at!: String"
+        FROM tags
+        ORDER BY name
+        "#
+    )
+    .fetch_all(&state.read_pool)
+    .await
+    .map_err(|error| AppError::internal("failed to fetch tags", error))?;
+
+    let tags = records
+        .into_iter()
+        .map(|record| {
+            Ok(Tag {
+                id: parse_uuid(record.id)?,
+                name: record.name,
+                created_at: record.created_at,
+            })
+        })
+        .collect::<Result<Vec<_>, AppError>>()?;
+
+    tracing::info!(count = tags.len(), "listed tags");
+    Ok(Json(tags))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/tags/{id}",
+    tag = "tags",
+    params(("id" = Uuid, Path)),
+    request_body = UpdateTag,
+    responses(
+        (status = 200, body = Tag),
+        (status = 404, body = ErrorBody),
+        (status = 409, description = "A tag with this name exists", body = ErrorBody),
+        (status = 422, description = "The name is blank", body = ErrorBody),
+    )
+)]
+pub(crate) async fn update_tag(
+    State(state): State<AppState>,
+    actor: Actor,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateTag>,
+) -> Result<Json<Tag>, AppError> {
+    tracing::info!(tag_id = %id, name = %payload.name, "renaming tag");
+    let name = validate_name(&payload.name)?;
+    let id_value = id.to_string();
+    let mut transaction = state
+        .pool
+        .begin()
+        .await
+        .map_err(|error| AppError::internal("failed to start transaction", error))?;
+
+    let before = audit::snapshot(&mut transaction, Entity::Tag, &id_value).await?;
+    if before.is_none() {
+        return Err(AppError::not_found("tag not found"));
+    }
+
+    sqlx::query!(
+        r#"
+        UPDATE tags
+        SET name = $1
+        WHERE id = $2
+        "#,
+        name,
+        id_value
+    )
+    .execute(&mut *transaction)
+    .await
+    .map_err(|error| map_tag_error(error, "failed to update tag"))?;
+
+    audit::record_row(
+        &mut transaction,
+        &actor,
+        Entity::Tag,
+        &id_value,
+        Action::Update,
+        before,
+    )
+    .await?;
+
+    transaction
+        .commit()
+        .await
+        .map_err(|error| AppError::internal("failed to commit transaction", error))?;
+    state.events.changed(Entity::Tag, &id_value, Action::Update);
+
+    match load_tag(&state.pool, &id_value).await? {
+        Some(tag) => Ok(Json(tag)),
+        None => Err(AppError::not_found("tag not found")),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/tags/{id}",
+    tag = "tags",
+    params(("id" = Uuid, Path)),
+    responses((status = 204), (status = 404, body = ErrorBody))
+)]
+pub(crate) async fn delete_tag(
+    State(state): State<AppState>,
+    actor: Actor,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    tracing::info!(tag_id = %id, "deleting tag");
+    let id_value = id.to_string();
+    let mut transaction = state
+        .pool
+        .begin()
+        .await
+        .map_err(|error| AppError::internal("failed to start transaction", error))?;
+
+    let before = audit::snapshot(&mut transaction, Entity::Tag, &id_value).await?;
+    if before.is_none() {
+        return Err(AppError::not_found("tag not found"));
+    }
+
+    sqlx::query!(
+        r#"
+        DELETE FROM tags
+        WHERE id = $1
+        "#,
+        id_value
+    )
+    .execute(&mut *transaction)
+    .await
+    .map_err(|error| AppError::internal("failed to delete tag", error))?;
+
+    audit::record_row(
+        &mut transaction,
+        &actor,
+        Entity::Tag,
+        &id_value,
+        Action::Delete,
+        before,
+    )
+    .await?;
+
+    transaction
+        .commit()
+        .await
+        .map_err(|error| AppError::internal("failed to commit transaction", error))?;
+    state.events.changed(Entity::Tag, &id_value, Action::Delete);
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    put,
+    path = "/user-models/{id}/tags",
+    tag = "tags",
+    params(("id" = Uuid, Path)),
+    request_body = SetTags,
+    responses(
+        (status = 200, description = "The lot's tags", body = Vec<TagSummary>),
+        (status = 404, description = "The lot or a tag does not exist", body = ErrorBody),
+    )
+)]
+pub(crate) async fn set_user_model_tags(
+    State(state): State<AppState>,
+    actor: Actor,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<SetTags>,
+) -> Result<Json<Vec<TagSummary>>, AppError> {
+    tracing::info!(user_model_id = %id, count = payload.tag_ids.len(), "setting user model tags");
+    let id_value = id.to_string();
+    let exists = sqlx::query!(
+        r#"
+        SELECT user_models.id
+        FROM user_models
+        INNER JOIN model_definitions ON model_definitions.id = user_models.model_definition_id
+        INNER JOIN games ON games.id = model_definitions.game_id
+        WHERE user_models.id = $1
+          AND user_models.deleted_at IS NULL
+          AND model_definitions.deleted_at IS NULL
+          AND games.deleted_at IS NULL
+        "#,
+        id_value
+    )
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|error| AppError::internal("failed to validate user model", error))?;
+
+    if exists.is_none() {
+        return Err(AppError::not_found("user model not found"));
+    }
+
+    let mut transaction = state
+        .pool
+        .begin()
+        .await
+        .map_err(|error| AppError::internal("failed to start transaction", error))?;
+
+    let before = linked_user_model_tags(&mut transaction, &id_value).await?;
+    sqlx::query!(
+        r#"
+        DELETE FROM user_model_tags
+        WHERE user_model_id = $1
+        "#,
+        id_value
+    )
+    .execute(&mut *transaction)
+    .await
+    .map_err(|error| AppError::internal("failed to clear user model tags", error))?;
+
+    for tag_id in &payload.tag_ids {
+        let tag_id_value = tag_id.to_string();
+        sqlx::query!(
+            r#"
+            INSERT OR IGNORE INTO user_model_tags (user_model_id, tag_id)
+            VALUES ($1, $2)
+            "#,
+            id_value,
+            tag_id_value
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(map_tag_link_error)?;
+    }
+
+    let after = linked_user_model_tags(&mut transaction, &id_value).await?;
+    audit::record(
+        &mut transaction,
+        &actor,
+        Entity::UserModel,
+        &id_value,
+        Action::Update,
+        Some(json!({ "tag_ids": before })),
+        Some(json!({ "tag_ids": after })),
+    )
+    .await?;
+
+    transaction
+        .commit()
+        .await
+        .map_err(|error| AppError::internal("failed to commit transaction", error))?;
+    events::publish_user_model(&*state.repository, &state.events, &id_value).await;
+
+    let mut tags = user_model_tags(&state.pool).await?;
+    Ok(Json(tags.remove(&id_value).unwrap_or_default()))
+}
+
+#[utoipa::path(
+    put,
+    path = "/model-definitions/{id}/tags",
+    tag = "tags",
+    params(("id" = Uuid, Path)),
+    request_body = SetTags,
+    responses(
+        (status = 200, description = "The model definition's tags", body = Vec<TagSummary>),
+        (status = 404, description = "The model definition or a tag does not exist", body = ErrorBody),
+    )
+)]
+pub(crate) async fn set_model_definition_tags(
+    State(state): State<AppState>,
+    actor: Actor,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<SetTags>,
+) -> Result<Json<Vec<TagSummary>>, AppError> {
+    tracing::info!(
+        model_definition_id = %id,
+        count = payload.tag_ids.len(),
+        "setting model definition tags"
+    );
+    let id_value = id.to_string();
+    let exists = sqlx::query!(
+        r#"
+        SELECT id
+        FROM model_definitions
+        WHERE id = $1
+        "#,
+        id_value
+    )
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|error| AppError::internal("failed to validate model definition", error))?;
+
+    if exists.is_none() {
+        return Err(AppError::not_found("model definition not found"));
+    }
+
+    let mut transaction = state
+        .pool
+        .begin()
+        .await
+        .map_err(|error| AppError::internal("failed to start transaction", error))?;
+
+    let before = linked_model_definition_tags(&mut transaction, &id_value).await?;
+    sqlx::query!(
+        r#"
+        DELETE FROM model_definition_tags
+        WHERE model_definition_id = $1
+        "#,
+        id_value
+    )
+    .execute(&mut *transaction)
+    .await
+    .map_err(|error| AppError::internal("failed to clear model definition tags", error))?;
+
+    for tag_id in &payload.tag_ids {
+        let tag_id_value = tag_id.to_string();
+        sqlx::query!(
+            r#"
+            INSERT OR IGNORE INTO model_definition_tags (model_definition_id, tag_id)
+            VALUES ($1, $2)
+            "#,
+            id_value,
+            tag_id_value
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(map_tag_link_error)?;
+    }
+
+    let after = linked_model_definition_tags(&mut transaction, &id_value).await?;
+    audit::record(
+        &mut transaction,
+        &actor,
+        Entity::ModelDefinition,
+        &id_value,
+        Action::Update,
+        Some(json!({ "tag_ids": before })),
+        Some(json!({ "tag_ids": after })),
+    )
+    .await?;
+
+    transaction
+        .commit()
+        .await
+        .map_err(|error| AppError::internal("failed to commit transaction", error))?;
+    state
+        .events
+        .changed(Entity::ModelDefinition, &id_value, Action::Update);
+
+    let mut tags = model_definition_tags(&state.pool).await?;
+    Ok(Json(tags.remove(&id_value).unwrap_or_default()))
+}
+
+/// Tag IDs currently linked to one user model, for audit snapshots.
+async fn linked_user_model_tags(
+    conn: &mut SqliteConnection,
+    id: &str,
+) -> Result<Vec<String>, AppError> {
+    let records = sqlx::query!(
+        r#"
+        SELECT tag_id as "tag_id!: String"
+        FROM user_model_tags
+        WHERE user_model_id = $1
+        ORDER BY tag_id
+        "#,
+        id
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|error| AppError::internal("failed to fetch user model tags", error))?;
+
+    Ok(records.into_iter().map(|record| record.tag_id).collect())
+}
+
+/// Tag IDs currently linked to one model definition, for audit snapshots.
+async fn linked_model_definition_tags(
+    conn: &mut SqliteConnection,
+    id: &str,
+) -> Result<Vec<String>, AppError> {
+    let records = sqlx::query!(
+        r#"
+        SELECT tag_id as "tag_id!: String"
+        FROM model_definition_tags
+        WHERE model_definition_id = $1
+        ORDER BY tag_id
+        "#,
+        id
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|error| AppError::internal("failed to fetch model definition tags", error))?;
+
+    Ok(records.into_iter().map(|record| record.tag_id).collect())
+}
+
+/// Loads the tags of every user model, keyed by the stored user model ID.
+pub(crate) async fn user_model_tags(
+    pool: &SqlitePool,
+) -> Result<HashMap<String, Vec<TagSummary>>, AppError> {
+    let records = sqlx::query!(
+        r#"
+        SELECT
+            user_model_tags.user_model_id as "owner_id!: String",
+            tags.id as "id!: String",
+            tags.name
+        FROM user_model_tags
+        INNER JOIN tags ON tags.id = user_model_tags.tag_id
+        ORDER BY tags.name
+        "#
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|error| AppError::internal("failed to fetch user model tags", error))?;
+
+    let mut tags: HashMap<String, Vec<TagSummary>> = HashMap::new();
+    for record in records {
+        tags.entry(record.owner_id).or_default().push(TagSummary {
+            id: parse_uuid(record.id)?,
+            name: record.name,
+        });
+    }
+
+    Ok(tags)
+}
+
+/// Loads the tags of every model definition, keyed by the stored model definition ID.
+pub(crate) async fn model_definition_tags(
+    pool: &SqlitePool,
+) -> Result<HashMap<String, Vec<TagSummary>>, AppError> {
+    let records = sqlx::query!(
+        r#"
+        SELECT
+            model_definition_tags.model_definition_id as "owner_id!: String",
+            tags.id as "id!: String",
+            tags.name
+        FROM model_definition_tags
+        INNER JOIN tags ON tags.id = model_definition_tags.tag_id
+        ORDER BY tags.name
+        "#
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|error| AppError::internal("failed to fetch model definition tags", error))?;
+
+    let mut tags: HashMap<String, Vec<TagSummary>> = HashMap::new();
+    for record in records {
+        tags.entry(record.owner_id).or_default().push(TagSummary {
+            id: parse_uuid(record.id)?,
+            name: record.name,
+        });
+    }
+
+    Ok(tags)
+}
+
+async fn load_tag(pool: &SqlitePool, id: &str) -> Result<Option<Tag>, AppError> {
+    let record = sqlx::query!(
+        r#"
+        SELECT
+            id as "id!: String",
+            name,
+            created_at as "created_at!: String"
+        FROM tags
+        WHERE id = $1
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|error| AppError::internal("failed to load tag", error))?;
+
+    record
+        .map(|record| {
+            Ok(Tag {
+                id: parse_uuid(record.id)?,
+                name: record.name,
+                created_at: record.created_at,
+            })
+        })
+        .transpose()
+}
+
+fn validate_name(name: &str) -> Result<&str, AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::unprocessable("tag name is required"));
+    }
+
+    Ok(name)
+}
+
+fn map_tag_error(error: sqlx::Error, message: &'static str) -> AppError {
+    if let sqlx::Error::Database(db_error) = &error {
+        let message = db_error.message();
+        if message.contains("UNIQUE constraint failed: tags.name") {
+            return AppError::conflict("tag name already exists");
+        }
+    }
+
+    AppError::internal(message, error)
+}
+
+fn map_tag_link_error(error: sqlx::Error) -> AppError {
+    if let sqlx::Error::Database(db_error) = &error {
+        let message = db_error.message();
+        if message.contains("FOREIGN KEY constraint failed") {
+            return AppError::not_found("tag not found");
+        }
+    }
+
+    AppError::internal("failed to tag item", error)
+}
@@ -0,0 +1,470 @@
+use std::{path::PathBuf, time::Duration};
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Serialize;
+use sqlx::SqliteConnection;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    audit::{self, Action, Actor, Entity},
+    events::{self, ChangeEvent},
+    parse_uuid, photos, AppError, AppState, ErrorBody,
+};
+
+/// How long a deleted item stays in the trash when `TRASH_RETENTION_DAYS` is not set.
+pub const DEFAULT_RETENTION_DAYS: u32 = 30;
+
+const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Entities that go to the trash instead of being deleted outright.
+const TRASHABLE: [Entity; 3] = [Entity::Game, Entity::ModelDefinition, Entity::UserModel];
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct TrashItem {
+    id: Uuid,
+    entity: String,
+    name: String,
+    deleted_at: String,
+}
+
+/// Moves the game to the trash.
+#[utoipa::path(
+    delete,
+    path = "/games/{id}",
+    tag = "games",
+    params(("id" = Uuid, Path)),
+    responses((status = 204), (status = 404, body = ErrorBody))
+)]
+pub(crate) async fn delete_game(
+    State(state): State<AppState>,
+    actor: Actor,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    tracing::info!(game_id = %id, "moving game to trash");
+    move_to_trash(&state, &actor, Entity::Game, id, "game not found").await
+}
+
+/// Moves the model definition to the trash.
+#[utoipa::path(
+    delete,
+    path = "/model-definitions/{id}",
+    tag = "model definitions",
+    params(("id" = Uuid, Path)),
+    responses((status = 204), (status = 404, body = ErrorBody))
+)]
+pub(crate) async fn delete_model_definition(
+    State(state): State<AppState>,
+    actor: Actor,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    tracing::info!(model_definition_id = %id, "moving model definition to trash");
+    move_to_trash(
+        &state,
+        &actor,
+        Entity::ModelDefinition,
+        id,
+        "model definition not found",
+    )
+    .await
+}
+
+/// Moves the lot to the trash.
+#[utoipa::path(
+    delete,
+    path = "/user-models/{id}",
+    tag = "user models",
+    params(("id" = Uuid, Path)),
+    responses((status = 204), (status = 404, body = ErrorBody))
+)]
+pub(crate) async fn delete_user_model(
+    State(state): State<AppState>,
+    actor: Actor,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    tracing::info!(user_model_id = %id, "moving user model to trash");
+    move_to_trash(
+        &state,
+        &actor,
+        Entity::UserModel,
+        id,
+        "user model not found",
+    )
+    .await
+}
+
+#[utoipa::path(
+    get,
+    path = "/trash",
+    tag = "trash",
+    responses((status = 200, description = "Most recently deleted first", body = Vec<TrashItem>))
+)]
+pub(crate) async fn list_trash(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<TrashItem>>, AppError> {
+    tracing::info!("listing trash");
+    let records = sqlx::query!(
+        r#"
+        SELECT id as "id!: String", 'game' as "entity!: String", name, deleted_at as "deleted_at!: String"
+        FROM games
+        WHERE deleted_at IS NOT NULL
+        UNION ALL
+        SELECT id, 'model_definition', name, deleted_at
+        FROM model_definitions
+        WHERE deleted_at IS NOT NULL
+        UNION ALL
+        SELECT user_models.id, 'user_model', model_definitions.name, user_models.deleted_at
+        FROM user_models
+        INNER JOIN model_definitions ON model_definitions.id = user_models.model_definition_id
+        WHERE user_models.deleted_at IS NOT NULL
+        ORDER BY 4 DESC
+        "#
+    )
+    .fetch_all(&state.read_pool)
+    .await
+    .map_err(|error| AppError::internal("failed to fetch trash", error))?;
+
+    let items = records
+        .into_iter()
+        .map(|record| {
+            Ok(TrashItem {
+                id: parse_uuid(record.id)?,
+                entity: record.entity,
+                name: record.name,
+                deleted_at: record.deleted_at,
+            })
+        })
+        .collect::<Result<Vec<_>, AppError>>()?;
+
+    tracing::info!(count = items.len(), "listed trash");
+    Ok(Json(items))
+}
+
+#[utoipa::path(
+    post,
+    path = "/trash/{id}/restore",
+    tag = "trash",
+    params(("id" = Uuid, Path, description = "ID of the trashed game, model definition or lot")),
+    responses(
+        (status = 204),
+        (status = 404, body = ErrorBody),
+        (status = 409, description = "Its game or model definition is still in the trash", body = ErrorBody)
+    )
+)]
+pub(crate) async fn restore(
+    State(state): State<AppState>,
+    actor: Actor,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    tracing::info!(id = %id, "restoring from trash");
+    let id_value = id.to_string();
+    let mut transaction = state
+        .pool
+        .begin()
+        .await
+        .map_err(|error| AppError::internal("failed to start transaction", error))?;
+
+    let mut found = None;
+    for entity in TRASHABLE {
+        if let Some(before) = audit::snapshot(&mut transaction, entity, &id_value).await? {
+            found = Some((entity, before));
+            break;
+        }
+    }
+
+    let Some((entity, before)) = found.filter(|(_, before)| !before["deleted_at"].is_null()) else {
+        return Err(AppError::not_found("trash item not found"));
+    };
+
+    if parent_in_trash(&mut transaction, entity, &id_value).await? {
+        return Err(AppError::conflict(match entity {
+            Entity::UserModel => "restore the model definition first",
+            _ => "restore the game first",
+        }));
+    }
+
+    let query = format!(
+        "UPDATE {} SET deleted_at = NULL WHERE id = $1",
+        entity.table()
+    );
+    sqlx::query(&query)
+        .bind(&id_value)
+        .execute(&mut *transaction)
+        .await
+        .map_err(|error| AppError::internal("failed to restore from trash", error))?;
+
+    audit::record_row(
+        &mut transaction,
+        &actor,
+        entity,
+        &id_value,
+        Action::Restore,
+        Some(before),
+    )
+    .await?;
+
+    transaction
+        .commit()
+        .await
+        .map_err(|error| AppError::internal("failed to commit transaction", error))?;
+    match entity {
+        Entity::UserModel => {
+            events::publish_user_model(&*state.repository, &state.events, &id_value).await
+        }
+        _ => state.events.changed(entity, &id_value, Action::Restore),
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Permanently deletes items that have been in the trash for longer than
+/// `retention_days`, along with everything that belongs to them, and returns
+/// how many rows were removed.
+pub async fn purge_trash(state: &AppState, retention_days: u32) -> Result<u64, AppError> {
+    let modifier = format!("-{retention_days} days");
+    let mut transaction = state
+        .pool
+        .begin()
+        .await
+        .map_err(|error| AppError::internal("failed to start transaction", error))?;
+
+    let cutoff = sqlx::query!(
+        r#"
+        SELECT datetime('now', $1) as "cutoff!: String"
+        "#,
+        modifier
+    )
+    .fetch_one(&mut *transaction)
+    .await
+    .map_err(|error| AppError::internal("failed to compute purge cutoff", error))?
+    .cutoff;
+
+    // Children first, so every row that disappears gets its own audit entry
+    // rather than vanishing through `ON DELETE CASCADE`.
+    let user_model_ids = sqlx::query!(
+        r#"
+        SELECT user_models.id as "id!: String"
+        FROM user_models
+        INNER JOIN model_definitions ON model_definitions.id = user_models.model_definition_id
+        INNER JOIN games ON games.id = model_definitions.game_id
+        WHERE user_models.deleted_at <= $1
+           OR model_definitions.deleted_at <= $1
+           OR games.deleted_at <= $1
+        "#,
+        cutoff
+    )
+    .fetch_all(&mut *transaction)
+    .await
+    .map_err(|error| AppError::internal("failed to find expired user models", error))?
+    .into_iter()
+    .map(|record| record.id);
+
+    let model_definition_ids = sqlx::query!(
+        r#"
+        SELECT model_definitions.id as "id!: String"
+        FROM model_definitions
+        INNER JOIN games ON games.id = model_definitions.game_id
+        WHERE model_definitions.deleted_at <= $1
+           OR games.deleted_at <= $1
+        "#,
+        cutoff
+    )
+    .fetch_all(&mut *transaction)
+    .await
+    .map_err(|error| AppError::internal("failed to find expired model definitions", error))?
+    .into_iter()
+    .map(|record| record.id);
+
+    let game_ids = sqlx::query!(
+        r#"
+        SELECT id as "id!: String"
+        FROM games
+        WHERE deleted_at <= $1
+        "#,
+        cutoff
+    )
+    .fetch_all(&mut *transaction)
+    .await
+    .map_err(|error| AppError::internal("failed to find expired games", error))?
+    .into_iter()
+    .map(|record| record.id);
+
+    let actor = Actor("purge-job".to_string());
+    let mut files = Vec::new();
+    let mut purged = 0;
+    let expired = user_model_ids
+        .map(|id| (Entity::UserModel, id))
+        .chain(model_definition_ids.map(|id| (Entity::ModelDefinition, id)))
+        .chain(game_ids.map(|id| (Entity::Game, id)))
+        .collect::<Vec<_>>();
+    for (entity, id) in &expired {
+        let (entity, id) = (*entity, id.as_str());
+        if entity == Entity::UserModel {
+            files.extend(photo_files(&mut transaction, state, id).await?);
+        }
+
+        let before = audit::snapshot(&mut transaction, entity, id).await?;
+        let query = format!("DELETE FROM {} WHERE id = $1", entity.table());
+        sqlx::query(&query)
+            .bind(id)
+            .execute(&mut *transaction)
+            .await
+            .map_err(|error| AppError::internal("failed to purge trash", error))?;
+        audit::record(
+            &mut transaction,
+            &actor,
+            entity,
+            id,
+            Action::Purge,
+            before,
+            None,
+        )
+        .await?;
+        purged += 1;
+    }
+
+    transaction
+        .commit()
+        .await
+        .map_err(|error| AppError::internal("failed to commit transaction", error))?;
+
+    photos::remove_files(&files).await;
+    for (entity, id) in &expired {
+        state.events.changed(*entity, id, Action::Purge);
+    }
+    Ok(purged)
+}
+
+/// Runs [`purge_trash`] once an hour for as long as the server is up.
+pub async fn run_purge_job(state: AppState, retention_days: u32) {
+    let mut interval = tokio::time::interval(PURGE_INTERVAL);
+    loop {
+        interval.tick().await;
+        match purge_trash(&state, retention_days).await {
+            Ok(0) => {}
+            Ok(purged) => tracing::info!(purged, retention_days, "purged expired trash"),
+            Err(error) => tracing::error!(%error, "failed to purge trash"),
+        }
+    }
+}
+
+async fn move_to_trash(
+    state: &AppState,
+    actor: &Actor,
+    entity: Entity,
+    id: Uuid,
+    not_found: &'static str,
+) -> Result<StatusCode, AppError> {
+    let id_value = id.to_string();
+    let mut transaction = state
+        .pool
+        .begin()
+        .await
+        .map_err(|error| AppError::internal("failed to start transaction", error))?;
+
+    let before = audit::snapshot(&mut transaction, entity, &id_value).await?;
+    let query = format!(
+        "UPDATE {} SET deleted_at = datetime('now') WHERE id = $1 AND deleted_at IS NULL",
+        entity.table()
+    );
+    let result = sqlx::query(&query)
+        .bind(&id_value)
+        .execute(&mut *transaction)
+        .await
+        .map_err(|error| AppError::internal("failed to move item to trash", error))?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found(not_found));
+    }
+
+    audit::record_row(
+        &mut transaction,
+        actor,
+        entity,
+        &id_value,
+        Action::Delete,
+        before,
+    )
+    .await?;
+
+    transaction
+        .commit()
+        .await
+        .map_err(|error| AppError::internal("failed to commit transaction", error))?;
+    match entity {
+        Entity::UserModel => state.events.publish(ChangeEvent::UserModelRemoved { id }),
+        _ => state.events.changed(entity, &id_value, Action::Delete),
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Whether the game or model definition an item belongs to is itself in the trash.
+async fn parent_in_trash(
+    conn: &mut SqliteConnection,
+    entity: Entity,
+    id: &str,
+) -> Result<bool, AppError> {
+    let record = match entity {
+        Entity::ModelDefinition => {
+            sqlx::query_scalar!(
+                r#"
+            SELECT games.deleted_at IS NOT NULL as "in_trash!: bool"
+            FROM model_definitions
+            INNER JOIN games ON games.id = model_definitions.game_id
+            WHERE model_definitions.id = $1
+            "#,
+                id
+            )
+            .fetch_optional(&mut *conn)
+            .await
+        }
+        Entity::UserModel => {
+            sqlx::query_scalar!(
+                r#"
+            SELECT (model_definitions.deleted_at IS NOT NULL
+                OR games.deleted_at IS NOT NULL) as "in_trash!: bool"
+            FROM user_models
+            INNER JOIN model_definitions ON model_definitions.id = user_models.model_definition_id
+            INNER JOIN games ON games.id = model_definitions.game_id
+            WHERE user_models.id = $1
+            "#,
+                id
+            )
+            .fetch_optional(&mut *conn)
+            .await
+        }
+        _ => Ok(None),
+    };
+
+    record
+        .map(|in_trash| in_trash.unwrap_or(false))
+        .map_err(|error| AppError::internal("failed to check parent in trash", error))
+}
+
+async fn photo_files(
+    conn: &mut SqliteConnection,
+    state: &AppState,
+    user_model_id: &str,
+) -> Result<Vec<PathBuf>, AppError> {
+    let records = sqlx::query!(
+        r#"
+        SELECT id as "id!: String", content_type
+        FROM photos
+        WHERE user_model_id = $1
+        "#,
+        user_model_id
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|error| AppError::internal("failed to fetch photos to purge", error))?;
+
+    Ok(records
+        .into_iter()
+        .flat_map(|record| photos::stored_files(&state.photo_dir, &record.id, &record.content_type))
+        .collect())
+}
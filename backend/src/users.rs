@@ -0,0 +1,136 @@
+use axum::{extract::State, http::StatusCode, Json};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    audit::{self, Action, Actor, Entity},
+    parse_uuid, AppError, AppState, ErrorBody,
+};
+
+#[derive(Deserialize, ToSchema)]
+pub(crate) struct CreateUser {
+    pub(crate) name: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct User {
+    pub(crate) id: Uuid,
+    pub(crate) name: String,
+    created_at: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/users",
+    tag = "users",
+    request_body = CreateUser,
+    responses(
+        (status = 201, body = User),
+        (status = 409, description = "A user with this name exists", body = ErrorBody),
+        (status = 422, description = "The name is blank", body = ErrorBody),
+    )
+)]
+pub(crate) async fn create_user(
+    State(state): State<AppState>,
+    actor: Actor,
+    Json(payload): Json<CreateUser>,
+) -> Result<(StatusCode, Json<User>), AppError> {
+    tracing::info!(name = %payload.name, "creating user");
+    let name = payload.name.trim();
+    if name.is_empty() {
+        return Err(AppError::unprocessable("user name is required"));
+    }
+
+    let id = Uuid::new_v4();
+    let id_value = id.to_string();
+    let mut transaction = state
+        .pool
+        .begin()
+        .await
+        .map_err(|error| AppError::internal("failed to start transaction", error))?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO users (id, name, created_at)
+        VALUES ($1, $2, datetime('now'))
+        "#,
+        id_value,
+        name
+    )
+    .execute(&mut *transaction)
+    .await
+    .map_err(|error| match &error {
+        sqlx::Error::Database(db_error)
+            if db_error
+                .message()
+                .contains("UNIQUE constraint failed: users.name") =>
+        {
+            AppError::conflict("user name already exists")
+        }
+        _ => AppError::internal("failed to create user", error),
+    })?;
+
+    audit::record_row(
+        &mut transaction,
+        &actor,
+        Entity::User,
+        &id_value,
+        Action::Create,
+        None,
+    )
+    .await?;
+
+    transaction
+        .commit()
+        .await
+        .map_err(|error| AppError::internal("failed to commit transaction", error))?;
+    state
+        .events
+        .changed(Entity::User, &id_value, Action::Create);
+
+    let user = load_users(&state.pool, Some(&id_value))
+        .await?
+        .pop()
+        .ok_or_else(|| AppError::internal_message("failed to load user"))?;
+
+    Ok((StatusCode::CREATED, Json(user)))
+}
+
+#[utoipa::path(get, path = "/users", tag = "users", responses((status = 200, body = Vec<User>)))]
+pub(crate) async fn list_users(State(state): State<AppState>) -> Result<Json<Vec<User>>, AppError> {
+    tracing::info!("listing users");
+    let users = load_users(&state.read_pool, None).await?;
+    tracing::info!(count = users.len(), "listed users");
+    Ok(Json(users))
+}
+
+async fn load_users(pool: &SqlitePool, id: Option<&str>) -> Result<Vec<User>, AppError> {
+    let records = sqlx::query!(
+        r#"
+        SELECT
+            id as "id!: String",
+            name,
+            created_at as "created_at!: String"
+        FROM users
+        WHERE $1 IS NULL OR id = $1
+        ORDER BY name
+        "#,
+        id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|error| AppError::internal("failed to fetch users", error))?;
+
+    records
+        .into_iter()
+        .map(|record| {
+            Ok(User {
+                id: parse_uuid(record.id)?,
+                name: record.name,
+                created_at: record.created_at,
+            })
+        })
+        .collect()
+}
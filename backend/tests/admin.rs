@@ -0,0 +1,109 @@
+mod common;
+
+use axum::http::StatusCode;
+use backend::{admin, app};
+use serde_json::json;
+
+use common::{json_body, request, setup_state};
+
+#[tokio::test]
+async fn seed_fills_an_empty_database_once() -> Result<(), Box<dyn std::error::Error>> {
+    let state = setup_state().await?;
+    let app = app(state.clone());
+
+    let summary = admin::seed(&state).await?;
+    assert_eq!(summary.games, 2);
+    assert_eq!(summary.model_definitions, 6);
+    assert_eq!(summary.user_models, 6);
+
+    let error = admin::seed(&state).await.unwrap_err();
+    assert!(error.to_string().contains("already has games"));
+
+    let stats = admin::stats(&state).await?;
+    let response = request(&app, "GET", "/api/v1/stats", None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(json_body(response).await, stats);
+    assert_eq!(stats["games"], 2);
+    assert_eq!(stats["user_models"], 6);
+    assert_eq!(stats["models"], 31);
+    assert_eq!(stats["models_by_status"]["painted"], 15);
+
+    let response = request(&app, "GET", "/api/v1/audit?entity=game", None).await;
+    let entries = json_body(response).await;
+    assert_eq!(entries[0]["actor"], admin::CLI_ACTOR);
+    Ok(())
+}
+
+#[tokio::test]
+async fn export_then_import_copies_the_collection() -> Result<(), Box<dyn std::error::Error>> {
+    let source = setup_state().await?;
+    admin::seed(&source).await?;
+    let source_app = app(source.clone());
+    let response = request(&source_app, "GET", "/api/v1/user-models", None).await;
+    let lots = json_body(response).await;
+    let trashed = lots[0]["id"].as_str().expect("lot id").to_string();
+    let response = request(
+        &source_app,
+        "DELETE",
+        &format!("/api/v1/user-models/{trashed}"),
+        None,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let path =
+        std::env::temp_dir().join(format!("mini-tracker-export-{}.json", uuid::Uuid::new_v4()));
+    assert_eq!(admin::export(&source, &path).await?, 13);
+
+    let target = setup_state().await?;
+    let summary = admin::import(&target, &path).await?;
+    assert_eq!(summary.accepted, 13);
+    assert!(summary.rejected.is_empty());
+
+    let summary = admin::import(&target, &path).await?;
+    assert_eq!(summary.accepted, 0);
+    assert_eq!(summary.conflicts, 13);
+
+    let target_app = app(target.clone());
+    let response = request(&target_app, "GET", "/api/v1/user-models", None).await;
+    let imported = json_body(response).await;
+    assert_eq!(imported.as_array().map(Vec::len), Some(5));
+    assert!(imported
+        .as_array()
+        .into_iter()
+        .flatten()
+        .all(|lot| lot["id"] != trashed.as_str()));
+
+    std::fs::write(&path, "not json")?;
+    let error = admin::import(&target, &path).await.unwrap_err();
+    assert!(error.to_string().contains("is not an export file"));
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn users_are_unique_by_name() -> Result<(), Box<dyn std::error::Error>> {
+    let state = setup_state().await?;
+    let app = app(state.clone());
+
+    let response = request(&app, "POST", "/api/v1/users", Some(json!({ "name": " Ana " }))).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(json_body(response).await["name"], "Ana");
+
+    let error = admin::create_user(&state, "ana").await.unwrap_err();
+    assert_eq!(error.to_string(), "user name already exists");
+    let error = admin::create_user(&state, "  ").await.unwrap_err();
+    assert_eq!(error.to_string(), "user name is required");
+
+    admin::create_user(&state, "Bo").await?;
+    let response = request(&app, "GET", "/api/v1/users", None).await;
+    let users = json_body(response).await;
+    let names: Vec<_> = users
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|user| user["name"].clone())
+        .collect();
+    assert_eq!(names, [json!("Ana"), json!("Bo")]);
+    Ok(())
+}
@@ -0,0 +1,263 @@
+mod common;
+
+use backend::app;
+use serde_json::json;
+use uuid::Uuid;
+
+use common::{send, setup_state};
+
+async fn create_game(app: &axum::Router, name: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let (status, data) = send(app, "POST", "/api/v1/games", Some(json!({ "name": name }))).await?;
+    assert_eq!(status, 201);
+    Ok(data["id"].as_str().ok_or("missing game id")?.to_string())
+}
+
+async fn create_user_model(
+    app: &axum::Router,
+    game_id: &str,
+    definition: serde_json::Value,
+    quantity: i32,
+    status: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let mut payload = definition;
+    payload["game_id"] = json!(game_id);
+    let (code, data) = send(app, "POST", "/api/v1/model-definitions", Some(payload)).await?;
+    assert_eq!(code, 201);
+    let model_definition_id = data["id"]
+        .as_str()
+        .ok_or("missing model definition id")?
+        .to_string();
+
+    let payload = json!({
+        "model_definition_id": model_definition_id,
+        "quantity": quantity,
+        "status": status
+    });
+    let (code, data) = send(app, "POST", "/api/v1/user-models", Some(payload)).await?;
+    assert_eq!(code, 201);
+    Ok(data["id"]
+        .as_str()
+        .ok_or("missing user model id")?
+        .to_string())
+}
+
+async fn create_army_list(
+    app: &axum::Router,
+    game_id: &str,
+    points_limit: i64,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let payload = json!({ "name": "Tournament", "game_id": game_id, "points_limit": points_limit });
+    let (status, data) = send(app, "POST", "/api/v1/army-lists", Some(payload)).await?;
+    assert_eq!(status, 201);
+    Ok(data["id"]
+        .as_str()
+        .ok_or("missing army list id")?
+        .to_string())
+}
+
+fn issue_kinds(data: &serde_json::Value) -> Vec<String> {
+    data["issues"]
+        .as_array()
+        .map(|issues| {
+            issues
+                .iter()
+                .filter_map(|issue| issue["kind"].as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[tokio::test]
+async fn valid_list_totals_points() -> Result<(), Box<dyn std::error::Error>> {
+    let state = setup_state().await?;
+    let app = app(state);
+
+    let game_id = create_game(&app, "Warhammer 40,000").await?;
+    let definition = json!({ "name": "Intercessors", "points": 80, "unit_size": 5 });
+    let intercessors = create_user_model(&app, &game_id, definition, 10, "painted").await?;
+    let definition = json!({ "name": "Captain", "points": 95 });
+    let captain = create_user_model(&app, &game_id, definition, 1, "painted").await?;
+    let list_id = create_army_list(&app, &game_id, 500).await?;
+
+    let uri = format!("/api/v1/army-lists/{list_id}/entries");
+    let payload = json!({ "user_model_id": intercessors, "quantity": 10 });
+    let (status, data) = send(&app, "POST", &uri, Some(payload)).await?;
+    assert_eq!(status, 201);
+    assert_eq!(data["points"], 160);
+    let payload = json!({ "user_model_id": captain, "quantity": 1 });
+    let (status, _) = send(&app, "POST", &uri, Some(payload)).await?;
+    assert_eq!(status, 201);
+
+    let (status, data) = send(&app, "GET", &format!("/api/v1/army-lists/{list_id}"), None).await?;
+    assert_eq!(status, 200);
+    assert_eq!(data["game"]["name"], "Warhammer 40,000");
+    assert_eq!(
+        data["entries"].as_array().ok_or("expected entries")?.len(),
+        2
+    );
+
+    let uri = format!("/api/v1/army-lists/{list_id}/validation");
+    let (status, data) = send(&app, "GET", &uri, None).await?;
+    assert_eq!(status, 200);
+    assert_eq!(data["valid"], true);
+    assert_eq!(data["total_points"], 255);
+    assert_eq!(data["points_limit"], 500);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn validation_reports_problems() -> Result<(), Box<dyn std::error::Error>> {
+    let state = setup_state().await?;
+    let app = app(state);
+
+    let game_id = create_game(&app, "Age of Sigmar").await?;
+    let other_game_id = create_game(&app, "Kill Team").await?;
+    let definition = json!({ "name": "Liberators", "points": 100, "unit_size": 5 });
+    let liberators = create_user_model(&app, &game_id, definition, 5, "assembled").await?;
+    let definition = json!({ "name": "Lord-Celestant" });
+    let lord = create_user_model(&app, &game_id, definition, 1, "painted").await?;
+    let definition = json!({ "name": "Kommandos", "points": 10 });
+    let kommandos = create_user_model(&app, &other_game_id, definition, 10, "painted").await?;
+    let list_id = create_army_list(&app, &game_id, 100).await?;
+
+    let uri = format!("/api/v1/army-lists/{list_id}/entries");
+    for (user_model_id, quantity) in [(&liberators, 7), (&lord, 1), (&kommandos, 1)] {
+        let payload = json!({ "user_model_id": user_model_id, "quantity": quantity });
+        let (status, _) = send(&app, "POST", &uri, Some(payload)).await?;
+        assert_eq!(status, 201);
+    }
+
+    let uri = format!("/api/v1/army-lists/{list_id}/validation");
+    let (status, data) = send(&app, "GET", &uri, None).await?;
+    assert_eq!(status, 200);
+    assert_eq!(data["valid"], false);
+    assert_eq!(data["total_points"], 210);
+    assert_eq!(
+        issue_kinds(&data),
+        vec![
+            "not_enough_owned",
+            "not_painted",
+            "incomplete_unit",
+            "missing_points",
+            "wrong_game",
+            "over_points_limit",
+        ]
+    );
+    assert!(data["issues"][5]["entry_id"].is_null());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn army_list_entries_are_validated() -> Result<(), Box<dyn std::error::Error>> {
+    let state = setup_state().await?;
+    let app = app(state);
+
+    let game_id = create_game(&app, "Bolt Action").await?;
+    let payload = json!({ "name": "Rifle Squad", "game_id": game_id, "points": -5 });
+    let (status, _) = send(&app, "POST", "/api/v1/model-definitions", Some(payload)).await?;
+    assert_eq!(status, 422);
+
+    let payload = json!({ "name": "Bad", "game_id": Uuid::new_v4() });
+    let (status, _) = send(&app, "POST", "/api/v1/army-lists", Some(payload)).await?;
+    assert_eq!(status, 404);
+
+    let definition = json!({ "name": "Rifle Squad", "points": 50 });
+    let squad = create_user_model(&app, &game_id, definition, 10, "painted").await?;
+    let list_id = create_army_list(&app, &game_id, 1000).await?;
+
+    let uri = format!("/api/v1/army-lists/{list_id}/entries");
+    let payload = json!({ "user_model_id": squad, "quantity": 0 });
+    let (status, _) = send(&app, "POST", &uri, Some(payload)).await?;
+    assert_eq!(status, 422);
+
+    let payload = json!({ "user_model_id": Uuid::new_v4(), "quantity": 1 });
+    let (status, _) = send(&app, "POST", &uri, Some(payload)).await?;
+    assert_eq!(status, 404);
+
+    let payload = json!({ "user_model_id": squad, "quantity": 1 });
+    let (_, data) = send(&app, "POST", &uri, Some(payload)).await?;
+    let entry_id = data["id"].as_str().ok_or("missing entry id")?.to_string();
+
+    let uri = format!("/api/v1/army-lists/{list_id}/entries/{entry_id}");
+    let (status, _) = send(&app, "DELETE", &uri, None).await?;
+    assert_eq!(status, 204);
+    let (status, _) = send(&app, "DELETE", &uri, None).await?;
+    assert_eq!(status, 404);
+
+    let uri = format!("/api/v1/army-lists/{list_id}");
+    let (status, _) = send(&app, "DELETE", &uri, None).await?;
+    assert_eq!(status, 204);
+    let (status, _) = send(&app, "GET", &uri, None).await?;
+    assert_eq!(status, 404);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn trashed_lots_fail_validation() -> Result<(), Box<dyn std::error::Error>> {
+    let state = setup_state().await?;
+    let app = app(state);
+
+    let game_id = create_game(&app, "Warcry").await?;
+    let definition = json!({ "name": "Untamed Beasts", "points": 15 });
+    let beasts = create_user_model(&app, &game_id, definition, 8, "painted").await?;
+    let definition = json!({ "name": "Iron Golem", "points": 12 });
+    let golem = create_user_model(&app, &game_id, definition, 8, "painted").await?;
+    let list_id = create_army_list(&app, &game_id, 1000).await?;
+
+    let uri = format!("/api/v1/army-lists/{list_id}/entries");
+    for user_model_id in [&beasts, &golem] {
+        let payload = json!({ "user_model_id": user_model_id, "quantity": 8 });
+        let (status, _) = send(&app, "POST", &uri, Some(payload)).await?;
+        assert_eq!(status, 201);
+    }
+
+    let uri = format!("/api/v1/user-models/{beasts}");
+    let (status, _) = send(&app, "DELETE", &uri, None).await?;
+    assert_eq!(status, 204);
+
+    let uri = format!("/api/v1/army-lists/{list_id}/validation");
+    let (status, data) = send(&app, "GET", &uri, None).await?;
+    assert_eq!(status, 200);
+    assert_eq!(data["valid"], false);
+    assert_eq!(data["total_points"], 96);
+    assert_eq!(issue_kinds(&data), vec!["in_trash"]);
+
+    let uri = format!("/api/v1/army-lists/{list_id}/entries");
+    let payload = json!({ "user_model_id": beasts, "quantity": 1 });
+    let (status, _) = send(&app, "POST", &uri, Some(payload)).await?;
+    assert_eq!(status, 404);
+
+    let uri = format!("/api/v1/army-lists/{list_id}");
+    let (_, data) = send(&app, "GET", &uri, None).await?;
+    assert_eq!(data["entries"].as_array().map(Vec::len), Some(1));
+    assert_eq!(data["entries"][0]["user_model_id"], golem);
+
+    let uri = format!("/api/v1/trash/{beasts}/restore");
+    let (status, _) = send(&app, "POST", &uri, None).await?;
+    assert_eq!(status, 204);
+    let uri = format!("/api/v1/army-lists/{list_id}/validation");
+    let (_, data) = send(&app, "GET", &uri, None).await?;
+    assert_eq!(data["valid"], true);
+    assert_eq!(data["total_points"], 216);
+
+    // Lots whose model definition is in the trash cannot be added either.
+    let definition = json!({ "name": "Spire Tyrants", "points": 10 });
+    let tyrants = create_user_model(&app, &game_id, definition, 8, "painted").await?;
+    let (_, data) = send(&app, "GET", &format!("/api/v1/user-models/{tyrants}"), None).await?;
+    let model_definition_id = data["model_definition_id"]
+        .as_str()
+        .ok_or("missing model definition id")?;
+    let uri = format!("/api/v1/model-definitions/{model_definition_id}");
+    let (status, _) = send(&app, "DELETE", &uri, None).await?;
+    assert_eq!(status, 204);
+    let uri = format!("/api/v1/army-lists/{list_id}/entries");
+    let payload = json!({ "user_model_id": tyrants, "quantity": 1 });
+    let (status, data) = send(&app, "POST", &uri, Some(payload)).await?;
+    assert_eq!(status, 404);
+    assert_eq!(data["error"], "user model not found");
+
+    Ok(())
+}
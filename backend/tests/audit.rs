@@ -0,0 +1,148 @@
+mod common;
+
+use axum::{body::Body, http::Request};
+use backend::app;
+use serde_json::json;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use common::setup_state;
+
+async fn send(
+    app: &axum::Router,
+    method: &str,
+    uri: &str,
+    actor: Option<&str>,
+    payload: Option<serde_json::Value>,
+) -> Result<(u16, serde_json::Value), Box<dyn std::error::Error>> {
+    let mut builder = Request::builder().method(method).uri(uri);
+    if let Some(actor) = actor {
+        builder = builder.header("x-actor", actor);
+    }
+    let request = match payload {
+        Some(payload) => builder
+            .header("content-type", "application/json")
+            .body(Body::from(payload.to_string()))?,
+        None => builder.body(Body::empty())?,
+    };
+
+    let response = app.clone().oneshot(request).await?;
+    let status = response.status().as_u16();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    let data = if body.is_empty() {
+        serde_json::Value::Null
+    } else {
+        serde_json::from_slice(&body)?
+    };
+
+    Ok((status, data))
+}
+
+#[tokio::test]
+async fn mutations_are_recorded_with_actor_and_snapshots() -> Result<(), Box<dyn std::error::Error>>
+{
+    let app = app(setup_state().await?);
+
+    let payload = json!({ "name": "Basing" });
+    let (status, tag) = send(&app, "POST", "/api/v1/tags", Some("alice"), Some(payload)).await?;
+    assert_eq!(status, 201);
+    let tag_id = tag["id"].as_str().ok_or("missing tag id")?.to_string();
+
+    let payload = json!({ "name": "Basing done" });
+    let uri = format!("/api/v1/tags/{tag_id}");
+    let (status, _) = send(&app, "PATCH", &uri, Some("bob"), Some(payload)).await?;
+    assert_eq!(status, 200);
+
+    let (status, _) = send(&app, "DELETE", &uri, None, None).await?;
+    assert_eq!(status, 204);
+
+    let uri = format!("/api/v1/audit?entity=tag&entity_id={tag_id}");
+    let (status, entries) = send(&app, "GET", &uri, None, None).await?;
+    assert_eq!(status, 200);
+    let entries = entries.as_array().ok_or("expected audit entries")?;
+    assert_eq!(entries.len(), 3);
+
+    let (deleted, updated, created) = (&entries[0], &entries[1], &entries[2]);
+    assert_eq!(created["action"], "create");
+    assert_eq!(created["actor"], "alice");
+    assert!(created["before"].is_null());
+    assert_eq!(created["after"]["name"], "Basing");
+
+    assert_eq!(updated["action"], "update");
+    assert_eq!(updated["actor"], "bob");
+    assert_eq!(updated["before"]["name"], "Basing");
+    assert_eq!(updated["after"]["name"], "Basing done");
+
+    assert_eq!(deleted["action"], "delete");
+    assert_eq!(deleted["actor"], "anonymous");
+    assert_eq!(deleted["before"]["name"], "Basing done");
+    assert!(deleted["after"].is_null());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn audit_log_filters_by_entity_and_time_range() -> Result<(), Box<dyn std::error::Error>> {
+    let app = app(setup_state().await?);
+
+    let payload = json!({ "name": "Warhammer 40k" });
+    let (status, _) = send(&app, "POST", "/api/v1/games", None, Some(payload)).await?;
+    assert_eq!(status, 201);
+    let payload = json!({ "name": "Hobby Room", "kind": "room" });
+    let (status, _) = send(&app, "POST", "/api/v1/locations", None, Some(payload)).await?;
+    assert_eq!(status, 201);
+
+    let (status, entries) = send(&app, "GET", "/api/v1/audit?entity=location", None, None).await?;
+    assert_eq!(status, 200);
+    let entries = entries.as_array().ok_or("expected audit entries")?;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["entity"], "location");
+    assert_eq!(entries[0]["after"]["name"], "Hobby Room");
+
+    let uri = "/api/v1/audit?since=2000-01-01&until=2999-01-01";
+    let (status, entries) = send(&app, "GET", uri, None, None).await?;
+    assert_eq!(status, 200);
+    assert_eq!(entries.as_array().map(Vec::len), Some(2));
+
+    let (status, entries) = send(&app, "GET", "/api/v1/audit?since=2999-01-01", None, None).await?;
+    assert_eq!(status, 200);
+    assert_eq!(entries.as_array().map(Vec::len), Some(0));
+
+    let (status, _) = send(&app, "GET", "/api/v1/audit?since=yesterday", None, None).await?;
+    assert_eq!(status, 400);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn audit_log_is_append_only() -> Result<(), Box<dyn std::error::Error>> {
+    let state = setup_state().await?;
+    let pool = state.pool.clone();
+    let app = app(state);
+
+    let payload = json!({ "name": "Age of Sigmar" });
+    let (status, _) = send(&app, "POST", "/api/v1/games", None, Some(payload)).await?;
+    assert_eq!(status, 201);
+
+    let payload = json!({ "name": "Missing" });
+    let uri = format!("/api/v1/tags/{}", Uuid::new_v4());
+    let (status, _) = send(&app, "PATCH", &uri, None, Some(payload)).await?;
+    assert_eq!(status, 404);
+
+    let (status, entries) = send(&app, "GET", "/api/v1/audit", None, None).await?;
+    assert_eq!(status, 200);
+    assert_eq!(entries.as_array().map(Vec::len), Some(1));
+
+    let updated = sqlx::query("UPDATE audit_log SET actor = 'mallory'")
+        .execute(&pool)
+        .await;
+    assert!(updated.is_err());
+    let deleted = sqlx::query("DELETE FROM audit_log").execute(&pool).await;
+    assert!(deleted.is_err());
+
+    let (status, entries) = send(&app, "GET", "/api/v1/audit", None, None).await?;
+    assert_eq!(status, 200);
+    assert_eq!(entries[0]["actor"], "anonymous");
+
+    Ok(())
+}
@@ -0,0 +1,195 @@
+mod common;
+
+use std::path::{Path, PathBuf};
+
+use axum::http::StatusCode;
+use backend::{admin, app, AppState, BackupSettings};
+use serde_json::json;
+
+use common::{json_body, request, state};
+
+/// Backups need a database on disk: `VACUUM INTO` from an in-memory one
+/// writes nowhere.
+async fn setup_state(dir: &Path) -> Result<AppState, Box<dyn std::error::Error>> {
+    std::fs::create_dir_all(dir)?;
+    let pool = backend::connect(&database_url(dir), 1).await?;
+    backend::migrate(&pool).await?;
+
+    Ok(AppState {
+        backups: BackupSettings {
+            dir: dir.join("backups"),
+            keep_daily: 3,
+            keep_weekly: 3,
+            ..BackupSettings::default()
+        },
+        ..state(pool.clone(), pool)
+    })
+}
+
+fn temp_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("mini-tracker-backups-{}", uuid::Uuid::new_v4()))
+}
+
+fn database_url(dir: &Path) -> String {
+    format!("sqlite://{}", dir.join("live.db").display())
+}
+
+async fn count_games(path: &Path) -> Result<i64, Box<dyn std::error::Error>> {
+    let pool = backend::connect(&format!("sqlite://{}", path.display()), 1).await?;
+    let count = sqlx::query_scalar("SELECT COUNT(*) FROM games")
+        .fetch_one(&pool)
+        .await?;
+    pool.close().await;
+    Ok(count)
+}
+
+#[tokio::test]
+async fn backups_are_listed_and_rotated() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = temp_dir();
+    let state = setup_state(&dir).await?;
+    let app = app(state.clone());
+
+    let response = request(&app, "GET", "/api/v1/admin/backups", None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(json_body(response).await, json!([]));
+
+    // Two weeks of older backups: Monday 5 to Thursday 8 and Monday 12 to
+    // Tuesday 13 January, with two on the 13th.
+    let backup_dir = dir.join("backups");
+    std::fs::create_dir_all(&backup_dir)?;
+    for stamp in [
+        "20260105-120000",
+        "20260106-120000",
+        "20260107-120000",
+        "20260108-120000",
+        "20260112-120000",
+        "20260113-080000",
+        "20260113-200000",
+    ] {
+        std::fs::write(backup_dir.join(format!("mini-tracker-{stamp}.db")), "")?;
+    }
+    std::fs::write(backup_dir.join("notes.txt"), "keep me")?;
+
+    request(&app, "POST", "/api/v1/games", Some(json!({ "name": "Warhammer" }))).await;
+    let response = request(&app, "POST", "/api/v1/admin/backups", None).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let created = json_body(response).await;
+    let name = created["backup"]["name"].as_str().expect("backup name");
+    assert!(name.starts_with("mini-tracker-"));
+    assert!(created["backup"]["size_bytes"].as_u64() > Some(0));
+    // Three days and three weeks: today, the 13th (newest copy), the 12th and
+    // the newest from the week of the 5th.
+    assert_eq!(
+        created["removed"],
+        json!([
+            "mini-tracker-20260113-080000.db",
+            "mini-tracker-20260107-120000.db",
+            "mini-tracker-20260106-120000.db",
+            "mini-tracker-20260105-120000.db",
+        ])
+    );
+
+    let response = request(&app, "GET", "/api/v1/admin/backups", None).await;
+    let listed = json_body(response).await;
+    let names: Vec<_> = listed
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|backup| backup["name"].as_str().unwrap_or_default())
+        .collect();
+    assert_eq!(
+        names,
+        [
+            name,
+            "mini-tracker-20260113-200000.db",
+            "mini-tracker-20260112-120000.db",
+            "mini-tracker-20260108-120000.db",
+        ]
+    );
+    assert_eq!(listed[1]["created_at"], "2026-01-13 20:00:00");
+    assert!(backup_dir.join("notes.txt").exists());
+    assert_eq!(count_games(&backup_dir.join(name)).await?, 1);
+
+    state.pool.close().await;
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn rotation_keeps_the_new_backup() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = temp_dir();
+    let mut state = setup_state(&dir).await?;
+    state.backups.keep_daily = 0;
+    state.backups.keep_weekly = 0;
+    let app = app(state.clone());
+
+    let backup_dir = dir.join("backups");
+    std::fs::create_dir_all(&backup_dir)?;
+    std::fs::write(backup_dir.join("mini-tracker-20260105-120000.db"), "")?;
+
+    let response = request(&app, "POST", "/api/v1/admin/backups", None).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let created = json_body(response).await;
+    assert_eq!(
+        created["removed"],
+        json!(["mini-tracker-20260105-120000.db"])
+    );
+    let name = created["backup"]["name"].as_str().expect("backup name");
+    assert!(backup_dir.join(name).exists());
+
+    state.pool.close().await;
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn restore_swaps_in_a_checked_backup() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = temp_dir();
+    let state = setup_state(&dir).await?;
+    let app = app(state.clone());
+
+    request(&app, "POST", "/api/v1/games", Some(json!({ "name": "Warhammer" }))).await;
+    let backup = admin::backup(&state).await?.path;
+    request(&app, "POST", "/api/v1/games", Some(json!({ "name": "Kill Team" }))).await;
+    state.pool.close().await;
+
+    let summary = admin::restore(&database_url(&dir), &backup).await?;
+    assert!(summary.schema_version > 0);
+    let previous = summary.previous.expect("old database to be kept");
+    assert_eq!(count_games(&dir.join("live.db")).await?, 1);
+    assert_eq!(count_games(&previous).await?, 2);
+
+    let garbage = dir.join("garbage.db");
+    std::fs::write(&garbage, "this is not a database")?;
+    assert!(admin::restore(&database_url(&dir), &garbage).await.is_err());
+
+    let other = dir.join("other.db");
+    let pool = backend::connect(&format!("sqlite://{}", other.display()), 1).await?;
+    sqlx::query("CREATE TABLE notes (text TEXT)")
+        .execute(&pool)
+        .await?;
+    pool.close().await;
+    let error = admin::restore(&database_url(&dir), &other)
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("is not a Mini Tracker database"));
+
+    let newer = dir.join("newer.db");
+    std::fs::copy(&backup, &newer)?;
+    let pool = backend::connect(&format!("sqlite://{}", newer.display()), 1).await?;
+    sqlx::query(
+        "INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time)
+         VALUES (99990101000000, 'from the future', 1, x'00', 0)",
+    )
+    .execute(&pool)
+    .await?;
+    pool.close().await;
+    let error = admin::restore(&database_url(&dir), &newer)
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("newer than this build"));
+    assert_eq!(count_games(&dir.join("live.db")).await?, 1);
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
@@ -0,0 +1,158 @@
+//! Helpers shared by the integration tests. Each test binary uses its own
+//! subset, hence the `dead_code` allowance.
+#![allow(dead_code)]
+
+use axum::{
+    body::{to_bytes, Body},
+    http::Request,
+    response::Response,
+    Router,
+};
+use backend::AppState;
+use serde_json::Value;
+use sqlx::{sqlite::SqlitePoolOptions, SqlitePool};
+use tower::ServiceExt;
+
+/// A migrated in-memory database behind a single connection.
+pub async fn setup_state() -> Result<AppState, Box<dyn std::error::Error>> {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await?;
+
+    sqlx::query("PRAGMA foreign_keys = ON;")
+        .execute(&pool)
+        .await?;
+
+    sqlx::migrate!().run(&pool).await?;
+
+    Ok(state(pool.clone(), pool))
+}
+
+/// State over already migrated pools, with the test defaults for the rest.
+pub fn state(writer: SqlitePool, reader: SqlitePool) -> AppState {
+    AppState {
+        repository: std::sync::Arc::new(backend::SqliteRepository::new(
+            writer.clone(),
+            reader.clone(),
+        )),
+        pool: writer,
+        read_pool: reader,
+        photo_dir: std::env::temp_dir().join("mini-tracker-test-photos"),
+        events: backend::EventBus::new(),
+        presence: backend::PresenceHub::new(),
+        backups: Default::default(),
+    }
+}
+
+/// Sends a request, with `payload` as its JSON body, and returns the status
+/// and the JSON reply (`null` when the body is empty).
+pub async fn send(
+    app: &Router,
+    method: &str,
+    uri: &str,
+    payload: Option<Value>,
+) -> Result<(u16, Value), Box<dyn std::error::Error>> {
+    let response = request(app, method, uri, payload).await;
+    let status = response.status().as_u16();
+    let body = to_bytes(response.into_body(), usize::MAX).await?;
+    let data = if body.is_empty() {
+        Value::Null
+    } else {
+        serde_json::from_slice(&body)?
+    };
+
+    Ok((status, data))
+}
+
+/// Like `send`, but hands back the whole response. Panics instead of
+/// returning errors, so it also works inside spawned tasks.
+pub async fn request(app: &Router, method: &str, uri: &str, body: Option<Value>) -> Response {
+    let request = Request::builder().method(method).uri(uri);
+    let request = match body {
+        Some(body) => request
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string())),
+        None => request.body(Body::empty()),
+    }
+    .expect("request to build");
+    app.clone().oneshot(request).await.expect("request to run")
+}
+
+pub async fn json_body(response: Response) -> Value {
+    let bytes = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body to read");
+    serde_json::from_slice(&bytes).expect("body to be JSON")
+}
+
+/// A migrated schema of its own on the server at `TEST_POSTGRES_URL`, so
+/// tests can run in parallel. Fails rather than falling back to SQLite when
+/// the variable is not set.
+#[cfg(feature = "postgres")]
+pub async fn postgres_repository() -> Result<backend::SharedRepository, Box<dyn std::error::Error>>
+{
+    use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+    use std::str::FromStr;
+
+    let url = std::env::var("TEST_POSTGRES_URL")
+        .map_err(|_| "TEST_POSTGRES_URL must point at a PostgreSQL server to run this test")?;
+    let schema = format!("test_{}", uuid::Uuid::new_v4().simple());
+    let admin = backend::connect_postgres(&url, 1).await?;
+    sqlx::query(&format!("CREATE SCHEMA {schema}"))
+        .execute(&admin)
+        .await?;
+    admin.close().await;
+
+    let options = PgConnectOptions::from_str(&url)?.options([("search_path", schema.as_str())]);
+    let pool = PgPoolOptions::new()
+        .max_connections(2)
+        .connect_with(options)
+        .await?;
+    backend::migrate_postgres(&pool).await?;
+
+    Ok(std::sync::Arc::new(backend::PostgresRepository::new(pool)))
+}
+
+/// The catalog routes over `postgres_repository`.
+#[cfg(feature = "postgres")]
+pub async fn postgres_app() -> Result<Router, Box<dyn std::error::Error>> {
+    let state = backend::CatalogState {
+        repository: postgres_repository().await?,
+        events: backend::EventBus::new(),
+    };
+    Ok(backend::catalog_app(state, &backend::Features::default()))
+}
+
+/// Declares, in a module named after the backend, a test for each listed
+/// check: an `async fn(Router)` in the calling file, given the app that
+/// `$setup` builds. With `ignored` and a reason, the tests only run when
+/// asked for.
+#[macro_export]
+macro_rules! backend_tests {
+    ($backend:ident, $setup:expr, $($name:ident),+ $(,)?) => {
+        mod $backend {
+            use super::*;
+
+            $(
+                #[tokio::test]
+                async fn $name() -> Result<(), Box<dyn std::error::Error>> {
+                    super::$name($setup).await
+                }
+            )+
+        }
+    };
+    (ignored $backend:ident, $setup:expr, $reason:literal, $($name:ident),+ $(,)?) => {
+        mod $backend {
+            use super::*;
+
+            $(
+                #[tokio::test]
+                #[ignore = $reason]
+                async fn $name() -> Result<(), Box<dyn std::error::Error>> {
+                    super::$name($setup).await
+                }
+            )+
+        }
+    };
+}
@@ -0,0 +1,223 @@
+mod common;
+
+use std::collections::HashMap;
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+};
+use backend::{
+    app_with_config,
+    config::{Config, ConfigArgs, ConfigError, LogFormat},
+};
+use clap::Parser;
+use tower::ServiceExt;
+
+use common::setup_state;
+
+#[derive(Parser)]
+struct Cli {
+    #[command(flatten)]
+    config: ConfigArgs,
+}
+
+fn load(args: &[&str], env: &[(&str, &str)]) -> Result<Config, ConfigError> {
+    let cli = Cli::try_parse_from(std::iter::once("backend").chain(args.iter().copied()))
+        .expect("flags to parse");
+    let env: HashMap<String, String> = env
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+    Config::load_with_env(&cli.config, |name| env.get(name).cloned())
+}
+
+fn write_config(contents: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let path = std::env::temp_dir().join(format!("mini-tracker-{}.toml", uuid::Uuid::new_v4()));
+    std::fs::write(&path, contents)?;
+    Ok(path.to_string_lossy().into_owned())
+}
+
+#[test]
+fn flags_override_environment_override_file() -> Result<(), Box<dyn std::error::Error>> {
+    let file = write_config(
+        r#"
+bind = "127.0.0.1:4000"
+database_url = "sqlite://file.db"
+max_connections = 2
+log_format = "json"
+cors_origins = ["http://localhost:8080"]
+
+[features]
+sync = false
+"#,
+    )?;
+
+    let from_file = load(&["--config", &file], &[])?;
+    assert_eq!(from_file.bind.to_string(), "127.0.0.1:4000");
+    assert_eq!(from_file.max_connections, 2);
+    assert_eq!(from_file.log_format, LogFormat::Json);
+    assert!(!from_file.features.sync);
+    assert!(from_file.features.events);
+    assert_eq!(from_file.trash_retention_days, 30);
+
+    let env = [
+        ("MINI_TRACKER_CONFIG", file.as_str()),
+        ("DATABASE_URL", "sqlite://env.db"),
+        ("DATABASE_MAX_CONNECTIONS", "8"),
+        ("CORS_ORIGINS", "https://a.example, https://b.example"),
+        ("DISABLE_FEATURES", "presence,purge-job"),
+    ];
+    let from_env = load(&[], &env)?;
+    assert_eq!(from_env.bind.to_string(), "127.0.0.1:4000");
+    assert_eq!(from_env.database_url.as_deref(), Some("sqlite://env.db"));
+    assert_eq!(from_env.max_connections, 8);
+    assert_eq!(
+        from_env.cors_origins,
+        ["https://a.example", "https://b.example"]
+    );
+    assert!(!from_env.features.presence);
+    assert!(!from_env.features.purge_job);
+
+    let from_flags = load(
+        &[
+            "--database-url",
+            "sqlite://flag.db",
+            "--log-format",
+            "text",
+            "--cors-origin",
+            "*",
+            "--enable",
+            "sync",
+        ],
+        &env,
+    )?;
+    assert_eq!(from_flags.database_url.as_deref(), Some("sqlite://flag.db"));
+    assert_eq!(from_flags.max_connections, 8);
+    assert_eq!(from_flags.log_format, LogFormat::Text);
+    assert_eq!(from_flags.cors_origins, ["*"]);
+    assert!(from_flags.features.sync);
+    from_flags.validate()?;
+
+    let printed = toml::to_string_pretty(&from_flags)?;
+    assert_eq!(toml::from_str::<Config>(&printed)?, from_flags);
+
+    std::fs::remove_file(&file)?;
+    Ok(())
+}
+
+#[test]
+fn bad_settings_are_reported_clearly() -> Result<(), Box<dyn std::error::Error>> {
+    let error = load(&[], &[("BIND_ADDRESS", "localhost")]).unwrap_err();
+    assert!(error
+        .to_string()
+        .starts_with("BIND_ADDRESS: `localhost` is not valid"));
+
+    let error = load(&[], &[("LOG_FORMAT", "xml")]).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "LOG_FORMAT: `xml` is not one of text, json"
+    );
+
+    let file = write_config("max_conections = 3\n")?;
+    let error = load(&["--config", &file], &[]).unwrap_err();
+    assert!(matches!(error, ConfigError::ParseFile { .. }));
+    assert!(error.to_string().contains("max_conections"));
+    std::fs::remove_file(&file)?;
+
+    let error = load(&["--config", "/nonexistent/mini-tracker.toml"], &[]).unwrap_err();
+    assert!(matches!(error, ConfigError::ReadFile { .. }));
+
+    let config = load(&[], &[])?;
+    let error = config.validate().unwrap_err();
+    assert!(error.to_string().contains("DATABASE_URL"));
+
+    let cases = [
+        (
+            &["--max-connections", "0"][..],
+            "max_connections must be at least 1",
+        ),
+        (&["--database-url", "mysql://db"][..], "is not supported"),
+        (
+            &["--cors-origin", "localhost:8080"][..],
+            "must be `*` or a scheme",
+        ),
+        (
+            &["--cors-origin", "*", "--cors-origin", "http://a.example"][..],
+            "cannot mix",
+        ),
+    ];
+    for (args, expected) in cases {
+        let config = load(args, &[("DATABASE_URL", "sqlite://test.db")])?;
+        let error = config.validate().unwrap_err();
+        assert!(
+            error.to_string().contains(expected),
+            "{args:?} gave `{error}`"
+        );
+    }
+
+    let config = load(&["--database-url", "postgres://localhost/minis"], &[])?;
+    assert!(config.uses_postgres());
+    if cfg!(feature = "postgres") {
+        config.validate()?;
+    } else {
+        let error = config.validate().unwrap_err();
+        assert!(error.to_string().contains("--features postgres"));
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn feature_toggles_and_cors_shape_the_router() -> Result<(), Box<dyn std::error::Error>> {
+    let config = load(
+        &[
+            "--disable",
+            "sync",
+            "--cors-origin",
+            "http://localhost:8080",
+        ],
+        &[],
+    )?;
+    let app = app_with_config(setup_state().await?, &config);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/sync/changes")
+                .body(Body::empty())?,
+        )
+        .await?;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/health")
+                .header(header::ORIGIN, "http://localhost:8080")
+                .body(Body::empty())?,
+        )
+        .await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+        "http://localhost:8080"
+    );
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/health")
+                .header(header::ORIGIN, "http://evil.example")
+                .body(Body::empty())?,
+        )
+        .await?;
+    assert!(!response
+        .headers()
+        .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+
+    let config = load(&["--cors-origin", "http://localhost:8080"], &[])?;
+    assert!(config.features.sync);
+    assert!(load(&[], &[])?.cors_layer().is_none());
+    Ok(())
+}
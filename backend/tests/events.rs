@@ -0,0 +1,190 @@
+mod common;
+
+use std::time::Duration;
+
+use axum::{
+    body::{Body, BodyDataStream},
+    http::Request,
+};
+use backend::app;
+use futures_util::StreamExt;
+use serde_json::json;
+use tower::ServiceExt;
+
+use common::{send, setup_state};
+
+/// An open `/events` response and whatever has been read from it so far.
+struct Subscription {
+    body: BodyDataStream,
+    buffer: String,
+}
+
+async fn subscribe(
+    app: &axum::Router,
+    last_event_id: Option<&str>,
+) -> Result<Subscription, Box<dyn std::error::Error>> {
+    let mut builder = Request::builder().uri("/api/v1/events");
+    if let Some(last_event_id) = last_event_id {
+        builder = builder.header("last-event-id", last_event_id);
+    }
+
+    let response = app.clone().oneshot(builder.body(Body::empty())?).await?;
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "text/event-stream");
+
+    Ok(Subscription {
+        body: response.into_body().into_data_stream(),
+        buffer: String::new(),
+    })
+}
+
+impl Subscription {
+    /// Reads the next event, returning its ID (if any) and JSON data.
+    async fn next(
+        &mut self,
+    ) -> Result<(Option<String>, serde_json::Value), Box<dyn std::error::Error>> {
+        loop {
+            if let Some(end) = self.buffer.find("\n\n") {
+                let frame = self.buffer[..end].to_string();
+                self.buffer.drain(..end + 2);
+
+                let mut id = None;
+                let mut data = None;
+                for line in frame.lines() {
+                    if let Some(value) = line.strip_prefix("id:") {
+                        id = Some(value.trim().to_string());
+                    } else if let Some(value) = line.strip_prefix("data:") {
+                        data = Some(serde_json::from_str(value.trim())?);
+                    }
+                }
+                match data {
+                    Some(data) => return Ok((id, data)),
+                    // Keep-alive comments carry no data.
+                    None => continue,
+                }
+            }
+
+            let chunk = tokio::time::timeout(Duration::from_secs(5), self.body.next())
+                .await?
+                .ok_or("event stream ended")??;
+            self.buffer.push_str(std::str::from_utf8(&chunk)?);
+        }
+    }
+}
+
+async fn create_user_model(app: &axum::Router) -> Result<String, Box<dyn std::error::Error>> {
+    let payload = json!({ "name": "Warhammer 40k" });
+    let (status, game) = send(app, "POST", "/api/v1/games", Some(payload)).await?;
+    assert_eq!(status, 201);
+
+    let payload = json!({ "name": "Intercessors", "game_id": game["id"] });
+    let (status, definition) = send(app, "POST", "/api/v1/model-definitions", Some(payload)).await?;
+    assert_eq!(status, 201);
+
+    let payload = json!({
+        "model_definition_id": definition["id"],
+        "quantity": 5,
+        "status": "unassembled"
+    });
+    let (status, user_model) = send(app, "POST", "/api/v1/user-models", Some(payload)).await?;
+    assert_eq!(status, 201);
+
+    Ok(user_model["id"]
+        .as_str()
+        .ok_or("missing user model id")?
+        .to_string())
+}
+
+#[tokio::test]
+async fn mutations_publish_typed_change_events() -> Result<(), Box<dyn std::error::Error>> {
+    let app = app(setup_state().await?);
+    let mut events = subscribe(&app, None).await?;
+
+    let id = create_user_model(&app).await?;
+
+    let (_, event) = events.next().await?;
+    assert_eq!(event["type"], "changed");
+    assert_eq!(event["entity"], "game");
+    assert_eq!(event["action"], "create");
+    let (_, event) = events.next().await?;
+    assert_eq!(event["entity"], "model_definition");
+
+    let (event_id, event) = events.next().await?;
+    assert_eq!(event_id.as_deref(), Some("3"));
+    assert_eq!(event["type"], "user_model_upserted");
+    assert_eq!(event["user_model"]["id"], id.as_str());
+    assert_eq!(event["user_model"]["model_name"], "Intercessors");
+    assert_eq!(event["user_model"]["game_name"], "Warhammer 40k");
+    assert_eq!(event["user_model"]["status"], "unassembled");
+
+    let uri = format!("/api/v1/user-models/{id}");
+    let payload = json!({ "status": "painted" });
+    let (status, _) = send(&app, "PATCH", &uri, Some(payload)).await?;
+    assert_eq!(status, 200);
+
+    let (_, event) = events.next().await?;
+    assert_eq!(event["type"], "user_model_upserted");
+    assert_eq!(event["user_model"]["status"], "painted");
+
+    let (status, _) = send(&app, "DELETE", &uri, None).await?;
+    assert_eq!(status, 204);
+
+    let (_, event) = events.next().await?;
+    assert_eq!(event, json!({ "type": "user_model_removed", "id": id }));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn reconnecting_replays_missed_events() -> Result<(), Box<dyn std::error::Error>> {
+    let app = app(setup_state().await?);
+    let id = create_user_model(&app).await?;
+
+    let mut events = subscribe(&app, Some("1")).await?;
+    let (event_id, event) = events.next().await?;
+    assert_eq!(event_id.as_deref(), Some("2"));
+    assert_eq!(event["entity"], "model_definition");
+    let (event_id, event) = events.next().await?;
+    assert_eq!(event_id.as_deref(), Some("3"));
+    assert_eq!(event["user_model"]["id"], id.as_str());
+
+    let uri = format!("/api/v1/user-models/{id}");
+    let payload = json!({ "quantity": 10 });
+    let (status, _) = send(&app, "PATCH", &uri, Some(payload)).await?;
+    assert_eq!(status, 200);
+
+    let (event_id, event) = events.next().await?;
+    assert_eq!(event_id.as_deref(), Some("4"));
+    assert_eq!(event["user_model"]["quantity"], 10);
+
+    let request = Request::builder()
+        .uri("/api/v1/events")
+        .header("last-event-id", "nope")
+        .body(Body::empty())?;
+    let response = app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), 400);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn unknown_last_event_id_asks_for_resync() -> Result<(), Box<dyn std::error::Error>> {
+    let app = app(setup_state().await?);
+    create_user_model(&app).await?;
+
+    // An ID the server never handed out, e.g. from before a restart.
+    let mut events = subscribe(&app, Some("42")).await?;
+    let (event_id, event) = events.next().await?;
+    assert_eq!(event_id, None);
+    assert_eq!(event, json!({ "type": "resync" }));
+
+    let payload = json!({ "name": "Basing" });
+    let (status, _) = send(&app, "POST", "/api/v1/tags", Some(payload)).await?;
+    assert_eq!(status, 201);
+
+    let (event_id, event) = events.next().await?;
+    assert_eq!(event_id.as_deref(), Some("4"));
+    assert_eq!(event["entity"], "tag");
+
+    Ok(())
+}
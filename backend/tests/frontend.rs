@@ -0,0 +1,157 @@
+mod common;
+
+use std::path::PathBuf;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, Method, Request, StatusCode},
+    response::Response,
+    Router,
+};
+use backend::{app_with_config, config::Config, frontend::Frontend};
+use tempfile::TempDir;
+use tower::ServiceExt;
+
+use common::setup_state;
+
+const INDEX: &str = "<!doctype html><html><body>Mini Tracker</body></html>";
+const SCRIPT: &str = "frontend-0123456789abcdef.js";
+const WASM: &str = "frontend-0123456789abcdef_bg.wasm";
+
+/// A directory laid out like `trunk build` output, removed when dropped.
+fn dist_dir() -> Result<TempDir, Box<dyn std::error::Error>> {
+    let dir = tempfile::Builder::new()
+        .prefix("mini-tracker-dist-")
+        .tempdir()?;
+    std::fs::write(dir.path().join("index.html"), INDEX)?;
+    std::fs::write(
+        dir.path().join(SCRIPT),
+        "export function start() {}\n".repeat(100),
+    )?;
+    std::fs::write(dir.path().join(WASM), [0, 97, 115, 109, 1, 0, 0, 0])?;
+    std::fs::write(dir.path().join("favicon.ico"), [0, 0, 1, 0])?;
+    Ok(dir)
+}
+
+async fn setup_app(dir: PathBuf) -> Result<Router, Box<dyn std::error::Error>> {
+    let config = Config {
+        frontend_dir: Some(dir),
+        ..Config::default()
+    };
+    Ok(app_with_config(setup_state().await?, &config))
+}
+
+async fn get(
+    app: &Router,
+    uri: &str,
+    accept_encoding: Option<&str>,
+) -> Result<Response, Box<dyn std::error::Error>> {
+    let mut request = Request::builder().method(Method::GET).uri(uri);
+    if let Some(encoding) = accept_encoding {
+        request = request.header(header::ACCEPT_ENCODING, encoding);
+    }
+    Ok(app.clone().oneshot(request.body(Body::empty())?).await?)
+}
+
+async fn text(response: Response) -> Result<String, Box<dyn std::error::Error>> {
+    let body = to_bytes(response.into_body(), usize::MAX).await?;
+    Ok(String::from_utf8(body.to_vec())?)
+}
+
+#[tokio::test]
+async fn serves_assets_with_cache_headers() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = dist_dir()?;
+    let app = setup_app(dir.path().to_path_buf()).await?;
+
+    let response = get(&app, "/", None).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CACHE_CONTROL], "no-cache");
+    assert_eq!(
+        response.headers()[header::CONTENT_TYPE],
+        "text/html; charset=utf-8"
+    );
+    assert_eq!(text(response).await?, INDEX);
+
+    let response = get(&app, &format!("/{WASM}"), None).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "application/wasm");
+    assert_eq!(
+        response.headers()[header::CACHE_CONTROL],
+        "public, max-age=31536000, immutable"
+    );
+
+    let response = get(&app, "/favicon.ico", None).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CACHE_CONTROL], "no-cache");
+
+    let response = get(&app, "/missing-0123456789abcdef.js", None).await?;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = get(&app, "/../Cargo.toml", None).await?;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    Ok(())
+}
+
+#[tokio::test]
+async fn client_routes_fall_back_to_index() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = dist_dir()?;
+    let app = setup_app(dir.path().to_path_buf()).await?;
+
+    let response = get(&app, "/collection/shelf-2", None).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CACHE_CONTROL], "no-cache");
+    assert_eq!(text(response).await?, INDEX);
+
+    // The API keeps its own answers.
+    let response = get(&app, "/api/v1/games", None).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(text(response).await?, "[]");
+    let response = get(&app, "/health", None).await?;
+    assert_eq!(text(response).await?, r#"{"status":"ok"}"#);
+    let response = get(&app, "/api/v1/unknown", None).await?;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/collection")
+        .body(Body::empty())?;
+    let response = app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    Ok(())
+}
+
+#[tokio::test]
+async fn assets_are_compressed() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = dist_dir()?;
+    let app = setup_app(dir.path().to_path_buf()).await?;
+    let uri = format!("/{SCRIPT}");
+
+    for encoding in ["gzip", "br"] {
+        let response = get(&app, &uri, Some(encoding)).await?;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_ENCODING], encoding);
+        let body = to_bytes(response.into_body(), usize::MAX).await?;
+        assert!(body.len() < "export function start() {}\n".len() * 100);
+    }
+
+    let response = get(&app, &uri, None).await?;
+    assert!(!response.headers().contains_key(header::CONTENT_ENCODING));
+    Ok(())
+}
+
+#[tokio::test]
+async fn frontend_dir_needs_an_index() -> Result<(), Box<dyn std::error::Error>> {
+    let dist = dist_dir()?;
+    let dir = dist.path().to_path_buf();
+    let mut config = Config {
+        database_url: Some("sqlite::memory:".to_string()),
+        frontend_dir: Some(dir.clone()),
+        ..Config::default()
+    };
+    config.validate()?;
+    assert!(matches!(Frontend::from_config(&config), Some(Frontend::Dir(served)) if served == dir));
+
+    config.frontend_dir = Some(dir.join("missing"));
+    let error = config.validate().unwrap_err().to_string();
+    assert!(error.contains("has no index.html"), "{error}");
+    Ok(())
+}
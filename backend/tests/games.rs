@@ -1,41 +1,17 @@
-use axum::{body::Body, http::Request};
-use backend::{app, AppState};
+mod common;
+
+use axum::{body::Body, http::Request, Router};
+use backend::app;
 use serde_json::json;
-use sqlx::sqlite::SqlitePoolOptions;
 use tower::ServiceExt;
 
-async fn setup_state() -> Result<AppState, Box<dyn std::error::Error>> {
-    let pool = SqlitePoolOptions::new()
-        .max_connections(1)
-        .connect("sqlite::memory:")
-        .await?;
-
-    sqlx::query("PRAGMA foreign_keys = ON;")
-        .execute(&pool)
-        .await?;
-
-    sqlx::migrate!().run(&pool).await?;
-
-    sqlx::query("DELETE FROM user_models;")
-        .execute(&pool)
-        .await?;
-    sqlx::query("DELETE FROM model_definitions;")
-        .execute(&pool)
-        .await?;
-    sqlx::query("DELETE FROM games;").execute(&pool).await?;
-
-    Ok(AppState { pool })
-}
-
-#[tokio::test]
-async fn create_game_returns_created() -> Result<(), Box<dyn std::error::Error>> {
-    let state = setup_state().await?;
-    let app = app(state);
+use common::setup_state;
 
+async fn create_game_returns_created(app: Router) -> Result<(), Box<dyn std::error::Error>> {
     let payload = json!({ "name": "Warhammer" }).to_string();
     let request = Request::builder()
         .method("POST")
-        .uri("/games")
+        .uri("/api/v1/games")
         .header("content-type", "application/json")
         .body(Body::from(payload))?;
 
@@ -52,15 +28,11 @@ async fn create_game_returns_created() -> Result<(), Box<dyn std::error::Error>>
     Ok(())
 }
 
-#[tokio::test]
-async fn duplicate_game_returns_conflict() -> Result<(), Box<dyn std::error::Error>> {
-    let state = setup_state().await?;
-    let app = app(state);
-
+async fn duplicate_game_returns_conflict(app: Router) -> Result<(), Box<dyn std::error::Error>> {
     let payload = json!({ "name": "Infinity" }).to_string();
     let request = Request::builder()
         .method("POST")
-        .uri("/games")
+        .uri("/api/v1/games")
         .header("content-type", "application/json")
         .body(Body::from(payload.clone()))?;
 
@@ -69,7 +41,7 @@ async fn duplicate_game_returns_conflict() -> Result<(), Box<dyn std::error::Err
 
     let request = Request::builder()
         .method("POST")
-        .uri("/games")
+        .uri("/api/v1/games")
         .header("content-type", "application/json")
         .body(Body::from(payload))?;
 
@@ -79,15 +51,11 @@ async fn duplicate_game_returns_conflict() -> Result<(), Box<dyn std::error::Err
     Ok(())
 }
 
-#[tokio::test]
-async fn list_games_returns_ordered_data() -> Result<(), Box<dyn std::error::Error>> {
-    let state = setup_state().await?;
-    let app = app(state);
-
+async fn list_games_returns_ordered_data(app: Router) -> Result<(), Box<dyn std::error::Error>> {
     let payload = json!({ "name": "Kill Team" }).to_string();
     let request = Request::builder()
         .method("POST")
-        .uri("/games")
+        .uri("/api/v1/games")
         .header("content-type", "application/json")
         .body(Body::from(payload))?;
     let response = app.clone().oneshot(request).await?;
@@ -98,7 +66,7 @@ async fn list_games_returns_ordered_data() -> Result<(), Box<dyn std::error::Err
     let payload = json!({ "name": "Legion" }).to_string();
     let request = Request::builder()
         .method("POST")
-        .uri("/games")
+        .uri("/api/v1/games")
         .header("content-type", "application/json")
         .body(Body::from(payload))?;
     let response = app.clone().oneshot(request).await?;
@@ -106,7 +74,7 @@ async fn list_games_returns_ordered_data() -> Result<(), Box<dyn std::error::Err
 
     let request = Request::builder()
         .method("GET")
-        .uri("/games")
+        .uri("/api/v1/games")
         .body(Body::empty())?;
     let response = app.oneshot(request).await?;
     assert_eq!(response.status(), 200);
@@ -121,3 +89,21 @@ async fn list_games_returns_ordered_data() -> Result<(), Box<dyn std::error::Err
 
     Ok(())
 }
+
+crate::backend_tests!(
+    sqlite,
+    app(setup_state().await?),
+    create_game_returns_created,
+    duplicate_game_returns_conflict,
+    list_games_returns_ordered_data,
+);
+
+#[cfg(feature = "postgres")]
+crate::backend_tests!(
+    ignored postgres,
+    common::postgres_app().await?,
+    "needs PostgreSQL: set TEST_POSTGRES_URL and pass --include-ignored",
+    create_game_returns_created,
+    duplicate_game_returns_conflict,
+    list_games_returns_ordered_data,
+);
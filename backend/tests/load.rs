@@ -0,0 +1,119 @@
+mod common;
+
+use std::path::{Path, PathBuf};
+
+use axum::http::StatusCode;
+use backend::{app, AppState};
+use serde_json::json;
+
+use common::{json_body, request, state};
+
+/// Concurrency needs a database on disk: with `sqlite::memory:` every
+/// connection would see a database of its own.
+async fn setup_state(dir: &Path) -> Result<AppState, Box<dyn std::error::Error>> {
+    std::fs::create_dir_all(dir)?;
+    let url = format!("sqlite://{}", dir.join("load.db").display());
+    let pools = backend::connect_pools(&url, 8).await?;
+    backend::migrate(&pools.writer).await?;
+
+    Ok(AppState {
+        photo_dir: dir.join("photos"),
+        ..state(pools.writer, pools.reader)
+    })
+}
+
+fn temp_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("mini-tracker-load-{}", uuid::Uuid::new_v4()))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+async fn concurrent_patches_never_hit_busy_errors() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = temp_dir();
+    let state = setup_state(&dir).await?;
+    let app = app(state.clone());
+
+    let journal_mode: String = sqlx::query_scalar("PRAGMA journal_mode")
+        .fetch_one(&state.read_pool)
+        .await?;
+    assert_eq!(journal_mode, "wal");
+
+    let game = json_body(
+        request(
+            &app,
+            "POST",
+            "/api/v1/games",
+            Some(json!({ "name": "Warhammer" })),
+        )
+        .await,
+    )
+    .await;
+    let definition = json_body(
+        request(
+            &app,
+            "POST",
+            "/api/v1/model-definitions",
+            Some(json!({ "name": "Intercessors", "game_id": game["id"] })),
+        )
+        .await,
+    )
+    .await;
+    let mut lots = Vec::new();
+    for _ in 0..4 {
+        let response = request(
+            &app,
+            "POST",
+            "/api/v1/user-models",
+            Some(json!({
+                "model_definition_id": definition["id"],
+                "quantity": 1,
+                "status": "unassembled",
+            })),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        lots.push(
+            json_body(response).await["id"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+        );
+    }
+
+    // Every lot patched 50 times over, with lists being read alongside.
+    let mut tasks = Vec::new();
+    for round in 0..200 {
+        let app = app.clone();
+        let lot = lots[round % lots.len()].clone();
+        tasks.push(tokio::spawn(async move {
+            let patch = request(
+                &app,
+                "PATCH",
+                &format!("/api/v1/user-models/{lot}"),
+                Some(json!({ "quantity": round + 1, "notes": format!("round {round}") })),
+            )
+            .await
+            .status();
+            let list = request(&app, "GET", "/api/v1/user-models", None)
+                .await
+                .status();
+            (patch, list)
+        }));
+    }
+    for task in tasks {
+        let (patch, list) = task.await?;
+        assert_eq!(patch, StatusCode::OK);
+        assert_eq!(list, StatusCode::OK);
+    }
+
+    let audited: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM audit_log WHERE entity = 'user_model' AND action = 'update'",
+    )
+    .fetch_one(&state.read_pool)
+    .await?;
+    assert_eq!(audited, 200);
+
+    state.pool.close().await;
+    state.read_pool.close().await;
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
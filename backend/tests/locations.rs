@@ -0,0 +1,171 @@
+mod common;
+
+use backend::app;
+use serde_json::json;
+use uuid::Uuid;
+
+use common::{send, setup_state};
+
+async fn create_location(
+    app: &axum::Router,
+    name: &str,
+    kind: &str,
+    parent_id: Option<Uuid>,
+) -> Result<Uuid, Box<dyn std::error::Error>> {
+    let payload = json!({ "name": name, "kind": kind, "parent_id": parent_id });
+    let (status, data) = send(app, "POST", "/api/v1/locations", Some(payload)).await?;
+    assert_eq!(status, 201);
+
+    let id = data["id"]
+        .as_str()
+        .ok_or("missing location id")?
+        .parse::<Uuid>()?;
+    Ok(id)
+}
+
+async fn create_user_model(
+    app: &axum::Router,
+    name: &str,
+    quantity: i32,
+    location_id: Option<Uuid>,
+) -> Result<Uuid, Box<dyn std::error::Error>> {
+    let (status, data) = send(app, "POST", "/api/v1/games", Some(json!({ "name": name }))).await?;
+    assert_eq!(status, 201);
+    let game_id = data["id"].as_str().ok_or("missing game id")?.to_string();
+
+    let payload = json!({ "name": name, "game_id": game_id });
+    let (status, data) = send(app, "POST", "/api/v1/model-definitions", Some(payload)).await?;
+    assert_eq!(status, 201);
+    let model_definition_id = data["id"]
+        .as_str()
+        .ok_or("missing model definition id")?
+        .to_string();
+
+    let payload = json!({
+        "model_definition_id": model_definition_id,
+        "quantity": quantity,
+        "status": "unassembled",
+        "location_id": location_id
+    });
+    let (status, data) = send(app, "POST", "/api/v1/user-models", Some(payload)).await?;
+    assert_eq!(status, 201);
+
+    let id = data["id"]
+        .as_str()
+        .ok_or("missing user model id")?
+        .parse::<Uuid>()?;
+    Ok(id)
+}
+
+#[tokio::test]
+async fn location_hierarchy_is_validated() -> Result<(), Box<dyn std::error::Error>> {
+    let state = setup_state().await?;
+    let app = app(state);
+
+    let room_id = create_location(&app, "Hobby Room", "room", None).await?;
+
+    let payload = json!({ "name": "Loose Box", "kind": "box", "parent_id": room_id });
+    let (status, _) = send(&app, "POST", "/api/v1/locations", Some(payload)).await?;
+    assert_eq!(status, 422);
+
+    let payload = json!({ "name": "Orphan Shelf", "kind": "shelf" });
+    let (status, _) = send(&app, "POST", "/api/v1/locations", Some(payload)).await?;
+    assert_eq!(status, 422);
+
+    let payload = json!({ "name": "Shelf", "kind": "shelf", "parent_id": Uuid::new_v4() });
+    let (status, _) = send(&app, "POST", "/api/v1/locations", Some(payload)).await?;
+    assert_eq!(status, 404);
+
+    let shelf_id = create_location(&app, "Top Shelf", "shelf", Some(room_id)).await?;
+    create_location(&app, "Box 1", "box", Some(shelf_id)).await?;
+
+    let (status, data) = send(&app, "GET", "/api/v1/locations", None).await?;
+    assert_eq!(status, 200);
+    let list = data.as_array().ok_or("expected array response")?;
+    assert_eq!(list.len(), 3);
+    assert_eq!(list[2]["kind"], "box");
+    assert_eq!(list[2]["parent_id"], shelf_id.to_string());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn move_user_models_and_filter_by_location() -> Result<(), Box<dyn std::error::Error>> {
+    let state = setup_state().await?;
+    let app = app(state);
+
+    let room_id = create_location(&app, "Garage", "room", None).await?;
+    let shelf_id = create_location(&app, "Rack A", "shelf", Some(room_id)).await?;
+    let box_id = create_location(&app, "Box 7", "box", Some(shelf_id)).await?;
+    let other_room_id = create_location(&app, "Attic", "room", None).await?;
+
+    let first = create_user_model(&app, "Intercessors", 10, None).await?;
+    let second = create_user_model(&app, "Hellblasters", 5, None).await?;
+    let third = create_user_model(&app, "Terminators", 3, Some(other_room_id)).await?;
+
+    let payload = json!({ "user_model_ids": [first, second, first], "location_id": box_id });
+    let (status, data) = send(&app, "POST", "/api/v1/user-models/move", Some(payload)).await?;
+    assert_eq!(status, 200);
+    assert_eq!(data["moved"], 2, "a repeated id moves once");
+
+    let (status, data) = send(
+        &app,
+        "GET",
+        &format!("/api/v1/user-models?location_id={room_id}"),
+        None,
+    )
+    .await?;
+    assert_eq!(status, 200);
+    let list = data.as_array().ok_or("expected array response")?;
+    assert_eq!(list.len(), 2);
+    assert!(list
+        .iter()
+        .all(|item| item["location_id"] == box_id.to_string()));
+
+    let (status, data) = send(&app, "GET", "/api/v1/user-models", None).await?;
+    assert_eq!(status, 200);
+    assert_eq!(data.as_array().ok_or("expected array response")?.len(), 3);
+
+    let payload = json!({ "user_model_ids": [third, Uuid::new_v4()], "location_id": box_id });
+    let (status, _) = send(&app, "POST", "/api/v1/user-models/move", Some(payload)).await?;
+    assert_eq!(status, 404);
+
+    let (_, data) = send(
+        &app,
+        "GET",
+        &format!("/api/v1/user-models?location_id={other_room_id}"),
+        None,
+    )
+    .await?;
+    let list = data.as_array().ok_or("expected array response")?;
+    assert_eq!(list.len(), 1, "failed bulk move must roll back");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn location_tree_sums_quantities() -> Result<(), Box<dyn std::error::Error>> {
+    let state = setup_state().await?;
+    let app = app(state);
+
+    let room_id = create_location(&app, "Study", "room", None).await?;
+    let shelf_id = create_location(&app, "Shelf 1", "shelf", Some(room_id)).await?;
+    let box_id = create_location(&app, "Box A", "box", Some(shelf_id)).await?;
+
+    create_user_model(&app, "Skeletons", 20, Some(box_id)).await?;
+    create_user_model(&app, "Zombies", 4, Some(shelf_id)).await?;
+    create_user_model(&app, "Ghouls", 6, None).await?;
+
+    let (status, data) = send(&app, "GET", "/api/v1/locations/tree", None).await?;
+    assert_eq!(status, 200);
+    assert_eq!(data["unassigned_quantity"], 6);
+
+    let room = &data["nodes"][0];
+    assert_eq!(room["name"], "Study");
+    assert_eq!(room["quantity"], 24);
+    let shelf = &room["children"][0];
+    assert_eq!(shelf["quantity"], 24);
+    assert_eq!(shelf["children"][0]["quantity"], 20);
+
+    Ok(())
+}
@@ -0,0 +1,300 @@
+mod common;
+
+use std::{
+    io::Write,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, Method, Request, StatusCode},
+    response::Response,
+    routing::get,
+    Router,
+};
+use backend::{
+    app, app_with_config,
+    config::Config,
+    middleware::{self, REQUEST_ID_HEADER},
+};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use common::setup_state;
+
+async fn send(
+    app: &Router,
+    request: Request<Body>,
+) -> Result<(Response, Value), Box<dyn std::error::Error>> {
+    let response = app.clone().oneshot(request).await?;
+    let (parts, body) = response.into_parts();
+    let body = to_bytes(body, usize::MAX).await?;
+    let data = serde_json::from_slice(&body).unwrap_or(Value::Null);
+    Ok((Response::from_parts(parts, Body::empty()), data))
+}
+
+fn post_json(uri: &str, body: Value) -> Result<Request<Body>, axum::http::Error> {
+    Request::builder()
+        .method(Method::POST)
+        .uri(uri)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+}
+
+/// Log lines written while it is the default subscriber, in JSON.
+#[derive(Clone, Default)]
+struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+impl Write for CapturedLogs {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0
+            .lock()
+            .map_err(|_| std::io::Error::other("poisoned"))?
+            .extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl CapturedLogs {
+    fn lines(&self) -> Result<Vec<Value>, Box<dyn std::error::Error>> {
+        let logs = self.0.lock().map_err(|_| "poisoned")?;
+        Ok(String::from_utf8(logs.clone())?
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()?)
+    }
+}
+
+#[tokio::test]
+async fn every_response_has_a_request_id() -> Result<(), Box<dyn std::error::Error>> {
+    let app = app(setup_state().await?);
+
+    let request = Request::builder().uri("/health").body(Body::empty())?;
+    let (response, _) = send(&app, request).await?;
+    let generated = response.headers()[&REQUEST_ID_HEADER].to_str()?;
+    assert!(Uuid::parse_str(generated).is_ok());
+
+    let request = Request::builder()
+        .uri("/health")
+        .header(&REQUEST_ID_HEADER, "from-the-proxy")
+        .body(Body::empty())?;
+    let (response, _) = send(&app, request).await?;
+    assert_eq!(response.headers()[&REQUEST_ID_HEADER], "from-the-proxy");
+
+    let request = Request::builder()
+        .uri("/health")
+        .header(&REQUEST_ID_HEADER, "x".repeat(200))
+        .body(Body::empty())?;
+    let (response, _) = send(&app, request).await?;
+    assert_eq!(response.headers()[&REQUEST_ID_HEADER].len(), 36);
+    Ok(())
+}
+
+#[tokio::test]
+async fn error_bodies_carry_the_request_id() -> Result<(), Box<dyn std::error::Error>> {
+    let app = app(setup_state().await?);
+
+    let request = Request::builder()
+        .uri(format!("/api/v1/user-models/{}", Uuid::new_v4()))
+        .header(&REQUEST_ID_HEADER, "lost-lot")
+        .body(Body::empty())?;
+    let (response, data) = send(&app, request).await?;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(
+        data,
+        json!({ "error": "user model not found", "request_id": "lost-lot" })
+    );
+
+    let (response, data) = send(
+        &app,
+        post_json("/api/v1/games", json!({ "name": "Frostgrave" }))?,
+    )
+    .await?;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert!(data.get("request_id").is_none());
+    Ok(())
+}
+
+#[tokio::test]
+async fn requests_are_logged_in_their_span() -> Result<(), Box<dyn std::error::Error>> {
+    let logs = CapturedLogs::default();
+    let writer = logs.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .json()
+        .with_writer(move || writer.clone())
+        .finish();
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let app = app(setup_state().await?);
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/api/v1/games")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(&REQUEST_ID_HEADER, "logged")
+        .body(Body::from(json!({ "name": "Stargrave" }).to_string()))?;
+    let (response, _) = send(&app, request).await?;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let lines = logs.lines()?;
+    let handler = lines
+        .iter()
+        .find(|line| line["fields"]["message"] == "creating game")
+        .ok_or("no handler log")?;
+    assert_eq!(handler["span"]["request_id"], "logged");
+
+    let access = lines
+        .iter()
+        .find(|line| line["fields"]["message"] == "finished request")
+        .ok_or("no access log")?;
+    assert_eq!(access["fields"]["status"], 201);
+    assert!(access["fields"]["latency_ms"].is_u64());
+    assert_eq!(access["span"]["request_id"], "logged");
+    assert_eq!(access["span"]["method"], "POST");
+    assert_eq!(access["span"]["path"], "/api/v1/games");
+    Ok(())
+}
+
+#[tokio::test]
+async fn responses_are_compressed() -> Result<(), Box<dyn std::error::Error>> {
+    let app = app(setup_state().await?);
+    for name in ["Warhammer 40,000", "Age of Sigmar", "Middle-earth SBG"] {
+        send(&app, post_json("/api/v1/games", json!({ "name": name }))?).await?;
+    }
+
+    for encoding in ["gzip", "br"] {
+        let request = Request::builder()
+            .uri("/api/v1/games")
+            .header(header::ACCEPT_ENCODING, encoding)
+            .body(Body::empty())?;
+        let response = app.clone().oneshot(request).await?;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_ENCODING], encoding);
+    }
+
+    let request = Request::builder()
+        .uri("/api/v1/games")
+        .body(Body::empty())?;
+    let (response, data) = send(&app, request).await?;
+    assert!(!response.headers().contains_key(header::CONTENT_ENCODING));
+    assert_eq!(data.as_array().map(Vec::len), Some(3));
+    Ok(())
+}
+
+#[tokio::test]
+async fn cors_follows_the_config() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config {
+        cors_origins: vec!["http://localhost:8080".to_string()],
+        ..Config::default()
+    };
+    let app = app_with_config(setup_state().await?, &config);
+
+    let request = Request::builder()
+        .method(Method::OPTIONS)
+        .uri("/api/v1/games")
+        .header(header::ORIGIN, "http://localhost:8080")
+        .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+        .body(Body::empty())?;
+    let (response, _) = send(&app, request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+        "http://localhost:8080"
+    );
+
+    let app = app_with_config(setup_state().await?, &Config::default());
+    let request = Request::builder()
+        .uri("/api/v1/games")
+        .header(header::ORIGIN, "http://localhost:8080")
+        .body(Body::empty())?;
+    let (response, _) = send(&app, request).await?;
+    assert!(!response
+        .headers()
+        .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+    Ok(())
+}
+
+#[tokio::test]
+async fn request_bodies_are_limited() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config {
+        max_body_bytes: 256,
+        ..Config::default()
+    };
+    let app = app_with_config(setup_state().await?, &config);
+
+    let long_name = "x".repeat(300);
+    let (response, _) = send(
+        &app,
+        post_json("/api/v1/games", json!({ "name": long_name }))?,
+    )
+    .await?;
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+    let (_, game) = send(
+        &app,
+        post_json("/api/v1/games", json!({ "name": "Kings of War" }))?,
+    )
+    .await?;
+    let definition = json!({ "name": "Ogre Warriors", "game_id": game["id"] });
+    let (_, definition) = send(&app, post_json("/api/v1/model-definitions", definition)?).await?;
+    let lot = json!({
+        "model_definition_id": definition["id"],
+        "quantity": 3,
+        "status": "unassembled"
+    });
+    let (_, lot) = send(&app, post_json("/api/v1/user-models", lot)?).await?;
+
+    // Photos keep their own, larger limit.
+    let boundary = "photo-boundary";
+    let mut body = format!(
+        "--{boundary}\r\nContent-Disposition: form-data; name=\"photo\"; filename=\"notes.txt\"\r\nContent-Type: text/plain\r\n\r\n"
+    )
+    .into_bytes();
+    body.extend(std::iter::repeat_n(b'x', 1024));
+    body.extend(format!("\r\n--{boundary}--\r\n").into_bytes());
+    let lot_id = lot["id"].as_str().ok_or("missing id")?;
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri(format!("/api/v1/user-models/{lot_id}/photos"))
+        .header(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={boundary}"),
+        )
+        .body(Body::from(body))?;
+    let (response, _) = send(&app, request).await?;
+    assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    Ok(())
+}
+
+#[tokio::test]
+async fn slow_requests_time_out() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config {
+        request_timeout_secs: 1,
+        ..Config::default()
+    };
+    let slow = Router::new().route(
+        "/slow",
+        get(|| async {
+            tokio::time::sleep(Duration::from_secs(10)).await;
+            "done"
+        }),
+    );
+    let app = middleware::stack(slow, &config);
+
+    let request = Request::builder()
+        .uri("/slow")
+        .header(&REQUEST_ID_HEADER, "too-slow")
+        .body(Body::empty())?;
+    let (response, data) = send(&app, request).await?;
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(
+        data,
+        json!({ "error": "request timed out", "request_id": "too-slow" })
+    );
+    Ok(())
+}
@@ -1,32 +1,12 @@
-use axum::{body::Body, http::Request};
-use backend::{app, AppState};
+mod common;
+
+use axum::{body::Body, http::Request, Router};
+use backend::app;
 use serde_json::json;
-use sqlx::sqlite::SqlitePoolOptions;
 use tower::ServiceExt;
 use uuid::Uuid;
 
-async fn setup_state() -> Result<AppState, Box<dyn std::error::Error>> {
-    let pool = SqlitePoolOptions::new()
-        .max_connections(1)
-        .connect("sqlite::memory:")
-        .await?;
-
-    sqlx::query("PRAGMA foreign_keys = ON;")
-        .execute(&pool)
-        .await?;
-
-    sqlx::migrate!().run(&pool).await?;
-
-    sqlx::query("DELETE FROM user_models;")
-        .execute(&pool)
-        .await?;
-    sqlx::query("DELETE FROM model_definitions;")
-        .execute(&pool)
-        .await?;
-    sqlx::query("DELETE FROM games;").execute(&pool).await?;
-
-    Ok(AppState { pool })
-}
+use common::setup_state;
 
 async fn create_game(
     app: axum::Router,
@@ -35,7 +15,7 @@ async fn create_game(
     let payload = json!({ "name": name }).to_string();
     let request = Request::builder()
         .method("POST")
-        .uri("/games")
+        .uri("/api/v1/games")
         .header("content-type", "application/json")
         .body(Body::from(payload))?;
 
@@ -51,15 +31,11 @@ async fn create_game(
     Ok((app, id))
 }
 
-#[tokio::test]
-async fn invalid_game_id_returns_not_found() -> Result<(), Box<dyn std::error::Error>> {
-    let state = setup_state().await?;
-    let app = app(state);
-
+async fn invalid_game_id_returns_not_found(app: Router) -> Result<(), Box<dyn std::error::Error>> {
     let payload = json!({ "name": "Stormcast", "game_id": Uuid::new_v4() }).to_string();
     let request = Request::builder()
         .method("POST")
-        .uri("/model-definitions")
+        .uri("/api/v1/model-definitions")
         .header("content-type", "application/json")
         .body(Body::from(payload))?;
 
@@ -69,16 +45,15 @@ async fn invalid_game_id_returns_not_found() -> Result<(), Box<dyn std::error::E
     Ok(())
 }
 
-#[tokio::test]
-async fn create_model_definition_returns_created() -> Result<(), Box<dyn std::error::Error>> {
-    let state = setup_state().await?;
-    let app = app(state);
+async fn create_model_definition_returns_created(
+    app: Router,
+) -> Result<(), Box<dyn std::error::Error>> {
     let (app, game_id) = create_game(app, "Age of Sigmar").await?;
 
     let payload = json!({ "name": "Stormcast", "game_id": game_id }).to_string();
     let request = Request::builder()
         .method("POST")
-        .uri("/model-definitions")
+        .uri("/api/v1/model-definitions")
         .header("content-type", "application/json")
         .body(Body::from(payload))?;
 
@@ -95,16 +70,15 @@ async fn create_model_definition_returns_created() -> Result<(), Box<dyn std::er
     Ok(())
 }
 
-#[tokio::test]
-async fn list_model_definitions_includes_game_info() -> Result<(), Box<dyn std::error::Error>> {
-    let state = setup_state().await?;
-    let app = app(state);
+async fn list_model_definitions_includes_game_info(
+    app: Router,
+) -> Result<(), Box<dyn std::error::Error>> {
     let (app, game_id) = create_game(app, "Star Wars Legion").await?;
 
     let payload = json!({ "name": "Clone Troopers", "game_id": game_id }).to_string();
     let request = Request::builder()
         .method("POST")
-        .uri("/model-definitions")
+        .uri("/api/v1/model-definitions")
         .header("content-type", "application/json")
         .body(Body::from(payload))?;
     let response = app.clone().oneshot(request).await?;
@@ -112,7 +86,7 @@ async fn list_model_definitions_includes_game_info() -> Result<(), Box<dyn std::
 
     let request = Request::builder()
         .method("GET")
-        .uri("/model-definitions")
+        .uri("/api/v1/model-definitions")
         .body(Body::empty())?;
     let response = app.oneshot(request).await?;
     assert_eq!(response.status(), 200);
@@ -128,3 +102,21 @@ async fn list_model_definitions_includes_game_info() -> Result<(), Box<dyn std::
 
     Ok(())
 }
+
+crate::backend_tests!(
+    sqlite,
+    app(setup_state().await?),
+    invalid_game_id_returns_not_found,
+    create_model_definition_returns_created,
+    list_model_definitions_includes_game_info,
+);
+
+#[cfg(feature = "postgres")]
+crate::backend_tests!(
+    ignored postgres,
+    common::postgres_app().await?,
+    "needs PostgreSQL: set TEST_POSTGRES_URL and pass --include-ignored",
+    invalid_game_id_returns_not_found,
+    create_model_definition_returns_created,
+    list_model_definitions_includes_game_info,
+);
@@ -0,0 +1,288 @@
+mod common;
+
+use std::collections::BTreeSet;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, Method, Request, StatusCode},
+    response::Response,
+    Router,
+};
+use backend::{
+    app_with_features, catalog_app, openapi, AppState, BackupSettings, CatalogState, EventBus,
+    Features, InMemoryRepository, API_V1,
+};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+const METHODS: [Method; 5] = [
+    Method::GET,
+    Method::POST,
+    Method::PUT,
+    Method::PATCH,
+    Method::DELETE,
+];
+
+async fn setup_state() -> Result<AppState, Box<dyn std::error::Error>> {
+    Ok(AppState {
+        backups: BackupSettings {
+            dir: std::env::temp_dir().join(format!("mini-tracker-openapi-{}", Uuid::new_v4())),
+            ..Default::default()
+        },
+        ..common::setup_state().await?
+    })
+}
+
+/// The documented paths and methods, with `{param}` placeholders.
+fn documented(features: &Features) -> BTreeSet<(String, String)> {
+    let spec = openapi::spec(features);
+    let mut operations = BTreeSet::new();
+    for (path, item) in &spec.paths.paths {
+        let methods = [
+            (Method::GET, item.get.is_some()),
+            (Method::POST, item.post.is_some()),
+            (Method::PUT, item.put.is_some()),
+            (Method::PATCH, item.patch.is_some()),
+            (Method::DELETE, item.delete.is_some()),
+        ];
+        for (method, present) in methods {
+            if present {
+                operations.insert((path.clone(), method.to_string()));
+            }
+        }
+    }
+    operations
+}
+
+/// Where a documented path is served, with every `{param}` filled with a
+/// fresh UUID. Everything but `/health` is relative to the `/api/v1` server.
+fn concrete(path: &str) -> String {
+    let prefix = if path == "/health" { "" } else { API_V1 };
+    let path = path
+        .split('/')
+        .map(|segment| {
+            if segment.starts_with('{') {
+                Uuid::new_v4().to_string()
+            } else {
+                segment.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    format!("{prefix}{path}")
+}
+
+/// Sends `method` to `path` with an empty JSON object as the body.
+async fn probe(
+    app: &Router,
+    method: Method,
+    path: &str,
+) -> Result<Response, Box<dyn std::error::Error>> {
+    let request = Request::builder()
+        .method(method)
+        .uri(path)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from("{}"))?;
+    Ok(app.clone().oneshot(request).await?)
+}
+
+/// Whether the router has a handler for the request. Unknown paths get
+/// axum's empty 404 and unknown methods a 405; handlers answer anything
+/// else, including their own 404s, which always carry an error body.
+async fn is_routed(
+    app: &Router,
+    method: Method,
+    path: &str,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let response = probe(app, method, path).await?;
+    match response.status() {
+        StatusCode::METHOD_NOT_ALLOWED => Ok(false),
+        StatusCode::NOT_FOUND => {
+            let body = to_bytes(response.into_body(), usize::MAX).await?;
+            Ok(!body.is_empty())
+        }
+        _ => Ok(true),
+    }
+}
+
+/// Whether some request could match both paths.
+fn overlaps(left: &str, right: &str) -> bool {
+    let left: Vec<&str> = left.split('/').collect();
+    let right: Vec<&str> = right.split('/').collect();
+    left.len() == right.len()
+        && left
+            .iter()
+            .zip(&right)
+            .all(|(left, right)| left == right || left.starts_with('{') || right.starts_with('{'))
+}
+
+#[tokio::test]
+async fn every_documented_operation_is_routed() -> Result<(), Box<dyn std::error::Error>> {
+    for features in [Features::default(), without_optional_routes()] {
+        let app = app_with_features(setup_state().await?, &features);
+        let documented = documented(&features);
+        assert!(!documented.is_empty());
+
+        let paths: BTreeSet<&String> = documented.iter().map(|(path, _)| path).collect();
+        for path in paths {
+            let uri = concrete(path);
+            for method in METHODS {
+                let expected = documented.contains(&(path.clone(), method.to_string()));
+                assert_eq!(
+                    is_routed(&app, method.clone(), &uri).await?,
+                    expected,
+                    "{method} {path} is routed if and only if it is documented"
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn undocumented_requests_are_not_routed() -> Result<(), Box<dyn std::error::Error>> {
+    let features = Features::default();
+    let app = app_with_features(setup_state().await?, &features);
+    let documented = documented(&features);
+    let paths: BTreeSet<&String> = documented.iter().map(|(path, _)| path).collect();
+
+    for path in &paths {
+        let uri = concrete(path);
+        for method in METHODS {
+            if !documented.contains(&((*path).clone(), method.to_string())) {
+                let response = probe(&app, method.clone(), &uri).await?;
+                assert_eq!(
+                    response.status(),
+                    StatusCode::METHOD_NOT_ALLOWED,
+                    "{method} {path}"
+                );
+            }
+        }
+
+        // axum cannot list a router's routes, so look for undocumented ones
+        // next to the documented: below each path and at each shorter prefix.
+        let segments: Vec<&str> = path.split('/').collect();
+        let candidates = (2..segments.len())
+            .map(|end| segments[..end].join("/"))
+            .chain([format!("{path}/undocumented"), format!("{path}/{{id}}")])
+            .filter(|candidate| !paths.iter().any(|path| overlaps(path, candidate)));
+        for candidate in candidates {
+            let uri = concrete(&candidate);
+            for method in METHODS {
+                assert!(
+                    !is_routed(&app, method.clone(), &uri).await?,
+                    "{method} {candidate} is routed but not documented"
+                );
+            }
+        }
+    }
+
+    for uri in [
+        format!("{API_V1}/undocumented"),
+        "/undocumented".to_string(),
+    ] {
+        for method in METHODS {
+            let response = probe(&app, method.clone(), &uri).await?;
+            assert_eq!(response.status(), StatusCode::NOT_FOUND, "{method} {uri}");
+            let body = to_bytes(response.into_body(), usize::MAX).await?;
+            assert!(body.is_empty(), "{method} {uri}");
+        }
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn catalog_app_answers_the_rest_with_501() -> Result<(), Box<dyn std::error::Error>> {
+    let features = Features::default();
+    let state = CatalogState {
+        repository: std::sync::Arc::new(InMemoryRepository::new()),
+        events: EventBus::new(),
+    };
+    let app = catalog_app(state, &features);
+    let served = [
+        ("/health", "GET"),
+        ("/events", "GET"),
+        ("/games", "GET"),
+        ("/games", "POST"),
+        ("/model-definitions", "GET"),
+        ("/model-definitions", "POST"),
+        ("/model-definitions/{id}", "PATCH"),
+        ("/user-models", "GET"),
+        ("/user-models", "POST"),
+        ("/user-models/{id}", "GET"),
+        ("/user-models/{id}", "PATCH"),
+    ];
+
+    for (path, method) in documented(&features) {
+        let uri = concrete(&path);
+        let method = Method::from_bytes(method.as_bytes())?;
+        let status = probe(&app, method.clone(), &uri).await?.status();
+        if served.contains(&(path.as_str(), method.as_str())) {
+            assert_ne!(status, StatusCode::NOT_IMPLEMENTED, "{method} {path}");
+            assert!(
+                is_routed(&app, method.clone(), &uri).await?,
+                "{method} {path}"
+            );
+        } else {
+            assert_eq!(status, StatusCode::NOT_IMPLEMENTED, "{method} {path}");
+        }
+    }
+
+    let response = probe(&app, Method::GET, "/tags").await?;
+    assert_eq!(response.status(), StatusCode::NOT_IMPLEMENTED);
+    assert!(response.headers().contains_key("deprecation"));
+    let response = probe(&app, Method::PUT, &format!("{API_V1}/games")).await?;
+    assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+    let response = probe(&app, Method::GET, &format!("{API_V1}/undocumented")).await?;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    Ok(())
+}
+
+#[tokio::test]
+async fn spec_and_docs_are_served() -> Result<(), Box<dyn std::error::Error>> {
+    let features = without_optional_routes();
+    let app = app_with_features(setup_state().await?, &features);
+
+    let request = Request::builder()
+        .uri(openapi::SPEC_PATH)
+        .body(Body::empty())?;
+    let response = app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX).await?;
+    let served: serde_json::Value = serde_json::from_slice(&body)?;
+    assert_eq!(served, serde_json::to_value(openapi::spec(&features))?);
+    assert_eq!(served["openapi"], "3.1.0");
+    assert_eq!(served["servers"][0]["url"], API_V1);
+    assert_eq!(served["paths"]["/health"]["servers"][0]["url"], "/");
+    assert!(served["paths"]["/sync/changes"].is_null());
+    assert!(served["paths"]["/games"]["post"].is_object());
+
+    let request = Request::builder()
+        .uri(format!("{}/", openapi::DOCS_PATH))
+        .body(Body::empty())?;
+    let response = app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX).await?;
+    assert!(String::from_utf8(body.to_vec())?.contains("swagger-ui"));
+
+    let features = Features {
+        docs: false,
+        ..Features::default()
+    };
+    let app = app_with_features(setup_state().await?, &features);
+    let request = Request::builder()
+        .uri(openapi::SPEC_PATH)
+        .body(Body::empty())?;
+    let response = app.oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    Ok(())
+}
+
+fn without_optional_routes() -> Features {
+    Features {
+        events: false,
+        presence: false,
+        sync: false,
+        ..Features::default()
+    }
+}
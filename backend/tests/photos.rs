@@ -0,0 +1,224 @@
+mod common;
+
+use std::path::PathBuf;
+
+use axum::{body::Body, http::Request};
+use backend::{app, AppState};
+use serde_json::json;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+const BOUNDARY: &str = "mini-tracker-boundary";
+
+async fn setup_state() -> Result<AppState, Box<dyn std::error::Error>> {
+    Ok(AppState {
+        photo_dir: std::env::temp_dir().join(format!("mini-tracker-photos-{}", Uuid::new_v4())),
+        ..common::setup_state().await?
+    })
+}
+
+async fn create_user_model(app: &axum::Router) -> Result<Uuid, Box<dyn std::error::Error>> {
+    let payload = json!({ "name": "Necromunda" }).to_string();
+    let request = Request::builder()
+        .method("POST")
+        .uri("/api/v1/games")
+        .header("content-type", "application/json")
+        .body(Body::from(payload))?;
+    let response = app.clone().oneshot(request).await?;
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    let data: serde_json::Value = serde_json::from_slice(&body)?;
+
+    let payload = json!({ "name": "Goliath Gang", "game_id": data["id"] }).to_string();
+    let request = Request::builder()
+        .method("POST")
+        .uri("/api/v1/model-definitions")
+        .header("content-type", "application/json")
+        .body(Body::from(payload))?;
+    let response = app.clone().oneshot(request).await?;
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    let data: serde_json::Value = serde_json::from_slice(&body)?;
+
+    let payload = json!({
+        "model_definition_id": data["id"],
+        "quantity": 10,
+        "status": "painted"
+    })
+    .to_string();
+    let request = Request::builder()
+        .method("POST")
+        .uri("/api/v1/user-models")
+        .header("content-type", "application/json")
+        .body(Body::from(payload))?;
+    let response = app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), 201);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    let data: serde_json::Value = serde_json::from_slice(&body)?;
+
+    let id = data["id"]
+        .as_str()
+        .ok_or("missing user model id")?
+        .parse::<Uuid>()?;
+    Ok(id)
+}
+
+fn png_bytes(width: u32, height: u32) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let image = image::RgbImage::from_pixel(width, height, image::Rgb([180, 40, 20]));
+    let mut encoded = std::io::Cursor::new(Vec::new());
+    image.write_to(&mut encoded, image::ImageFormat::Png)?;
+    Ok(encoded.into_inner())
+}
+
+fn upload_request(
+    user_model_id: Uuid,
+    bytes: &[u8],
+) -> Result<Request<Body>, Box<dyn std::error::Error>> {
+    let mut body = Vec::new();
+    body.extend_from_slice(
+        format!(
+            "--{BOUNDARY}\r\n\
+             Content-Disposition: form-data; name=\"photo\"; filename=\"progress.png\"\r\n\
+             Content-Type: application/octet-stream\r\n\r\n"
+        )
+        .as_bytes(),
+    );
+    body.extend_from_slice(bytes);
+    body.extend_from_slice(format!("\r\n--{BOUNDARY}--\r\n").as_bytes());
+
+    let request = Request::builder()
+        .method("POST")
+        .uri(format!("/api/v1/user-models/{user_model_id}/photos"))
+        .header(
+            "content-type",
+            format!("multipart/form-data; boundary={BOUNDARY}"),
+        )
+        .body(Body::from(body))?;
+    Ok(request)
+}
+
+#[tokio::test]
+async fn upload_and_serve_photo() -> Result<(), Box<dyn std::error::Error>> {
+    let state = setup_state().await?;
+    let photo_dir: PathBuf = state.photo_dir.clone();
+    let app = app(state);
+    let user_model_id = create_user_model(&app).await?;
+
+    let original = png_bytes(800, 400)?;
+    let response = app
+        .clone()
+        .oneshot(upload_request(user_model_id, &original)?)
+        .await?;
+    assert_eq!(response.status(), 201);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    let data: serde_json::Value = serde_json::from_slice(&body)?;
+    assert_eq!(data["content_type"], "image/png");
+    assert_eq!(data["file_name"], "progress.png");
+    assert_eq!(data["width"], 800);
+    assert_eq!(data["height"], 400);
+    let photo_id = data["id"].as_str().ok_or("missing photo id")?.to_string();
+
+    let request = Request::builder()
+        .method("GET")
+        .uri(format!("/api/v1/user-models/{user_model_id}/photos"))
+        .body(Body::empty())?;
+    let response = app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), 200);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    let data: serde_json::Value = serde_json::from_slice(&body)?;
+    assert_eq!(data.as_array().ok_or("expected array response")?.len(), 1);
+
+    let request = Request::builder()
+        .method("GET")
+        .uri(format!("/api/v1/photos/{photo_id}"))
+        .body(Body::empty())?;
+    let response = app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "image/png");
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    assert_eq!(body.as_ref(), original.as_slice());
+
+    let request = Request::builder()
+        .method("GET")
+        .uri(format!("/api/v1/photos/{photo_id}/thumbnail"))
+        .body(Body::empty())?;
+    let response = app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "image/jpeg");
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    let thumbnail = image::load_from_memory(&body)?;
+    assert_eq!((thumbnail.width(), thumbnail.height()), (320, 160));
+
+    let request = Request::builder()
+        .method("DELETE")
+        .uri(format!("/api/v1/photos/{photo_id}"))
+        .body(Body::empty())?;
+    let response = app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), 204);
+    assert_eq!(std::fs::read_dir(&photo_dir)?.count(), 0);
+
+    let request = Request::builder()
+        .method("GET")
+        .uri(format!("/api/v1/photos/{photo_id}"))
+        .body(Body::empty())?;
+    let response = app.oneshot(request).await?;
+    assert_eq!(response.status(), 404);
+
+    std::fs::remove_dir_all(photo_dir)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn rejects_non_image_uploads() -> Result<(), Box<dyn std::error::Error>> {
+    let state = setup_state().await?;
+    let app = app(state);
+    let user_model_id = create_user_model(&app).await?;
+
+    let response = app
+        .clone()
+        .oneshot(upload_request(user_model_id, b"#!/bin/sh\necho hello\n")?)
+        .await?;
+    assert_eq!(response.status(), 415);
+
+    let response = app
+        .clone()
+        .oneshot(upload_request(Uuid::new_v4(), &png_bytes(10, 10)?)?)
+        .await?;
+    assert_eq!(response.status(), 404);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn rejects_oversized_uploads() -> Result<(), Box<dyn std::error::Error>> {
+    let state = setup_state().await?;
+    let app = app(state);
+    let user_model_id = create_user_model(&app).await?;
+
+    let mut bytes = png_bytes(10, 10)?;
+    bytes.resize(11 * 1024 * 1024, 0);
+    let response = app.oneshot(upload_request(user_model_id, &bytes)?).await?;
+    assert_eq!(response.status(), 413);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn trashed_lots_take_no_photos() -> Result<(), Box<dyn std::error::Error>> {
+    let state = setup_state().await?;
+    let app = app(state);
+    let user_model_id = create_user_model(&app).await?;
+
+    let request = Request::builder()
+        .method("DELETE")
+        .uri(format!("/api/v1/user-models/{user_model_id}"))
+        .body(Body::empty())?;
+    let response = app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), 204);
+
+    let response = app
+        .oneshot(upload_request(user_model_id, &png_bytes(10, 10)?)?)
+        .await?;
+    assert_eq!(response.status(), 404);
+
+    Ok(())
+}
@@ -0,0 +1,152 @@
+mod common;
+
+use std::time::Duration;
+
+use axum::{body::Body, http::Request};
+use backend::app;
+use futures_util::{SinkExt, StreamExt};
+use serde_json::json;
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::{tungstenite::Message, MaybeTlsStream, WebSocketStream};
+use tower::ServiceExt;
+
+use common::setup_state;
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Serves the app on a random local port, since WebSockets need a real connection.
+async fn serve(app: axum::Router) -> Result<String, Box<dyn std::error::Error>> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("ws://{}/api/v1/ws", listener.local_addr()?);
+    tokio::spawn(async move { axum::serve(listener, app).await });
+    Ok(url)
+}
+
+async fn send(
+    socket: &mut Socket,
+    message: serde_json::Value,
+) -> Result<(), Box<dyn std::error::Error>> {
+    socket.send(Message::text(message.to_string())).await?;
+    Ok(())
+}
+
+/// Reads the next JSON message of the given type, skipping pings and anything else.
+async fn next(
+    socket: &mut Socket,
+    kind: &str,
+) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+    loop {
+        let message = tokio::time::timeout(Duration::from_secs(5), socket.next())
+            .await?
+            .ok_or("socket closed")??;
+        if let Message::Text(text) = message {
+            let value: serde_json::Value = serde_json::from_str(&text)?;
+            if value["type"] == kind {
+                return Ok(value);
+            }
+        }
+    }
+}
+
+async fn join(url: &str, name: &str) -> Result<(Socket, String), Box<dyn std::error::Error>> {
+    let (mut socket, _) = tokio_tungstenite::connect_async(url).await?;
+    send(&mut socket, json!({ "type": "hello", "name": name })).await?;
+    let hello = next(&mut socket, "hello").await?;
+    let client_id = hello["client_id"]
+        .as_str()
+        .ok_or("missing client id")?
+        .to_string();
+    Ok((socket, client_id))
+}
+
+#[tokio::test]
+async fn presence_tracks_joins_focus_and_leaves() -> Result<(), Box<dyn std::error::Error>> {
+    let url = serve(app(setup_state().await?)).await?;
+
+    let (mut alice, alice_id) = join(&url, "Alice").await?;
+    let presence = next(&mut alice, "presence").await?;
+    assert_eq!(presence["members"].as_array().map(Vec::len), Some(1));
+    assert_eq!(presence["members"][0]["client_id"], alice_id.as_str());
+    assert_eq!(presence["members"][0]["name"], "Alice");
+    assert!(presence["members"][0]["color"].is_string());
+
+    let (mut bob, bob_id) = join(&url, "  Bob  ").await?;
+    let presence = next(&mut alice, "presence").await?;
+    assert_eq!(presence["members"][1]["client_id"], bob_id.as_str());
+    assert_eq!(presence["members"][1]["name"], "Bob");
+    assert_ne!(
+        presence["members"][0]["color"],
+        presence["members"][1]["color"]
+    );
+
+    let row = uuid::Uuid::new_v4().to_string();
+    send(
+        &mut bob,
+        json!({ "type": "row-focus", "user_model_id": row }),
+    )
+    .await?;
+    let presence = next(&mut alice, "presence").await?;
+    assert_eq!(presence["members"][1]["user_model_id"], row.as_str());
+    assert!(presence["members"][0]["user_model_id"].is_null());
+
+    bob.close(None).await?;
+    let presence = next(&mut alice, "presence").await?;
+    assert_eq!(presence["members"].as_array().map(Vec::len), Some(1));
+    assert_eq!(presence["members"][0]["client_id"], alice_id.as_str());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn change_events_are_forwarded() -> Result<(), Box<dyn std::error::Error>> {
+    let app = app(setup_state().await?);
+    let url = serve(app.clone()).await?;
+    let (mut socket, _) = join(&url, "Alice").await?;
+    next(&mut socket, "presence").await?;
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/api/v1/games")
+        .header("content-type", "application/json")
+        .body(Body::from(json!({ "name": "Warhammer 40k" }).to_string()))?;
+    let response = app.oneshot(request).await?;
+    assert_eq!(response.status(), 201);
+
+    let change = next(&mut socket, "change").await?;
+    assert_eq!(change["event"]["type"], "changed");
+    assert_eq!(change["event"]["entity"], "game");
+    assert_eq!(change["event"]["action"], "create");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn protocol_errors_are_reported() -> Result<(), Box<dyn std::error::Error>> {
+    let url = serve(app(setup_state().await?)).await?;
+
+    let (mut socket, _) = tokio_tungstenite::connect_async(&url).await?;
+    send(
+        &mut socket,
+        json!({ "type": "row-focus", "user_model_id": null }),
+    )
+    .await?;
+    let error = next(&mut socket, "error").await?;
+    assert_eq!(error["message"], "expected hello with a name");
+
+    let (mut socket, _) = join(&url, "Alice").await?;
+    send(&mut socket, json!({ "type": "wave" })).await?;
+    let error = next(&mut socket, "error").await?;
+    assert_eq!(error["message"], "invalid message");
+
+    // The connection stays usable after a bad message.
+    send(
+        &mut socket,
+        json!({ "type": "row-focus", "user_model_id": null }),
+    )
+    .await?;
+    send(&mut socket, json!({ "type": "hello", "name": "Alice" })).await?;
+    let error = next(&mut socket, "error").await?;
+    assert_eq!(error["message"], "already said hello");
+
+    Ok(())
+}
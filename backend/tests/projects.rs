@@ -0,0 +1,178 @@
+mod common;
+
+use backend::app;
+use serde_json::json;
+use uuid::Uuid;
+
+use common::{send, setup_state};
+
+async fn create_user_model(
+    app: &axum::Router,
+    name: &str,
+    quantity: i32,
+    status: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let (code, data) = send(app, "POST", "/api/v1/games", Some(json!({ "name": name }))).await?;
+    assert_eq!(code, 201);
+    let game_id = data["id"].as_str().ok_or("missing game id")?.to_string();
+
+    let payload = json!({ "name": name, "game_id": game_id });
+    let (code, data) = send(app, "POST", "/api/v1/model-definitions", Some(payload)).await?;
+    assert_eq!(code, 201);
+    let model_definition_id = data["id"]
+        .as_str()
+        .ok_or("missing model definition id")?
+        .to_string();
+
+    let payload = json!({
+        "model_definition_id": model_definition_id,
+        "quantity": quantity,
+        "status": status
+    });
+    let (code, data) = send(app, "POST", "/api/v1/user-models", Some(payload)).await?;
+    assert_eq!(code, 201);
+    Ok(data["id"]
+        .as_str()
+        .ok_or("missing user model id")?
+        .to_string())
+}
+
+async fn create_project(
+    app: &axum::Router,
+    payload: serde_json::Value,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let (status, data) = send(app, "POST", "/api/v1/projects", Some(payload)).await?;
+    assert_eq!(status, 201);
+    Ok(data["id"].as_str().ok_or("missing project id")?.to_string())
+}
+
+#[tokio::test]
+async fn progress_counts_lots_at_target_status() -> Result<(), Box<dyn std::error::Error>> {
+    let state = setup_state().await?;
+    let app = app(state);
+
+    let painted = create_user_model(&app, "Kroot", 10, "painted").await?;
+    let assembled = create_user_model(&app, "Stealth Suits", 3, "assembled").await?;
+    let unassembled = create_user_model(&app, "Crisis Suits", 7, "unassembled").await?;
+
+    let payload = json!({
+        "name": "Event army",
+        "target_status": "painted",
+        "due_date": "2999-12-01",
+        "user_model_ids": [painted, assembled, unassembled]
+    });
+    let project_id = create_project(&app, payload).await?;
+
+    let uri = format!("/api/v1/projects/{project_id}/progress");
+    let (status, data) = send(&app, "GET", &uri, None).await?;
+    assert_eq!(status, 200);
+    assert_eq!(data["total_quantity"], 20);
+    assert_eq!(data["completed_quantity"], 10);
+    assert_eq!(data["remaining_quantity"], 10);
+    assert_eq!(data["overdue"], false);
+    assert_eq!(data["lots"].as_array().ok_or("expected lots")?.len(), 3);
+
+    let payload = json!({ "name": "Build everything", "target_status": "assembled" });
+    let project_id = create_project(&app, payload).await?;
+    let payload = json!({ "user_model_ids": [painted, assembled, unassembled] });
+    let uri = format!("/api/v1/projects/{project_id}/user-models");
+    let (status, data) = send(&app, "PUT", &uri, Some(payload)).await?;
+    assert_eq!(status, 200);
+    assert_eq!(
+        data["user_model_ids"]
+            .as_array()
+            .ok_or("expected ids")?
+            .len(),
+        3
+    );
+
+    let uri = format!("/api/v1/projects/{project_id}/progress");
+    let (_, data) = send(&app, "GET", &uri, None).await?;
+    assert_eq!(data["completed_quantity"], 13);
+    assert_eq!(data["remaining_quantity"], 7);
+
+    let (status, data) = send(&app, "GET", "/api/v1/projects", None).await?;
+    assert_eq!(status, 200);
+    let list = data.as_array().ok_or("expected array response")?;
+    assert_eq!(list.len(), 2);
+    assert_eq!(list[0]["due_date"], "2999-12-01");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn past_due_projects_are_overdue_until_complete() -> Result<(), Box<dyn std::error::Error>> {
+    let state = setup_state().await?;
+    let app = app(state);
+
+    let lot = create_user_model(&app, "Orruks", 5, "assembled").await?;
+    let payload = json!({
+        "name": "Last year's league",
+        "target_status": "painted",
+        "due_date": "2001-06-30",
+        "user_model_ids": [lot]
+    });
+    let project_id = create_project(&app, payload).await?;
+
+    let uri = format!("/api/v1/projects/{project_id}/progress");
+    let (_, data) = send(&app, "GET", &uri, None).await?;
+    assert_eq!(data["overdue"], true);
+
+    let payload = json!({ "status": "painted" });
+    let (status, _) = send(&app, "PATCH", &format!("/api/v1/user-models/{lot}"), Some(payload)).await?;
+    assert_eq!(status, 200);
+
+    let (_, data) = send(&app, "GET", &uri, None).await?;
+    assert_eq!(data["remaining_quantity"], 0);
+    assert_eq!(data["overdue"], false);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn project_input_is_validated() -> Result<(), Box<dyn std::error::Error>> {
+    let state = setup_state().await?;
+    let app = app(state);
+
+    let payload =
+        json!({ "name": "Bad date", "target_status": "painted", "due_date": "2026-02-30" });
+    let (status, _) = send(&app, "POST", "/api/v1/projects", Some(payload)).await?;
+    assert_eq!(status, 422);
+
+    let payload =
+        json!({ "name": "Bad date", "target_status": "painted", "due_date": "next week" });
+    let (status, _) = send(&app, "POST", "/api/v1/projects", Some(payload)).await?;
+    assert_eq!(status, 422);
+
+    let payload = json!({
+        "name": "Ghost lots",
+        "target_status": "painted",
+        "user_model_ids": [Uuid::new_v4()]
+    });
+    let (status, _) = send(&app, "POST", "/api/v1/projects", Some(payload)).await?;
+    assert_eq!(status, 404);
+
+    let (_, data) = send(&app, "GET", "/api/v1/projects", None).await?;
+    assert_eq!(
+        data.as_array().ok_or("expected array response")?.len(),
+        0,
+        "failed project creation must roll back"
+    );
+
+    let uri = format!("/api/v1/projects/{}/progress", Uuid::new_v4());
+    let (status, _) = send(&app, "GET", &uri, None).await?;
+    assert_eq!(status, 404);
+
+    let project_id = create_project(
+        &app,
+        json!({ "name": "Tidy up", "target_status": "assembled" }),
+    )
+    .await?;
+    let uri = format!("/api/v1/projects/{project_id}");
+    let (status, _) = send(&app, "DELETE", &uri, None).await?;
+    assert_eq!(status, 204);
+    let (status, _) = send(&app, "DELETE", &uri, None).await?;
+    assert_eq!(status, 404);
+
+    Ok(())
+}
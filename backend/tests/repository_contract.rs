@@ -0,0 +1,263 @@
+//! The same requests against every repository, so the in-memory one used in
+//! handler tests cannot drift from the SQL ones.
+
+mod common;
+
+use std::sync::Arc;
+
+use axum::{body::Body, http::Request, Router};
+use backend::{catalog_app, CatalogState, EventBus, Features, SharedRepository};
+use serde_json::{json, Value};
+use sqlx::sqlite::SqlitePoolOptions;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use common::send;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+async fn sqlite_repository() -> Result<SharedRepository, Box<dyn std::error::Error>> {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await?;
+
+    sqlx::query("PRAGMA foreign_keys = ON;")
+        .execute(&pool)
+        .await?;
+
+    sqlx::migrate!().run(&pool).await?;
+
+    Ok(Arc::new(backend::SqliteRepository::new(pool.clone(), pool)))
+}
+
+fn catalog(repository: SharedRepository) -> Router {
+    let state = CatalogState {
+        repository,
+        events: EventBus::new(),
+    };
+    catalog_app(state, &Features::default())
+}
+
+async fn games_are_unique_by_name(app: Router) -> TestResult {
+    let (status, first) = send(
+        &app,
+        "POST",
+        "/api/v1/games",
+        Some(json!({ "name": "Bolt Action" })),
+    )
+    .await?;
+    assert_eq!(status, 201);
+    assert_eq!(first["name"], "Bolt Action");
+    let created_at = first["created_at"].as_str().ok_or("missing created_at")?;
+    assert_eq!(created_at.len(), "2026-01-01 00:00:00".len());
+
+    let (status, data) = send(
+        &app,
+        "POST",
+        "/api/v1/games",
+        Some(json!({ "name": "Bolt Action" })),
+    )
+    .await?;
+    assert_eq!(status, 409);
+    assert_eq!(data["error"], "game name already exists");
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/api/v1/games",
+        Some(json!({ "name": "Infinity" })),
+    )
+    .await?;
+    assert_eq!(status, 201);
+
+    let (status, data) = send(&app, "GET", "/api/v1/games", None).await?;
+    assert_eq!(status, 200);
+    let names: Vec<_> = data
+        .as_array()
+        .ok_or("expected array response")?
+        .iter()
+        .map(|game| game["name"].clone())
+        .collect();
+    assert_eq!(names, ["Bolt Action", "Infinity"]);
+    assert_eq!(data[0]["id"], first["id"]);
+    Ok(())
+}
+
+async fn model_definitions_need_a_game_and_keep_unset_fields(app: Router) -> TestResult {
+    let missing = json!({ "name": "Kommandos", "game_id": Uuid::new_v4() });
+    let (status, data) = send(&app, "POST", "/api/v1/model-definitions", Some(missing)).await?;
+    assert_eq!(status, 404);
+    assert_eq!(data["error"], "game not found");
+
+    let (_, game) = send(
+        &app,
+        "POST",
+        "/api/v1/games",
+        Some(json!({ "name": "Kill Team" })),
+    )
+    .await?;
+    let invalid = json!({ "name": "Kommandos", "game_id": game["id"], "points": -1 });
+    let (status, _) = send(&app, "POST", "/api/v1/model-definitions", Some(invalid)).await?;
+    assert_eq!(status, 422);
+
+    let payload = json!({ "name": "Kommandos", "game_id": game["id"], "points": 120 });
+    let (status, created) = send(&app, "POST", "/api/v1/model-definitions", Some(payload)).await?;
+    assert_eq!(status, 201);
+    assert_eq!(created["game"]["name"], "Kill Team");
+    assert_eq!(created["points"], 120);
+    assert_eq!(created["unit_size"], Value::Null);
+    assert_eq!(created["tags"], json!([]));
+
+    let uri = format!(
+        "/api/v1/model-definitions/{}",
+        created["id"].as_str().ok_or("missing id")?
+    );
+    let changes = json!({ "notes": "  Magnetised  ", "unit_size": 10 });
+    let (status, updated) = send(&app, "PATCH", &uri, Some(changes)).await?;
+    assert_eq!(status, 200);
+    assert_eq!(updated["notes"], "Magnetised");
+    assert_eq!(updated["points"], 120);
+    assert_eq!(updated["unit_size"], 10);
+
+    let (status, updated) = send(&app, "PATCH", &uri, Some(json!({ "notes": " " }))).await?;
+    assert_eq!(status, 200);
+    assert_eq!(updated["notes"], Value::Null);
+    assert_eq!(updated["unit_size"], 10);
+
+    let (status, data) = send(&app, "GET", "/api/v1/model-definitions", None).await?;
+    assert_eq!(status, 200);
+    assert_eq!(data, json!([updated]));
+
+    let uri = format!("/api/v1/model-definitions/{}", Uuid::new_v4());
+    let (status, data) = send(&app, "PATCH", &uri, Some(json!({ "points": 5 }))).await?;
+    assert_eq!(status, 404);
+    assert_eq!(data["error"], "model definition not found");
+    Ok(())
+}
+
+async fn user_models_validate_references_and_versions(app: Router) -> TestResult {
+    let missing = json!({
+        "model_definition_id": Uuid::new_v4(),
+        "quantity": 3,
+        "status": "unassembled"
+    });
+    let (status, data) = send(&app, "POST", "/api/v1/user-models", Some(missing)).await?;
+    assert_eq!(status, 404);
+    assert_eq!(data["error"], "model definition not found");
+
+    let (_, game) = send(
+        &app,
+        "POST",
+        "/api/v1/games",
+        Some(json!({ "name": "Warcry" })),
+    )
+    .await?;
+    let definition = json!({ "name": "Untamed Beasts", "game_id": game["id"] });
+    let (_, definition) = send(&app, "POST", "/api/v1/model-definitions", Some(definition)).await?;
+
+    let unknown_location = json!({
+        "model_definition_id": definition["id"],
+        "quantity": 3,
+        "status": "unassembled",
+        "location_id": Uuid::new_v4()
+    });
+    let (status, data) = send(&app, "POST", "/api/v1/user-models", Some(unknown_location)).await?;
+    assert_eq!(status, 404);
+    assert_eq!(data["error"], "location not found");
+
+    let payload = json!({
+        "model_definition_id": definition["id"],
+        "quantity": 3,
+        "status": "unassembled"
+    });
+    let (status, created) = send(&app, "POST", "/api/v1/user-models", Some(payload)).await?;
+    assert_eq!(status, 201);
+    assert_eq!(created["version"], 1);
+    assert_eq!(created["notes"], Value::Null);
+    assert_eq!(created["created_at"], created["updated_at"]);
+
+    let uri = format!(
+        "/api/v1/user-models/{}",
+        created["id"].as_str().ok_or("missing id")?
+    );
+    let (status, data) = send(&app, "GET", &uri, None).await?;
+    assert_eq!(status, 200);
+    assert_eq!(data, created);
+
+    let (status, _) = send(&app, "PATCH", &uri, Some(json!({ "quantity": 0 }))).await?;
+    assert_eq!(status, 422);
+
+    let changes = json!({ "status": "painted", "notes": "  Blood for the arena  " });
+    let (status, updated) = send(&app, "PATCH", &uri, Some(changes)).await?;
+    assert_eq!(status, 200);
+    assert_eq!(updated["status"], "painted");
+    assert_eq!(updated["quantity"], 3);
+    assert_eq!(updated["notes"], "Blood for the arena");
+    assert_eq!(updated["version"], 2);
+
+    let request = Request::builder()
+        .method("PATCH")
+        .uri(&uri)
+        .header("content-type", "application/json")
+        .header("if-match", "\"1\"")
+        .body(Body::from(json!({ "quantity": 4 }).to_string()))?;
+    let response = app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), 412);
+
+    let (status, data) = send(&app, "GET", "/api/v1/user-models", None).await?;
+    assert_eq!(status, 200);
+    assert_eq!(
+        data,
+        json!([{
+            "id": created["id"],
+            "model_name": "Untamed Beasts",
+            "game_name": "Warcry",
+            "quantity": 3,
+            "status": "painted",
+            "location_id": null,
+            "notes": "Blood for the arena",
+            "tags": [],
+            "version": 2
+        }])
+    );
+
+    let uri = format!("/api/v1/user-models?location_id={}", Uuid::new_v4());
+    let (status, data) = send(&app, "GET", &uri, None).await?;
+    assert_eq!(status, 200);
+    assert_eq!(data, json!([]));
+
+    let uri = format!("/api/v1/user-models/{}", Uuid::new_v4());
+    let (status, data) = send(&app, "GET", &uri, None).await?;
+    assert_eq!(status, 404);
+    assert_eq!(data["error"], "user model not found");
+    let (status, _) = send(&app, "PATCH", &uri, Some(json!({ "quantity": 2 }))).await?;
+    assert_eq!(status, 404);
+    Ok(())
+}
+
+crate::backend_tests!(
+    memory,
+    catalog(Arc::new(backend::InMemoryRepository::new())),
+    games_are_unique_by_name,
+    model_definitions_need_a_game_and_keep_unset_fields,
+    user_models_validate_references_and_versions,
+);
+
+crate::backend_tests!(
+    sqlite,
+    catalog(sqlite_repository().await?),
+    games_are_unique_by_name,
+    model_definitions_need_a_game_and_keep_unset_fields,
+    user_models_validate_references_and_versions,
+);
+
+#[cfg(feature = "postgres")]
+crate::backend_tests!(
+    ignored postgres,
+    catalog(common::postgres_repository().await?),
+    "needs PostgreSQL: set TEST_POSTGRES_URL and pass --include-ignored",
+    games_are_unique_by_name,
+    model_definitions_need_a_game_and_keep_unset_fields,
+    user_models_validate_references_and_versions,
+);
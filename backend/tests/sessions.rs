@@ -0,0 +1,275 @@
+mod common;
+
+use backend::app;
+use serde_json::json;
+use uuid::Uuid;
+
+use common::{send, setup_state};
+
+async fn create_user_model(
+    app: &axum::Router,
+    game_name: &str,
+    name: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let (status, data) = send(app, "GET", "/api/v1/games", None).await?;
+    assert_eq!(status, 200);
+    let existing = data
+        .as_array()
+        .and_then(|games| games.iter().find(|game| game["name"] == game_name))
+        .and_then(|game| game["id"].as_str())
+        .map(str::to_string);
+    let game_id = match existing {
+        Some(id) => id,
+        None => {
+            let payload = json!({ "name": game_name });
+            let (status, data) = send(app, "POST", "/api/v1/games", Some(payload)).await?;
+            assert_eq!(status, 201);
+            data["id"].as_str().ok_or("missing game id")?.to_string()
+        }
+    };
+
+    let payload = json!({ "name": name, "game_id": game_id });
+    let (status, data) = send(app, "POST", "/api/v1/model-definitions", Some(payload)).await?;
+    assert_eq!(status, 201);
+    let model_definition_id = data["id"]
+        .as_str()
+        .ok_or("missing model definition id")?
+        .to_string();
+
+    let payload = json!({
+        "model_definition_id": model_definition_id,
+        "quantity": 5,
+        "status": "assembled"
+    });
+    let (status, data) = send(app, "POST", "/api/v1/user-models", Some(payload)).await?;
+    assert_eq!(status, 201);
+    Ok(data["id"]
+        .as_str()
+        .ok_or("missing user model id")?
+        .to_string())
+}
+
+async fn record_session(
+    app: &axum::Router,
+    user_model_ids: &[&str],
+    started_at: &str,
+    stopped_at: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let payload = json!({ "user_model_ids": user_model_ids, "started_at": started_at });
+    let (status, data) = send(app, "POST", "/api/v1/sessions/start", Some(payload)).await?;
+    assert_eq!(status, 201);
+    let id = data["id"].as_str().ok_or("missing session id")?;
+
+    let payload = json!({ "stopped_at": stopped_at });
+    let uri = format!("/api/v1/sessions/{id}/stop");
+    let (status, _) = send(app, "POST", &uri, Some(payload)).await?;
+    assert_eq!(status, 200);
+    Ok(())
+}
+
+#[tokio::test]
+async fn start_and_stop_session() -> Result<(), Box<dyn std::error::Error>> {
+    let state = setup_state().await?;
+    let app = app(state);
+
+    let (status, data) = send(&app, "GET", "/api/v1/sessions/active", None).await?;
+    assert_eq!(status, 200);
+    assert!(data.is_null());
+
+    let lot = create_user_model(&app, "Warcry", "Splintered Fang").await?;
+    let payload = json!({ "user_model_ids": [lot], "note": "base coats" });
+    let (status, data) = send(&app, "POST", "/api/v1/sessions/start", Some(payload)).await?;
+    assert_eq!(status, 201);
+    assert!(data["stopped_at"].is_null());
+    assert!(data["duration_hours"].is_null());
+    let id = data["id"].as_str().ok_or("missing session id")?.to_string();
+
+    let (_, data) = send(&app, "GET", "/api/v1/sessions/active", None).await?;
+    assert_eq!(data["id"], id);
+    assert_eq!(data["user_model_ids"][0], lot);
+
+    let uri = format!("/api/v1/sessions/{id}/stop");
+    let (status, data) = send(&app, "POST", &uri, Some(json!({}))).await?;
+    assert_eq!(status, 200);
+    assert!(data["stopped_at"].is_string());
+    assert_eq!(data["note"], "base coats");
+
+    let (status, _) = send(&app, "POST", &uri, Some(json!({}))).await?;
+    assert_eq!(status, 409);
+
+    let (_, data) = send(&app, "GET", "/api/v1/sessions/active", None).await?;
+    assert!(data.is_null());
+
+    let uri = format!("/api/v1/sessions/{}/stop", Uuid::new_v4());
+    let (status, _) = send(&app, "POST", &uri, Some(json!({}))).await?;
+    assert_eq!(status, 404);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn racing_stops_stop_once() -> Result<(), Box<dyn std::error::Error>> {
+    let app = app(setup_state().await?);
+
+    let lot = create_user_model(&app, "Warcry", "Cypher Lords").await?;
+    let payload = json!({ "user_model_ids": [lot], "started_at": "2026-03-10T18:00:00" });
+    let (status, data) = send(&app, "POST", "/api/v1/sessions/start", Some(payload)).await?;
+    assert_eq!(status, 201);
+    let id = data["id"].as_str().ok_or("missing session id")?.to_string();
+
+    let uri = format!("/api/v1/sessions/{id}/stop");
+    let first = json!({ "stopped_at": "2026-03-10T19:00:00" });
+    let second = json!({ "stopped_at": "2026-03-10T20:00:00" });
+    let (first, second) = tokio::join!(
+        send(&app, "POST", &uri, Some(first)),
+        send(&app, "POST", &uri, Some(second))
+    );
+    let mut statuses = [first?.0, second?.0];
+    statuses.sort();
+    assert_eq!(statuses, [200, 409]);
+
+    let uri = format!("/api/v1/audit?entity=session&entity_id={id}");
+    let (status, entries) = send(&app, "GET", &uri, None).await?;
+    assert_eq!(status, 200);
+    let updates = entries
+        .as_array()
+        .ok_or("expected audit entries")?
+        .iter()
+        .filter(|entry| entry["action"] == "update")
+        .count();
+    assert_eq!(updates, 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn overlapping_sessions_are_rejected() -> Result<(), Box<dyn std::error::Error>> {
+    let state = setup_state().await?;
+    let app = app(state);
+
+    record_session(&app, &[], "2026-03-01T18:00:00", "2026-03-01T20:00:00").await?;
+
+    let payload = json!({ "started_at": "2026-03-01T19:00:00" });
+    let (status, _) = send(&app, "POST", "/api/v1/sessions/start", Some(payload)).await?;
+    assert_eq!(status, 409);
+
+    let payload = json!({ "started_at": "2026-03-02T18:00:00" });
+    let (status, data) = send(&app, "POST", "/api/v1/sessions/start", Some(payload)).await?;
+    assert_eq!(status, 201);
+    let id = data["id"].as_str().ok_or("missing session id")?.to_string();
+
+    let (status, _) = send(&app, "POST", "/api/v1/sessions/start", Some(json!({}))).await?;
+    assert_eq!(status, 409);
+
+    let payload = json!({ "started_at": "yesterday-ish" });
+    let (status, _) = send(&app, "POST", "/api/v1/sessions/start", Some(payload)).await?;
+    assert_eq!(status, 422);
+
+    let payload = json!({ "stopped_at": "2026-03-02T17:00:00" });
+    let uri = format!("/api/v1/sessions/{id}/stop");
+    let (status, _) = send(&app, "POST", &uri, Some(payload)).await?;
+    assert_eq!(status, 422);
+
+    let payload =
+        json!({ "user_model_ids": [Uuid::new_v4()], "started_at": "2026-04-01T10:00:00" });
+    let (status, _) = send(&app, "POST", "/api/v1/sessions/start", Some(payload)).await?;
+    assert_eq!(status, 409, "the running session still blocks new ones");
+
+    let (_, data) = send(&app, "GET", "/api/v1/sessions", None).await?;
+    assert_eq!(data.as_array().ok_or("expected array response")?.len(), 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn backdated_sessions_cannot_overlap() -> Result<(), Box<dyn std::error::Error>> {
+    let state = setup_state().await?;
+    let app = app(state.clone());
+
+    record_session(&app, &[], "2026-03-01T18:00:00", "2026-03-01T20:00:00").await?;
+
+    let payload = json!({ "started_at": "2026-03-01T17:00:00" });
+    let (status, _) = send(&app, "POST", "/api/v1/sessions/start", Some(payload)).await?;
+    assert_eq!(status, 409, "a session started before 18:00 would run through it");
+
+    record_session(&app, &[], "2026-03-01T20:00:00", "2026-03-01T21:00:00").await?;
+
+    let payload = json!({ "started_at": "2026-03-05T10:00:00" });
+    let (status, data) = send(&app, "POST", "/api/v1/sessions/start", Some(payload)).await?;
+    assert_eq!(status, 201);
+    let id = data["id"].as_str().ok_or("missing session id")?.to_string();
+
+    // A finished session the start check never saw, say from before it existed.
+    sqlx::query(
+        "INSERT INTO sessions (id, started_at, stopped_at) VALUES ($1, '2026-03-05 12:00:00', '2026-03-05 13:00:00')",
+    )
+    .bind(Uuid::new_v4().to_string())
+    .execute(&state.pool)
+    .await?;
+
+    let uri = format!("/api/v1/sessions/{id}/stop");
+    let payload = json!({ "stopped_at": "2026-03-05T14:00:00" });
+    let (status, _) = send(&app, "POST", &uri, Some(payload)).await?;
+    assert_eq!(status, 409);
+
+    let payload = json!({ "stopped_at": "2026-03-05T12:00:00" });
+    let (status, data) = send(&app, "POST", &uri, Some(payload)).await?;
+    assert_eq!(status, 200);
+    assert_eq!(data["stopped_at"], "2026-03-05 12:00:00");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn stats_aggregate_hours() -> Result<(), Box<dyn std::error::Error>> {
+    let state = setup_state().await?;
+    let app = app(state);
+
+    let rangers = create_user_model(&app, "Kill Team", "Pathfinders").await?;
+    let kasrkin = create_user_model(&app, "Kill Team", "Kasrkin").await?;
+    let knight = create_user_model(&app, "Warhammer 40,000", "Armiger").await?;
+
+    record_session(
+        &app,
+        &[&rangers],
+        "2026-01-10T18:00:00",
+        "2026-01-10T21:00:00",
+    )
+    .await?;
+    record_session(
+        &app,
+        &[&rangers, &kasrkin],
+        "2026-01-20T18:00:00",
+        "2026-01-20T20:00:00",
+    )
+    .await?;
+    record_session(
+        &app,
+        &[&knight],
+        "2026-02-03T09:00:00",
+        "2026-02-03T10:30:00",
+    )
+    .await?;
+
+    let (status, data) = send(&app, "GET", "/api/v1/sessions/stats", None).await?;
+    assert_eq!(status, 200);
+
+    let hours = |value: &serde_json::Value| value["hours"].as_f64().unwrap_or_default();
+    let by_model = data["by_model"].as_array().ok_or("expected by_model")?;
+    assert_eq!(by_model[0]["model_name"], "Pathfinders");
+    assert!((hours(&by_model[0]) - 4.0).abs() < 0.01);
+    assert!((hours(&by_model[1]) - 1.5).abs() < 0.01);
+    assert!((hours(&by_model[2]) - 1.0).abs() < 0.01);
+
+    let by_game = data["by_game"].as_array().ok_or("expected by_game")?;
+    assert_eq!(by_game[0]["game_name"], "Kill Team");
+    assert!((hours(&by_game[0]) - 5.0).abs() < 0.01);
+
+    let by_month = data["by_month"].as_array().ok_or("expected by_month")?;
+    assert_eq!(by_month.len(), 2);
+    assert_eq!(by_month[0]["month"], "2026-01");
+    assert!((hours(&by_month[0]) - 5.0).abs() < 0.01);
+    assert!((hours(&by_month[1]) - 1.5).abs() < 0.01);
+
+    Ok(())
+}
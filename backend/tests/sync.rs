@@ -0,0 +1,286 @@
+mod common;
+
+use backend::app;
+use serde_json::json;
+
+use common::{send, setup_state};
+
+/// Pushes a batch and returns the per-record results.
+async fn push(
+    app: &axum::Router,
+    changes: serde_json::Value,
+) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>> {
+    let (status, data) = send(
+        app,
+        "POST",
+        "/api/v1/sync/push",
+        Some(json!({ "changes": changes })),
+    )
+    .await?;
+    assert_eq!(status, 200);
+    Ok(data["results"].as_array().ok_or("missing results")?.clone())
+}
+
+/// Creates a game, model definition and lot through sync and returns their IDs.
+async fn seed(app: &axum::Router) -> Result<[String; 3], Box<dyn std::error::Error>> {
+    let ids = [
+        uuid::Uuid::new_v4().to_string(),
+        uuid::Uuid::new_v4().to_string(),
+        uuid::Uuid::new_v4().to_string(),
+    ];
+    let results = push(
+        app,
+        json!([
+            {
+                "entity": "game",
+                "id": ids[0],
+                "base_version": null,
+                "modified_at": "2026-10-18T10:00:00Z",
+                "data": { "name": "Warhammer 40k" }
+            },
+            {
+                "entity": "model_definition",
+                "id": ids[1],
+                "base_version": null,
+                "modified_at": "2026-10-18T10:00:00Z",
+                "data": { "name": "Intercessors", "game_id": ids[0] }
+            },
+            {
+                "entity": "user_model",
+                "id": ids[2],
+                "base_version": null,
+                "modified_at": "2026-10-18T10:00:00Z",
+                "data": {
+                    "model_definition_id": ids[1],
+                    "quantity": 5,
+                    "status": "unassembled"
+                }
+            }
+        ]),
+    )
+    .await?;
+    assert!(results.iter().all(|result| result["status"] == "accepted"));
+    Ok(ids)
+}
+
+#[tokio::test]
+async fn change_feed_is_ordered_and_keeps_tombstones() -> Result<(), Box<dyn std::error::Error>> {
+    let state = setup_state().await?;
+    let app = app(state.clone());
+    let [game_id, model_definition_id, user_model_id] = seed(&app).await?;
+
+    let (status, feed) = send(&app, "GET", "/api/v1/sync/changes", None).await?;
+    assert_eq!(status, 200);
+    let ids = feed["changes"]
+        .as_array()
+        .ok_or("missing changes")?
+        .iter()
+        .map(|change| change["id"].as_str().unwrap_or_default())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, [&game_id, &model_definition_id, &user_model_id]);
+    assert_eq!(feed["changes"][2]["entity"], "user_model");
+    assert_eq!(feed["changes"][2]["data"]["quantity"], 5);
+    assert_eq!(feed["has_more"], false);
+    let cursor = feed["cursor"].as_i64().ok_or("missing cursor")?;
+    assert_eq!(feed["changes"][2]["version"], cursor);
+
+    let (_, page) = send(&app, "GET", "/api/v1/sync/changes?limit=2", None).await?;
+    assert_eq!(page["changes"].as_array().map(Vec::len), Some(2));
+    assert_eq!(page["has_more"], true);
+
+    // An edit moves the lot to the end of the sequence.
+    let uri = format!("/api/v1/user-models/{user_model_id}");
+    let (status, _) = send(&app, "PATCH", &uri, Some(json!({ "status": "painted" }))).await?;
+    assert_eq!(status, 200);
+    let (_, feed) = send(&app, "GET", &format!("/api/v1/sync/changes?since={cursor}"), None).await?;
+    assert_eq!(feed["changes"].as_array().map(Vec::len), Some(1));
+    assert_eq!(feed["changes"][0]["data"]["status"], "painted");
+    assert!(feed["cursor"].as_i64() > Some(cursor));
+    let cursor = feed["cursor"].as_i64().ok_or("missing cursor")?;
+
+    // Trashed and purged rows stay in the feed as tombstones.
+    let (status, _) = send(&app, "DELETE", &format!("/api/v1/games/{game_id}"), None).await?;
+    assert_eq!(status, 204);
+    let (_, feed) = send(&app, "GET", &format!("/api/v1/sync/changes?since={cursor}"), None).await?;
+    assert_eq!(feed["changes"][0]["id"], game_id.as_str());
+    assert_eq!(feed["changes"][0]["deleted"], true);
+    assert!(feed["changes"][0]["data"]["deleted_at"].is_string());
+
+    sqlx::query("UPDATE games SET deleted_at = datetime('now', '-40 days') WHERE id = $1")
+        .bind(&game_id)
+        .execute(&state.pool)
+        .await?;
+    assert_eq!(backend::purge_trash(&state, 30).await?, 3);
+    let (_, feed) = send(&app, "GET", &format!("/api/v1/sync/changes?since={cursor}"), None).await?;
+    assert_eq!(feed["changes"].as_array().map(Vec::len), Some(3));
+    assert!(feed["changes"]
+        .as_array()
+        .ok_or("missing changes")?
+        .iter()
+        .all(|change| change["deleted"] == true && change["data"].is_null()));
+
+    let (status, _) = send(&app, "GET", "/api/v1/sync/changes?since=-1", None).await?;
+    assert_eq!(status, 400);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn push_reports_each_record() -> Result<(), Box<dyn std::error::Error>> {
+    let app = app(setup_state().await?);
+    let [game_id, model_definition_id, user_model_id] = seed(&app).await?;
+
+    let (_, models) = send(&app, "GET", "/api/v1/user-models", None).await?;
+    assert_eq!(models[0]["id"], user_model_id.as_str());
+    assert_eq!(models[0]["model_name"], "Intercessors");
+
+    let (_, feed) = send(&app, "GET", "/api/v1/sync/changes", None).await?;
+    let version = feed["changes"][2]["version"].clone();
+    let results = push(
+        &app,
+        json!([
+            {
+                "entity": "user_model",
+                "id": user_model_id,
+                "base_version": version,
+                "modified_at": "2026-10-18T11:00:00Z",
+                "data": {
+                    "model_definition_id": model_definition_id,
+                    "quantity": 10,
+                    "status": "assembled"
+                }
+            },
+            {
+                "entity": "user_model",
+                "id": uuid::Uuid::new_v4(),
+                "base_version": null,
+                "modified_at": "2026-10-18T11:00:00Z",
+                "data": {
+                    "model_definition_id": model_definition_id,
+                    "quantity": 0,
+                    "status": "assembled"
+                }
+            },
+            {
+                "entity": "model_definition",
+                "id": uuid::Uuid::new_v4(),
+                "base_version": null,
+                "modified_at": "2026-10-18T11:00:00Z",
+                "data": { "name": "Hellblasters", "game_id": uuid::Uuid::new_v4() }
+            },
+            {
+                "entity": "game",
+                "id": game_id,
+                "base_version": null,
+                "modified_at": "not a time",
+                "deleted": true
+            },
+            {
+                "entity": "tag",
+                "id": uuid::Uuid::new_v4(),
+                "base_version": null,
+                "modified_at": "2026-10-18T11:00:00Z",
+                "data": { "name": "Primaris" }
+            }
+        ]),
+    )
+    .await?;
+
+    assert_eq!(results[0]["status"], "accepted");
+    assert!(results[0]["version"].as_i64() > version.as_i64());
+    assert_eq!(results[1]["status"], "rejected");
+    assert_eq!(results[1]["error"], "quantity must be greater than 0");
+    assert_eq!(results[2]["status"], "rejected");
+    assert_eq!(results[2]["error"], "game not found");
+    assert_eq!(results[3]["status"], "rejected");
+    assert_eq!(results[3]["error"], "invalid modified_at");
+    assert_eq!(results[4]["status"], "rejected");
+    assert_eq!(results[4]["error"], "entity cannot be synced");
+
+    // Only the accepted change was written.
+    let (_, models) = send(&app, "GET", "/api/v1/user-models", None).await?;
+    assert_eq!(models.as_array().map(Vec::len), Some(1));
+    assert_eq!(models[0]["quantity"], 10);
+    let (_, definitions) = send(&app, "GET", "/api/v1/model-definitions", None).await?;
+    assert_eq!(definitions.as_array().map(Vec::len), Some(1));
+
+    let results = push(
+        &app,
+        json!([{
+            "entity": "user_model",
+            "id": user_model_id,
+            "base_version": results[0]["version"],
+            "modified_at": "2026-10-18T12:00:00Z",
+            "deleted": true
+        }]),
+    )
+    .await?;
+    assert_eq!(results[0]["status"], "accepted");
+    let (_, models) = send(&app, "GET", "/api/v1/user-models", None).await?;
+    assert_eq!(models.as_array().map(Vec::len), Some(0));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn stale_pushes_resolve_last_write_wins() -> Result<(), Box<dyn std::error::Error>> {
+    let app = app(setup_state().await?);
+    let [_, model_definition_id, user_model_id] = seed(&app).await?;
+    let (_, feed) = send(&app, "GET", "/api/v1/sync/changes", None).await?;
+    let base_version = feed["changes"][2]["version"].clone();
+
+    // Someone else edits the lot, so `base_version` is now stale.
+    let uri = format!("/api/v1/user-models/{user_model_id}");
+    let (status, _) = send(&app, "PATCH", &uri, Some(json!({ "status": "painted" }))).await?;
+    assert_eq!(status, 200);
+
+    let change = |modified_at: &str, quantity: i32| {
+        json!([{
+            "entity": "user_model",
+            "id": user_model_id,
+            "base_version": base_version,
+            "modified_at": modified_at,
+            "data": {
+                "model_definition_id": model_definition_id,
+                "quantity": quantity,
+                "status": "assembled"
+            }
+        }])
+    };
+
+    // Made before the server's copy changed: the server wins.
+    let results = push(&app, change("2000-01-01T00:00:00Z", 7)).await?;
+    assert_eq!(results[0]["status"], "conflict");
+    assert_eq!(results[0]["current"]["data"]["status"], "painted");
+    assert_eq!(results[0]["current"]["data"]["quantity"], 5);
+    let (_, model) = send(&app, "GET", "/api/v1/user-models", None).await?;
+    assert_eq!(model[0]["status"], "painted");
+
+    // Made after it: the later write wins despite the stale base.
+    let results = push(&app, change("2999-01-01T00:00:00Z", 8)).await?;
+    assert_eq!(results[0]["status"], "accepted");
+    let (_, model) = send(&app, "GET", "/api/v1/user-models", None).await?;
+    assert_eq!(model[0]["status"], "assembled");
+    assert_eq!(model[0]["quantity"], 8);
+
+    // A create for an ID that already exists is just as stale.
+    let results = push(
+        &app,
+        json!([{
+            "entity": "user_model",
+            "id": user_model_id,
+            "base_version": null,
+            "modified_at": "2000-01-01T00:00:00Z",
+            "data": {
+                "model_definition_id": model_definition_id,
+                "quantity": 1,
+                "status": "unassembled"
+            }
+        }]),
+    )
+    .await?;
+    assert_eq!(results[0]["status"], "conflict");
+    assert_eq!(results[0]["current"]["data"]["quantity"], 8);
+
+    Ok(())
+}
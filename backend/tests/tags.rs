@@ -0,0 +1,228 @@
+mod common;
+
+use backend::app;
+use serde_json::json;
+
+use common::{send, setup_state};
+
+async fn create_tag(app: &axum::Router, name: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let (status, data) = send(app, "POST", "/api/v1/tags", Some(json!({ "name": name }))).await?;
+    assert_eq!(status, 201);
+    Ok(data["id"].as_str().ok_or("missing tag id")?.to_string())
+}
+
+async fn create_user_model(
+    app: &axum::Router,
+    name: &str,
+) -> Result<(String, String), Box<dyn std::error::Error>> {
+    let (status, data) = send(app, "POST", "/api/v1/games", Some(json!({ "name": name }))).await?;
+    assert_eq!(status, 201);
+    let game_id = data["id"].as_str().ok_or("missing game id")?.to_string();
+
+    let payload = json!({ "name": name, "game_id": game_id });
+    let (status, data) = send(app, "POST", "/api/v1/model-definitions", Some(payload)).await?;
+    assert_eq!(status, 201);
+    let model_definition_id = data["id"]
+        .as_str()
+        .ok_or("missing model definition id")?
+        .to_string();
+
+    let payload = json!({
+        "model_definition_id": model_definition_id,
+        "quantity": 1,
+        "status": "unassembled"
+    });
+    let (status, data) = send(app, "POST", "/api/v1/user-models", Some(payload)).await?;
+    assert_eq!(status, 201);
+    let id = data["id"]
+        .as_str()
+        .ok_or("missing user model id")?
+        .to_string();
+
+    Ok((id, model_definition_id))
+}
+
+#[tokio::test]
+async fn tag_crud() -> Result<(), Box<dyn std::error::Error>> {
+    let state = setup_state().await?;
+    let app = app(state);
+
+    let id = create_tag(&app, "magnetised").await?;
+
+    let (status, _) = send(&app, "POST", "/api/v1/tags", Some(json!({ "name": "magnetised" }))).await?;
+    assert_eq!(status, 409);
+
+    let (status, _) = send(&app, "POST", "/api/v1/tags", Some(json!({ "name": "  " }))).await?;
+    assert_eq!(status, 422);
+
+    let payload = json!({ "name": "magnetized" });
+    let (status, data) = send(&app, "PATCH", &format!("/api/v1/tags/{id}"), Some(payload)).await?;
+    assert_eq!(status, 200);
+    assert_eq!(data["name"], "magnetized");
+
+    let (status, data) = send(&app, "GET", "/api/v1/tags", None).await?;
+    assert_eq!(status, 200);
+    assert_eq!(data.as_array().ok_or("expected array response")?.len(), 1);
+
+    let (status, _) = send(&app, "DELETE", &format!("/api/v1/tags/{id}"), None).await?;
+    assert_eq!(status, 204);
+
+    let (status, _) = send(&app, "DELETE", &format!("/api/v1/tags/{id}"), None).await?;
+    assert_eq!(status, 404);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn user_model_tag_filters() -> Result<(), Box<dyn std::error::Error>> {
+    let state = setup_state().await?;
+    let app = app(state);
+
+    let tournament = create_tag(&app, "tournament list").await?;
+    let commission = create_tag(&app, "commission").await?;
+    let (both, _) = create_user_model(&app, "Custodians").await?;
+    let (only_tournament, _) = create_user_model(&app, "Sisters").await?;
+    create_user_model(&app, "Untagged").await?;
+
+    let payload = json!({ "tag_ids": [tournament, commission] });
+    let (status, data) = send(
+        &app,
+        "PUT",
+        &format!("/api/v1/user-models/{both}/tags"),
+        Some(payload),
+    )
+    .await?;
+    assert_eq!(status, 200);
+    assert_eq!(data.as_array().ok_or("expected array response")?.len(), 2);
+
+    let payload = json!({ "tag_ids": [tournament] });
+    let (status, _) = send(
+        &app,
+        "PUT",
+        &format!("/api/v1/user-models/{only_tournament}/tags"),
+        Some(payload),
+    )
+    .await?;
+    assert_eq!(status, 200);
+
+    let uri = format!("/api/v1/user-models?tag_ids={tournament},{commission}");
+    let (status, data) = send(&app, "GET", &uri, None).await?;
+    assert_eq!(status, 200);
+    let list = data.as_array().ok_or("expected array response")?;
+    assert_eq!(list.len(), 1);
+    assert_eq!(list[0]["id"], both);
+    assert_eq!(list[0]["tags"][0]["name"], "commission");
+
+    let uri = format!("/api/v1/user-models?tag_ids={tournament},{commission}&tag_mode=any");
+    let (_, data) = send(&app, "GET", &uri, None).await?;
+    assert_eq!(data.as_array().ok_or("expected array response")?.len(), 2);
+
+    let (_, data) = send(&app, "GET", "/api/v1/user-models", None).await?;
+    assert_eq!(data.as_array().ok_or("expected array response")?.len(), 3);
+
+    let (status, _) = send(&app, "GET", "/api/v1/user-models?tag_ids=nope", None).await?;
+    assert_eq!(status, 400);
+
+    let payload = json!({ "tag_ids": [uuid::Uuid::new_v4()] });
+    let (status, _) = send(
+        &app,
+        "PUT",
+        &format!("/api/v1/user-models/{both}/tags"),
+        Some(payload),
+    )
+    .await?;
+    assert_eq!(status, 404);
+
+    let (_, data) = send(
+        &app,
+        "GET",
+        &format!("/api/v1/user-models?tag_ids={commission}"),
+        None,
+    )
+    .await?;
+    assert_eq!(
+        data.as_array().ok_or("expected array response")?.len(),
+        1,
+        "failed tag update must roll back"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn notes_and_definition_tags() -> Result<(), Box<dyn std::error::Error>> {
+    let state = setup_state().await?;
+    let app = app(state);
+
+    let tag = create_tag(&app, "metal").await?;
+    let (user_model_id, model_definition_id) = create_user_model(&app, "Old Marines").await?;
+
+    let payload = json!({ "notes": "missing left arm" });
+    let (status, data) = send(
+        &app,
+        "PATCH",
+        &format!("/api/v1/user-models/{user_model_id}"),
+        Some(payload),
+    )
+    .await?;
+    assert_eq!(status, 200);
+    assert_eq!(data["notes"], "missing left arm");
+    assert_eq!(data["status"], "unassembled");
+
+    let payload = json!({ "status": "assembled" });
+    let (_, data) = send(
+        &app,
+        "PATCH",
+        &format!("/api/v1/user-models/{user_model_id}"),
+        Some(payload),
+    )
+    .await?;
+    assert_eq!(data["notes"], "missing left arm");
+    assert_eq!(data["status"], "assembled");
+
+    let payload = json!({ "notes": "" });
+    let (_, data) = send(
+        &app,
+        "PATCH",
+        &format!("/api/v1/user-models/{user_model_id}"),
+        Some(payload),
+    )
+    .await?;
+    assert!(data["notes"].is_null());
+
+    let payload = json!({ "notes": "2nd edition sculpt" });
+    let (status, data) = send(
+        &app,
+        "PATCH",
+        &format!("/api/v1/model-definitions/{model_definition_id}"),
+        Some(payload),
+    )
+    .await?;
+    assert_eq!(status, 200);
+    assert_eq!(data["notes"], "2nd edition sculpt");
+
+    let payload = json!({ "tag_ids": [tag] });
+    let (status, _) = send(
+        &app,
+        "PUT",
+        &format!("/api/v1/model-definitions/{model_definition_id}/tags"),
+        Some(payload),
+    )
+    .await?;
+    assert_eq!(status, 200);
+
+    let (status, data) = send(
+        &app,
+        "GET",
+        &format!("/api/v1/model-definitions?tag_ids={tag}"),
+        None,
+    )
+    .await?;
+    assert_eq!(status, 200);
+    let list = data.as_array().ok_or("expected array response")?;
+    assert_eq!(list.len(), 1);
+    assert_eq!(list[0]["tags"][0]["name"], "metal");
+    assert_eq!(list[0]["notes"], "2nd edition sculpt");
+
+    Ok(())
+}
@@ -0,0 +1,292 @@
+mod common;
+
+use backend::app;
+use serde_json::json;
+
+use common::{send, setup_state};
+
+struct Lot {
+    game_id: String,
+    model_definition_id: String,
+    user_model_id: String,
+}
+
+async fn create_lot(
+    app: &axum::Router,
+    game_name: &str,
+    name: &str,
+) -> Result<Lot, Box<dyn std::error::Error>> {
+    let payload = json!({ "name": game_name });
+    let (status, data) = send(app, "POST", "/api/v1/games", Some(payload)).await?;
+    assert_eq!(status, 201);
+    let game_id = data["id"].as_str().ok_or("missing game id")?.to_string();
+
+    let payload = json!({ "name": name, "game_id": game_id });
+    let (status, data) = send(app, "POST", "/api/v1/model-definitions", Some(payload)).await?;
+    assert_eq!(status, 201);
+    let model_definition_id = data["id"]
+        .as_str()
+        .ok_or("missing model definition id")?
+        .to_string();
+
+    let payload = json!({
+        "model_definition_id": model_definition_id,
+        "quantity": 5,
+        "status": "assembled"
+    });
+    let (status, data) = send(app, "POST", "/api/v1/user-models", Some(payload)).await?;
+    assert_eq!(status, 201);
+    let user_model_id = data["id"]
+        .as_str()
+        .ok_or("missing user model id")?
+        .to_string();
+
+    Ok(Lot {
+        game_id,
+        model_definition_id,
+        user_model_id,
+    })
+}
+
+async fn count(app: &axum::Router, uri: &str) -> Result<usize, Box<dyn std::error::Error>> {
+    let (status, data) = send(app, "GET", uri, None).await?;
+    assert_eq!(status, 200);
+    Ok(data.as_array().ok_or("expected an array")?.len())
+}
+
+#[tokio::test]
+async fn deleted_user_model_moves_to_trash_and_restores() -> Result<(), Box<dyn std::error::Error>>
+{
+    let app = app(setup_state().await?);
+    let lot = create_lot(&app, "Warhammer 40k", "Intercessors").await?;
+
+    let uri = format!("/api/v1/user-models/{}", lot.user_model_id);
+    let (status, _) = send(&app, "DELETE", &uri, None).await?;
+    assert_eq!(status, 204);
+    assert_eq!(count(&app, "/api/v1/user-models").await?, 0);
+
+    let (status, _) = send(&app, "DELETE", &uri, None).await?;
+    assert_eq!(status, 404);
+    let payload = json!({ "status": "painted" });
+    let (status, _) = send(&app, "PATCH", &uri, Some(payload)).await?;
+    assert_eq!(status, 404);
+
+    let (status, trash) = send(&app, "GET", "/api/v1/trash", None).await?;
+    assert_eq!(status, 200);
+    assert_eq!(trash[0]["id"], lot.user_model_id.as_str());
+    assert_eq!(trash[0]["entity"], "user_model");
+    assert_eq!(trash[0]["name"], "Intercessors");
+
+    let restore = format!("/api/v1/trash/{}/restore", lot.user_model_id);
+    let (status, _) = send(&app, "POST", &restore, None).await?;
+    assert_eq!(status, 204);
+    assert_eq!(count(&app, "/api/v1/user-models").await?, 1);
+    assert_eq!(count(&app, "/api/v1/trash").await?, 0);
+
+    let (status, _) = send(&app, "POST", &restore, None).await?;
+    assert_eq!(status, 404);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn trashed_game_hides_its_models_until_restored() -> Result<(), Box<dyn std::error::Error>> {
+    let app = app(setup_state().await?);
+    let lot = create_lot(&app, "Warhammer 40k", "Intercessors").await?;
+    create_lot(&app, "Age of Sigmar", "Liberators").await?;
+
+    let uri = format!("/api/v1/user-models/{}", lot.user_model_id);
+    let (status, _) = send(&app, "DELETE", &uri, None).await?;
+    assert_eq!(status, 204);
+    let uri = format!("/api/v1/games/{}", lot.game_id);
+    let (status, _) = send(&app, "DELETE", &uri, None).await?;
+    assert_eq!(status, 204);
+
+    assert_eq!(count(&app, "/api/v1/games").await?, 1);
+    assert_eq!(count(&app, "/api/v1/model-definitions").await?, 1);
+    assert_eq!(count(&app, "/api/v1/user-models").await?, 1);
+
+    let payload = json!({
+        "model_definition_id": lot.model_definition_id,
+        "quantity": 1,
+        "status": "unassembled"
+    });
+    let (status, _) = send(&app, "POST", "/api/v1/user-models", Some(payload)).await?;
+    assert_eq!(status, 404);
+
+    let (status, data) = send(
+        &app,
+        "POST",
+        &format!("/api/v1/trash/{}/restore", lot.user_model_id),
+        None,
+    )
+    .await?;
+    assert_eq!(status, 409);
+    assert_eq!(data["error"], "restore the model definition first");
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/api/v1/trash/{}/restore", lot.game_id),
+        None,
+    )
+    .await?;
+    assert_eq!(status, 204);
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/api/v1/trash/{}/restore", lot.user_model_id),
+        None,
+    )
+    .await?;
+    assert_eq!(status, 204);
+
+    assert_eq!(count(&app, "/api/v1/games").await?, 2);
+    assert_eq!(count(&app, "/api/v1/model-definitions").await?, 2);
+    assert_eq!(count(&app, "/api/v1/user-models").await?, 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn lots_under_a_trashed_game_are_missing() -> Result<(), Box<dyn std::error::Error>> {
+    let app = app(setup_state().await?);
+    let lot = create_lot(&app, "Necromunda", "Goliath Gang").await?;
+
+    let uri = format!("/api/v1/games/{}", lot.game_id);
+    let (status, _) = send(&app, "DELETE", &uri, None).await?;
+    assert_eq!(status, 204);
+
+    let uri = format!("/api/v1/user-models/{}", lot.user_model_id);
+    let (status, data) = send(&app, "GET", &uri, None).await?;
+    assert_eq!(status, 404);
+    assert_eq!(data["error"], "user model not found");
+    let payload = json!({ "status": "painted" });
+    let (status, _) = send(&app, "PATCH", &uri, Some(payload)).await?;
+    assert_eq!(status, 404);
+
+    let restore = format!("/api/v1/trash/{}/restore", lot.game_id);
+    let (status, _) = send(&app, "POST", &restore, None).await?;
+    assert_eq!(status, 204);
+    let (status, data) = send(&app, "GET", &uri, None).await?;
+    assert_eq!(status, 200);
+    assert_eq!(data["status"], "assembled");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn purge_removes_only_expired_trash() -> Result<(), Box<dyn std::error::Error>> {
+    let state = setup_state().await?;
+    let app = app(state.clone());
+    let expired = create_lot(&app, "Warhammer 40k", "Intercessors").await?;
+    let recent = create_lot(&app, "Age of Sigmar", "Liberators").await?;
+
+    let (status, _) = send(&app, "DELETE", &format!("/api/v1/games/{}", expired.game_id), None).await?;
+    assert_eq!(status, 204);
+    let uri = format!("/api/v1/user-models/{}", recent.user_model_id);
+    let (status, _) = send(&app, "DELETE", &uri, None).await?;
+    assert_eq!(status, 204);
+
+    sqlx::query("UPDATE games SET deleted_at = datetime('now', '-40 days') WHERE id = $1")
+        .bind(&expired.game_id)
+        .execute(&state.pool)
+        .await?;
+
+    // The game, its model definition and its lot are all removed.
+    assert_eq!(backend::purge_trash(&state, 30).await?, 3);
+    assert_eq!(backend::purge_trash(&state, 30).await?, 0);
+
+    let (status, trash) = send(&app, "GET", "/api/v1/trash", None).await?;
+    assert_eq!(status, 200);
+    assert_eq!(trash.as_array().map(Vec::len), Some(1));
+    assert_eq!(trash[0]["id"], recent.user_model_id.as_str());
+
+    let uri = format!(
+        "/api/v1/audit?entity=user_model&entity_id={}",
+        expired.user_model_id
+    );
+    let (status, entries) = send(&app, "GET", &uri, None).await?;
+    assert_eq!(status, 200);
+    assert_eq!(entries[0]["action"], "purge");
+    assert_eq!(entries[0]["actor"], "purge-job");
+    assert!(entries[0]["after"].is_null());
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/api/v1/trash/{}/restore", expired.game_id),
+        None,
+    )
+    .await?;
+    assert_eq!(status, 404);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn trashed_lots_cannot_be_tagged_moved_or_painted() -> Result<(), Box<dyn std::error::Error>>
+{
+    let app = app(setup_state().await?);
+    let lot = create_lot(&app, "Warhammer Underworlds", "Thorns of the Briar Queen").await?;
+    let kept = create_lot(&app, "Warcry", "Iron Golem").await?;
+
+    let payload = json!({
+        "user_model_ids": [kept.user_model_id, lot.user_model_id],
+        "started_at": "2026-02-01T18:00:00"
+    });
+    let (status, data) = send(&app, "POST", "/api/v1/sessions/start", Some(payload)).await?;
+    assert_eq!(status, 201);
+    let uri = format!("/api/v1/sessions/{}/stop", data["id"].as_str().ok_or("missing session id")?);
+    let payload = json!({ "stopped_at": "2026-02-01T20:00:00" });
+    let (status, _) = send(&app, "POST", &uri, Some(payload)).await?;
+    assert_eq!(status, 200);
+
+    let (status, data) = send(&app, "POST", "/api/v1/tags", Some(json!({ "name": "wip" }))).await?;
+    assert_eq!(status, 201);
+    let tag_id = data["id"].clone();
+    let (status, data) = send(
+        &app,
+        "POST",
+        "/api/v1/locations",
+        Some(json!({ "name": "Hobby Room", "kind": "room" })),
+    )
+    .await?;
+    assert_eq!(status, 201);
+    let location_id = data["id"].clone();
+
+    let uri = format!("/api/v1/user-models/{}", lot.user_model_id);
+    let (status, _) = send(&app, "DELETE", &uri, None).await?;
+    assert_eq!(status, 204);
+
+    let uri = format!("/api/v1/user-models/{}/tags", lot.user_model_id);
+    let (status, _) = send(&app, "PUT", &uri, Some(json!({ "tag_ids": [tag_id] }))).await?;
+    assert_eq!(status, 404);
+
+    let payload = json!({ "user_model_ids": [lot.user_model_id], "location_id": location_id });
+    let (status, _) = send(&app, "POST", "/api/v1/user-models/move", Some(payload)).await?;
+    assert_eq!(status, 404);
+
+    let payload = json!({ "user_model_ids": [lot.user_model_id] });
+    let (status, _) = send(&app, "POST", "/api/v1/sessions/start", Some(payload)).await?;
+    assert_eq!(status, 404);
+
+    // Its share of the session no longer counts, and the kept lot's is unchanged.
+    let (status, data) = send(&app, "GET", "/api/v1/sessions/stats", None).await?;
+    assert_eq!(status, 200);
+    assert_eq!(data["by_model"].as_array().map(Vec::len), Some(1));
+    assert_eq!(data["by_model"][0]["user_model_id"], kept.user_model_id);
+    let hours = data["by_model"][0]["hours"].as_f64().unwrap_or_default();
+    assert!((hours - 1.0).abs() < 0.01);
+    assert_eq!(data["by_game"].as_array().map(Vec::len), Some(1));
+
+    // Trashing the game hides its lots the same way.
+    let uri = format!("/api/v1/games/{}", kept.game_id);
+    let (status, _) = send(&app, "DELETE", &uri, None).await?;
+    assert_eq!(status, 204);
+    let payload = json!({ "user_model_ids": [kept.user_model_id], "location_id": location_id });
+    let (status, _) = send(&app, "POST", "/api/v1/user-models/move", Some(payload)).await?;
+    assert_eq!(status, 404);
+
+    Ok(())
+}
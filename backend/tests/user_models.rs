@@ -1,32 +1,12 @@
-use axum::{body::Body, http::Request};
-use backend::{app, AppState};
+mod common;
+
+use axum::{body::Body, http::Request, Router};
+use backend::app;
 use serde_json::json;
-use sqlx::sqlite::SqlitePoolOptions;
 use tower::ServiceExt;
 use uuid::Uuid;
 
-async fn setup_state() -> Result<AppState, Box<dyn std::error::Error>> {
-    let pool = SqlitePoolOptions::new()
-        .max_connections(1)
-        .connect("sqlite::memory:")
-        .await?;
-
-    sqlx::query("PRAGMA foreign_keys = ON;")
-        .execute(&pool)
-        .await?;
-
-    sqlx::migrate!().run(&pool).await?;
-
-    sqlx::query("DELETE FROM user_models;")
-        .execute(&pool)
-        .await?;
-    sqlx::query("DELETE FROM model_definitions;")
-        .execute(&pool)
-        .await?;
-    sqlx::query("DELETE FROM games;").execute(&pool).await?;
-
-    Ok(AppState { pool })
-}
+use common::setup_state;
 
 async fn create_game(
     app: axum::Router,
@@ -35,7 +15,7 @@ async fn create_game(
     let payload = json!({ "name": name }).to_string();
     let request = Request::builder()
         .method("POST")
-        .uri("/games")
+        .uri("/api/v1/games")
         .header("content-type", "application/json")
         .body(Body::from(payload))?;
 
@@ -60,7 +40,7 @@ async fn create_model_definition(
     let payload = json!({ "name": name, "game_id": game_id }).to_string();
     let request = Request::builder()
         .method("POST")
-        .uri("/model-definitions")
+        .uri("/api/v1/model-definitions")
         .header("content-type", "application/json")
         .body(Body::from(payload))?;
 
@@ -77,10 +57,7 @@ async fn create_model_definition(
     Ok((app, id))
 }
 
-#[tokio::test]
-async fn create_user_model_returns_created() -> Result<(), Box<dyn std::error::Error>> {
-    let state = setup_state().await?;
-    let app = app(state);
+async fn create_user_model_returns_created(app: Router) -> Result<(), Box<dyn std::error::Error>> {
     let (app, game_id) = create_game(app, "Conquest").await?;
     let (app, model_definition_id) =
         create_model_definition(app, "Household Guard", game_id).await?;
@@ -93,7 +70,7 @@ async fn create_user_model_returns_created() -> Result<(), Box<dyn std::error::E
     .to_string();
     let request = Request::builder()
         .method("POST")
-        .uri("/user-models")
+        .uri("/api/v1/user-models")
         .header("content-type", "application/json")
         .body(Body::from(payload))?;
 
@@ -110,10 +87,7 @@ async fn create_user_model_returns_created() -> Result<(), Box<dyn std::error::E
     Ok(())
 }
 
-#[tokio::test]
-async fn update_user_model_status() -> Result<(), Box<dyn std::error::Error>> {
-    let state = setup_state().await?;
-    let app = app(state);
+async fn update_user_model_status(app: Router) -> Result<(), Box<dyn std::error::Error>> {
     let (app, game_id) = create_game(app, "The Old World").await?;
     let (app, model_definition_id) =
         create_model_definition(app, "Empire Knights", game_id).await?;
@@ -126,7 +100,7 @@ async fn update_user_model_status() -> Result<(), Box<dyn std::error::Error>> {
     .to_string();
     let request = Request::builder()
         .method("POST")
-        .uri("/user-models")
+        .uri("/api/v1/user-models")
         .header("content-type", "application/json")
         .body(Body::from(payload))?;
     let response = app.clone().oneshot(request).await?;
@@ -142,7 +116,7 @@ async fn update_user_model_status() -> Result<(), Box<dyn std::error::Error>> {
     let payload = json!({ "status": "painted" }).to_string();
     let request = Request::builder()
         .method("PATCH")
-        .uri(format!("/user-models/{user_model_id}"))
+        .uri(format!("/api/v1/user-models/{user_model_id}"))
         .header("content-type", "application/json")
         .body(Body::from(payload))?;
     let response = app.oneshot(request).await?;
@@ -155,10 +129,58 @@ async fn update_user_model_status() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-#[tokio::test]
-async fn invalid_status_rejected() -> Result<(), Box<dyn std::error::Error>> {
-    let state = setup_state().await?;
-    let app = app(state);
+async fn update_user_model_quantity(app: Router) -> Result<(), Box<dyn std::error::Error>> {
+    let (app, game_id) = create_game(app, "Kill Team").await?;
+    let (app, model_definition_id) = create_model_definition(app, "Kommandos", game_id).await?;
+
+    let payload = json!({
+        "model_definition_id": model_definition_id,
+        "quantity": 5,
+        "status": "assembled"
+    })
+    .to_string();
+    let request = Request::builder()
+        .method("POST")
+        .uri("/api/v1/user-models")
+        .header("content-type", "application/json")
+        .body(Body::from(payload))?;
+    let response = app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), 201);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    let data: serde_json::Value = serde_json::from_slice(&body)?;
+    let user_model_id = data["id"]
+        .as_str()
+        .ok_or("missing user model id")?
+        .parse::<Uuid>()?;
+
+    let payload = json!({ "quantity": 8 }).to_string();
+    let request = Request::builder()
+        .method("PATCH")
+        .uri(format!("/api/v1/user-models/{user_model_id}"))
+        .header("content-type", "application/json")
+        .body(Body::from(payload))?;
+    let response = app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), 200);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    let data: serde_json::Value = serde_json::from_slice(&body)?;
+    assert_eq!(data["quantity"], 8);
+    assert_eq!(data["status"], "assembled");
+
+    let payload = json!({ "quantity": 0 }).to_string();
+    let request = Request::builder()
+        .method("PATCH")
+        .uri(format!("/api/v1/user-models/{user_model_id}"))
+        .header("content-type", "application/json")
+        .body(Body::from(payload))?;
+    let response = app.oneshot(request).await?;
+    assert_eq!(response.status(), 422);
+
+    Ok(())
+}
+
+async fn invalid_status_rejected(app: Router) -> Result<(), Box<dyn std::error::Error>> {
     let (app, game_id) = create_game(app, "Malifaux").await?;
     let (app, model_definition_id) =
         create_model_definition(app, "Neverborn", game_id).await?;
@@ -171,7 +193,7 @@ async fn invalid_status_rejected() -> Result<(), Box<dyn std::error::Error>> {
     .to_string();
     let request = Request::builder()
         .method("POST")
-        .uri("/user-models")
+        .uri("/api/v1/user-models")
         .header("content-type", "application/json")
         .body(Body::from(payload))?;
 
@@ -180,3 +202,103 @@ async fn invalid_status_rejected() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+async fn stale_if_match_is_rejected(app: Router) -> Result<(), Box<dyn std::error::Error>> {
+    let (app, game_id) = create_game(app, "Kings of War").await?;
+    let (app, model_definition_id) = create_model_definition(app, "Ogre Warriors", game_id).await?;
+
+    let payload = json!({
+        "model_definition_id": model_definition_id,
+        "quantity": 3,
+        "status": "unassembled"
+    })
+    .to_string();
+    let request = Request::builder()
+        .method("POST")
+        .uri("/api/v1/user-models")
+        .header("content-type", "application/json")
+        .body(Body::from(payload))?;
+    let response = app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), 201);
+    let created_etag = response
+        .headers()
+        .get("etag")
+        .ok_or("missing etag")?
+        .to_str()?
+        .to_string();
+    assert_eq!(created_etag, "\"1\"");
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    let data: serde_json::Value = serde_json::from_slice(&body)?;
+    let uri = format!("/api/v1/user-models/{}", data["id"].as_str().ok_or("missing id")?);
+
+    let patch = |if_match: &str, status: &str| {
+        Request::builder()
+            .method("PATCH")
+            .uri(&uri)
+            .header("content-type", "application/json")
+            .header("if-match", if_match)
+            .body(Body::from(json!({ "status": status }).to_string()))
+    };
+
+    // The first tab saves against the version it loaded.
+    let response = app
+        .clone()
+        .oneshot(patch(&created_etag, "assembled")?)
+        .await?;
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["etag"], "\"2\"");
+
+    // The second tab still holds the old version and must not overwrite it.
+    let response = app
+        .clone()
+        .oneshot(patch(&created_etag, "painted")?)
+        .await?;
+    assert_eq!(response.status(), 412);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    let data: serde_json::Value = serde_json::from_slice(&body)?;
+    assert_eq!(data["error"], "user model has been changed");
+
+    let request = Request::builder().uri(&uri).body(Body::empty())?;
+    let response = app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["etag"], "\"2\"");
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    let data: serde_json::Value = serde_json::from_slice(&body)?;
+    assert_eq!(data["status"], "assembled");
+    assert_eq!(data["version"], 2);
+
+    // Any listed version, or `*`, is enough.
+    let response = app
+        .clone()
+        .oneshot(patch("\"1\", \"2\"", "painted")?)
+        .await?;
+    assert_eq!(response.status(), 200);
+    let response = app.clone().oneshot(patch("*", "unassembled")?).await?;
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["etag"], "\"4\"");
+
+    Ok(())
+}
+
+crate::backend_tests!(
+    sqlite,
+    app(setup_state().await?),
+    create_user_model_returns_created,
+    update_user_model_status,
+    update_user_model_quantity,
+    invalid_status_rejected,
+    stale_if_match_is_rejected,
+);
+
+#[cfg(feature = "postgres")]
+crate::backend_tests!(
+    ignored postgres,
+    common::postgres_app().await?,
+    "needs PostgreSQL: set TEST_POSTGRES_URL and pass --include-ignored",
+    create_user_model_returns_created,
+    update_user_model_status,
+    update_user_model_quantity,
+    invalid_status_rejected,
+    stale_if_match_is_rejected,
+);
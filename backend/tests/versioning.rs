@@ -0,0 +1,129 @@
+mod common;
+
+use std::sync::Arc;
+
+use axum::{
+    http::{header, StatusCode},
+    response::Response,
+};
+use backend::{
+    app, catalog_app, openapi, CatalogState, EventBus, Features, InMemoryRepository,
+    DEPRECATED_SINCE, SUNSET,
+};
+use serde_json::json;
+
+use common::{json_body, request, setup_state};
+
+fn is_deprecated(response: &Response) -> bool {
+    response.headers().contains_key("deprecation")
+}
+
+#[tokio::test]
+async fn routes_are_served_under_api_v1() -> Result<(), Box<dyn std::error::Error>> {
+    let app = app(setup_state().await?);
+
+    let response = request(
+        &app,
+        "POST",
+        "/api/v1/games",
+        Some(json!({ "name": "Necromunda" })),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert!(!is_deprecated(&response));
+
+    let response = request(&app, "GET", "/api/v1/games", None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(!is_deprecated(&response));
+    assert_eq!(json_body(response).await[0]["name"], "Necromunda");
+
+    let response = request(&app, "GET", "/health", None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(!is_deprecated(&response));
+
+    let response = request(&app, "GET", "/api/v1/health", None).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = request(&app, "GET", "/api/v2/games", None).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    Ok(())
+}
+
+#[tokio::test]
+async fn unversioned_paths_still_work_but_are_deprecated() -> Result<(), Box<dyn std::error::Error>>
+{
+    let app = app(setup_state().await?);
+
+    let response = request(&app, "POST", "/games", Some(json!({ "name": "Mordheim" }))).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert!(is_deprecated(&response));
+
+    let response = request(&app, "GET", "/user-models?tag_mode=any", None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let headers = response.headers();
+    assert_eq!(headers["deprecation"], DEPRECATED_SINCE);
+    assert_eq!(headers["sunset"], SUNSET);
+    assert_eq!(
+        headers[header::LINK],
+        "</api/v1/user-models?tag_mode=any>; rel=\"successor-version\""
+    );
+
+    let response = request(&app, "GET", "/api/v1/games", None).await;
+    assert_eq!(json_body(response).await[0]["name"], "Mordheim");
+
+    // Errors are marked too.
+    let response = request(&app, "POST", "/games", Some(json!({ "name": "Mordheim" }))).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    assert!(is_deprecated(&response));
+    Ok(())
+}
+
+#[tokio::test]
+async fn unversioned_spec_is_deprecated() -> Result<(), Box<dyn std::error::Error>> {
+    let app = app(setup_state().await?);
+
+    let response = request(&app, "GET", openapi::SPEC_PATH, None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(!is_deprecated(&response));
+    let spec = json_body(response).await;
+
+    let response = request(&app, "GET", "/openapi.json", None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let headers = response.headers();
+    assert_eq!(headers["deprecation"], DEPRECATED_SINCE);
+    assert_eq!(headers["sunset"], SUNSET);
+    assert_eq!(
+        headers[header::LINK],
+        "</api/v1/openapi.json>; rel=\"successor-version\""
+    );
+    assert_eq!(json_body(response).await, spec);
+    Ok(())
+}
+
+#[tokio::test]
+async fn catalog_app_is_versioned_the_same_way() -> Result<(), Box<dyn std::error::Error>> {
+    let state = CatalogState {
+        repository: Arc::new(InMemoryRepository::new()),
+        events: EventBus::new(),
+    };
+    let app = catalog_app(state, &Features::default());
+
+    let response = request(
+        &app,
+        "POST",
+        "/api/v1/games",
+        Some(json!({ "name": "Blood Bowl" })),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert!(!is_deprecated(&response));
+
+    let response = request(&app, "GET", "/games", None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(is_deprecated(&response));
+
+    let response = request(&app, "GET", "/health", None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(!is_deprecated(&response));
+    Ok(())
+}
@@ -0,0 +1,554 @@
+use gloo_net::http::Request;
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{fetch_user_models, Game, API_BASE};
+
+#[component]
+pub fn ArmyListsPage(games: Resource<(), Result<Vec<Game>, String>>) -> impl IntoView {
+    let lists_refresh = create_rw_signal(0u32);
+    let lists = create_resource(move || lists_refresh.get(), |_| async {
+        fetch_army_lists().await
+    });
+    let selected_list_id = create_rw_signal(None::<String>);
+
+    let name = create_rw_signal(String::new());
+    let game_id = create_rw_signal(String::new());
+    let points_limit = create_rw_signal(String::new());
+    let form_error = create_rw_signal(None::<String>);
+
+    create_effect(move |_| {
+        if game_id.get().is_empty()
+            && let Some(Ok(list)) = games.get()
+            && let Some(game) = list.first()
+        {
+            game_id.set(game.id.clone());
+        }
+    });
+
+    view! {
+        <section class="panel">
+            <h2>"New Army List"</h2>
+            <form class="form" on:submit=move |event| {
+                event.prevent_default();
+                let name_value = name.get().trim().to_string();
+                let game_id_value = game_id.get();
+                if name_value.is_empty() {
+                    form_error.set(Some("List name is required.".to_string()));
+                    return;
+                }
+                if game_id_value.is_empty() {
+                    form_error.set(Some("Select a game.".to_string()));
+                    return;
+                }
+                let limit = match points_limit.get().trim() {
+                    "" => None,
+                    value => match value.parse::<i64>() {
+                        Ok(limit) if limit > 0 => Some(limit),
+                        _ => {
+                            form_error.set(Some("Points limit must be greater than 0.".to_string()));
+                            return;
+                        }
+                    },
+                };
+                form_error.set(None);
+                spawn_local(async move {
+                    match create_army_list(name_value, game_id_value, limit).await {
+                        Ok(list) => {
+                            name.set(String::new());
+                            points_limit.set(String::new());
+                            selected_list_id.set(Some(list.id));
+                            lists_refresh.update(|value| *value += 1);
+                        }
+                        Err(message) => form_error.set(Some(message)),
+                    }
+                });
+            }>
+                <label class="field">
+                    <span>"List Name"</span>
+                    <input
+                        type="text"
+                        prop:value=move || name.get()
+                        on:input=move |event| name.set(event_target_value(&event))
+                    />
+                </label>
+                <label class="field">
+                    <span>"Game"</span>
+                    {move || match games.get() {
+                        None => view! { <p class="state">"Loading games..."</p> }.into_view(),
+                        Some(Err(message)) => view! { <p class="state state--error">{message}</p> }.into_view(),
+                        Some(Ok(list)) => view! {
+                            <select
+                                prop:value=move || game_id.get()
+                                on:change=move |event| game_id.set(event_target_value(&event))
+                            >
+                                {list.into_iter().map(|game| view! {
+                                    <option value={game.id.clone()}>{game.name}</option>
+                                }).collect_view()}
+                            </select>
+                        }.into_view(),
+                    }}
+                </label>
+                <label class="field">
+                    <span>"Points Limit"</span>
+                    <input
+                        type="number"
+                        min="1"
+                        prop:value=move || points_limit.get()
+                        on:input=move |event| points_limit.set(event_target_value(&event))
+                    />
+                </label>
+                <button type="submit">"Create List"</button>
+                {move || form_error.get().map(|message| view! {
+                    <p class="state state--error">{message}</p>
+                })}
+            </form>
+        </section>
+        <section class="panel">
+            <h2>"Army Lists"</h2>
+            {move || match lists.get() {
+                None => view! { <p class="state">"Loading army lists..."</p> }.into_view(),
+                Some(Err(message)) => view! { <p class="state state--error">{message}</p> }.into_view(),
+                Some(Ok(list)) if list.is_empty() => {
+                    view! { <p class="state">"No army lists yet."</p> }.into_view()
+                }
+                Some(Ok(list)) => view! {
+                    <ul class="army-lists">
+                        {list.into_iter().map(|army_list| {
+                            let id = army_list.id.clone();
+                            let is_selected = {
+                                let id = id.clone();
+                                move || selected_list_id.get().as_deref() == Some(id.as_str())
+                            };
+                            view! {
+                                <li>
+                                    <button
+                                        type="button"
+                                        class="location"
+                                        class=("location--selected", is_selected)
+                                        on:click=move |_| selected_list_id.set(Some(id.clone()))
+                                    >
+                                        {army_list.name}
+                                        <span class="location__kind">{army_list.game.name}</span>
+                                        <span class="location__quantity">
+                                            {army_list.points_limit.map(|limit| format!("{limit} pts"))}
+                                        </span>
+                                    </button>
+                                </li>
+                            }
+                        }).collect_view()}
+                    </ul>
+                }.into_view(),
+            }}
+        </section>
+        {move || selected_list_id.get().map(|id| view! {
+            <ArmyListBuilder
+                id
+                on_deleted=Callback::new(move |_| {
+                    selected_list_id.set(None);
+                    lists_refresh.update(|value| *value += 1);
+                })
+            />
+        })}
+    }
+}
+
+#[component]
+fn ArmyListBuilder(id: String, on_deleted: Callback<()>) -> impl IntoView {
+    let refresh = create_rw_signal(0u32);
+    let detail = {
+        let id = id.clone();
+        create_resource(move || refresh.get(), move |_| {
+            let id = id.clone();
+            async move { fetch_army_list(id).await }
+        })
+    };
+    let validation = {
+        let id = id.clone();
+        create_resource(move || refresh.get(), move |_| {
+            let id = id.clone();
+            async move { fetch_army_list_validation(id).await }
+        })
+    };
+    let owned = create_resource(|| (), |_| async { fetch_user_models(None).await });
+
+    let selected_model_id = create_rw_signal(String::new());
+    let quantity = create_rw_signal(1_i64);
+    let error = create_rw_signal(None::<String>);
+
+    let add_entry = {
+        let id = id.clone();
+        move |event: ev::SubmitEvent| {
+            event.prevent_default();
+            let user_model_id = selected_model_id.get();
+            let quantity_value = quantity.get();
+            if user_model_id.is_empty() {
+                error.set(Some("Select a model.".to_string()));
+                return;
+            }
+            if quantity_value <= 0 {
+                error.set(Some("Quantity must be greater than 0.".to_string()));
+                return;
+            }
+            error.set(None);
+            let id = id.clone();
+            spawn_local(async move {
+                match add_army_list_entry(id, user_model_id, quantity_value).await {
+                    Ok(()) => {
+                        quantity.set(1);
+                        refresh.update(|value| *value += 1);
+                    }
+                    Err(message) => error.set(Some(message)),
+                }
+            });
+        }
+    };
+
+    let delete_list = {
+        let id = id.clone();
+        move |_| {
+            let id = id.clone();
+            spawn_local(async move {
+                match delete_army_list(id).await {
+                    Ok(()) => on_deleted.call(()),
+                    Err(message) => error.set(Some(message)),
+                }
+            });
+        }
+    };
+
+    view! {
+        <section class="panel">
+            {move || match detail.get() {
+                None => view! { <p class="state">"Loading army list..."</p> }.into_view(),
+                Some(Err(message)) => view! { <p class="state state--error">{message}</p> }.into_view(),
+                Some(Ok(list)) => {
+                    let list_id = list.id.clone();
+                    let game_name = list.game.name.clone();
+                    view! {
+                        <h2>{list.name}" — "{list.game.name}</h2>
+                        <table class="table">
+                            <thead>
+                                <tr>
+                                    <th>"Model Name"</th>
+                                    <th class="cell-right">"Quantity"</th>
+                                    <th class="cell-right">"Points"</th>
+                                    <th></th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                {list.entries.into_iter().map(|entry| {
+                                    let list_id = list_id.clone();
+                                    let entry_id = entry.id.clone();
+                                    view! {
+                                        <tr>
+                                            <td>{entry.model_name}</td>
+                                            <td class="cell-right">{entry.quantity}</td>
+                                            <td class="cell-right">
+                                                {entry.points.map(|points| points.to_string()).unwrap_or_else(|| "—".to_string())}
+                                            </td>
+                                            <td>
+                                                <button
+                                                    type="button"
+                                                    class="link-button"
+                                                    on:click=move |_| {
+                                                        let list_id = list_id.clone();
+                                                        let entry_id = entry_id.clone();
+                                                        spawn_local(async move {
+                                                            match delete_army_list_entry(list_id, entry_id).await {
+                                                                Ok(()) => refresh.update(|value| *value += 1),
+                                                                Err(message) => error.set(Some(message)),
+                                                            }
+                                                        });
+                                                    }
+                                                >
+                                                    "Remove"
+                                                </button>
+                                            </td>
+                                        </tr>
+                                    }
+                                }).collect_view()}
+                            </tbody>
+                        </table>
+                        <form class="form" on:submit=add_entry.clone()>
+                            <label class="field">
+                                <span>"Model"</span>
+                                {move || match owned.get() {
+                                    None => view! { <p class="state">"Loading models..."</p> }.into_view(),
+                                    Some(Err(message)) => view! { <p class="state state--error">{message}</p> }.into_view(),
+                                    Some(Ok(models)) => {
+                                        let game_name = game_name.clone();
+                                        view! {
+                                            <select
+                                                prop:value=move || selected_model_id.get()
+                                                on:change=move |event| selected_model_id.set(event_target_value(&event))
+                                            >
+                                                <option value="">"Select a model"</option>
+                                                {models
+                                                    .into_iter()
+                                                    .filter(|model| model.game_name == game_name)
+                                                    .map(|model| view! {
+                                                        <option value={model.id.clone()}>
+                                                            {format!(
+                                                                "{} ({} owned, {})",
+                                                                model.model_name,
+                                                                model.quantity,
+                                                                model.status.label()
+                                                            )}
+                                                        </option>
+                                                    })
+                                                    .collect_view()}
+                                            </select>
+                                        }.into_view()
+                                    }
+                                }}
+                            </label>
+                            <label class="field">
+                                <span>"Quantity"</span>
+                                <input
+                                    type="number"
+                                    min="1"
+                                    prop:value=move || quantity.get().to_string()
+                                    on:input=move |event| {
+                                        let value = event_target_value(&event).parse::<i64>().unwrap_or(1);
+                                        quantity.set(value);
+                                    }
+                                />
+                            </label>
+                            <button type="submit">"Add to List"</button>
+                        </form>
+                    }.into_view()
+                }
+            }}
+            {move || error.get().map(|message| view! {
+                <p class="state state--error">{message}</p>
+            })}
+            {move || match validation.get() {
+                None => view! { <p class="state">"Validating..."</p> }.into_view(),
+                Some(Err(message)) => view! { <p class="state state--error">{message}</p> }.into_view(),
+                Some(Ok(result)) => view! {
+                    <div class="validation" class=("validation--invalid", !result.valid)>
+                        <p class="validation__total">
+                            {match result.points_limit {
+                                Some(limit) => format!("{} / {limit} pts", result.total_points),
+                                None => format!("{} pts", result.total_points),
+                            }}
+                            {if result.valid { " — ready to play" } else { "" }}
+                        </p>
+                        <ul class="validation__issues">
+                            {result.issues.into_iter().map(|issue| view! {
+                                <li>{issue.message}</li>
+                            }).collect_view()}
+                        </ul>
+                    </div>
+                }.into_view(),
+            }}
+            <button type="button" class="link-button" on:click=delete_list>
+                "Delete list"
+            </button>
+        </section>
+    }
+}
+
+async fn fetch_army_lists() -> Result<Vec<ArmyList>, String> {
+    let response = Request::get(&format!("{API_BASE}/army-lists"))
+        .send()
+        .await
+        .map_err(|error| format!("Failed to load army lists: {error}"))?;
+
+    if !response.ok() {
+        return Err(format!(
+            "Failed to load army lists: {}",
+            response.status()
+        ));
+    }
+
+    response
+        .json::<Vec<ArmyList>>()
+        .await
+        .map_err(|error| format!("Failed to parse army lists: {error}"))
+}
+
+async fn fetch_army_list(id: String) -> Result<ArmyListDetail, String> {
+    let response = Request::get(&format!("{API_BASE}/army-lists/{id}"))
+        .send()
+        .await
+        .map_err(|error| format!("Failed to load army list: {error}"))?;
+
+    if !response.ok() {
+        return Err(format!(
+            "Failed to load army list: {}",
+            response.status()
+        ));
+    }
+
+    response
+        .json::<ArmyListDetail>()
+        .await
+        .map_err(|error| format!("Failed to parse army list: {error}"))
+}
+
+async fn fetch_army_list_validation(id: String) -> Result<ArmyListValidation, String> {
+    let response = Request::get(&format!("{API_BASE}/army-lists/{id}/validation"))
+        .send()
+        .await
+        .map_err(|error| format!("Failed to validate army list: {error}"))?;
+
+    if !response.ok() {
+        return Err(format!(
+            "Failed to validate army list: {}",
+            response.status()
+        ));
+    }
+
+    response
+        .json::<ArmyListValidation>()
+        .await
+        .map_err(|error| format!("Failed to parse army list validation: {error}"))
+}
+
+async fn create_army_list(
+    name: String,
+    game_id: String,
+    points_limit: Option<i64>,
+) -> Result<ArmyList, String> {
+    let response = Request::post(&format!("{API_BASE}/army-lists"))
+        .header("content-type", "application/json")
+        .body(
+            serde_json::to_string(&CreateArmyListRequest {
+                name,
+                game_id,
+                points_limit,
+            })
+            .map_err(|error| format!("Failed to serialize army list: {error}"))?,
+        )
+        .map_err(|error| format!("Failed to create army list: {error}"))?
+        .send()
+        .await
+        .map_err(|error| format!("Failed to create army list: {error}"))?;
+
+    if !response.ok() {
+        return Err(format!(
+            "Failed to create army list: {}",
+            response.status()
+        ));
+    }
+
+    response
+        .json::<ArmyList>()
+        .await
+        .map_err(|error| format!("Failed to parse army list: {error}"))
+}
+
+async fn delete_army_list(id: String) -> Result<(), String> {
+    let response = Request::delete(&format!("{API_BASE}/army-lists/{id}"))
+        .send()
+        .await
+        .map_err(|error| format!("Failed to delete army list: {error}"))?;
+
+    if !response.ok() {
+        return Err(format!(
+            "Failed to delete army list: {}",
+            response.status()
+        ));
+    }
+
+    Ok(())
+}
+
+async fn add_army_list_entry(
+    army_list_id: String,
+    user_model_id: String,
+    quantity: i64,
+) -> Result<(), String> {
+    let response = Request::post(&format!("{API_BASE}/army-lists/{army_list_id}/entries"))
+        .header("content-type", "application/json")
+        .body(
+            serde_json::to_string(&CreateArmyListEntryRequest {
+                user_model_id,
+                quantity,
+            })
+            .map_err(|error| format!("Failed to serialize army list entry: {error}"))?,
+        )
+        .map_err(|error| format!("Failed to add army list entry: {error}"))?
+        .send()
+        .await
+        .map_err(|error| format!("Failed to add army list entry: {error}"))?;
+
+    if !response.ok() {
+        return Err(format!(
+            "Failed to add army list entry: {}",
+            response.status()
+        ));
+    }
+
+    Ok(())
+}
+
+async fn delete_army_list_entry(army_list_id: String, entry_id: String) -> Result<(), String> {
+    let response = Request::delete(&format!(
+        "{API_BASE}/army-lists/{army_list_id}/entries/{entry_id}"
+    ))
+    .send()
+    .await
+    .map_err(|error| format!("Failed to remove army list entry: {error}"))?;
+
+    if !response.ok() {
+        return Err(format!(
+            "Failed to remove army list entry: {}",
+            response.status()
+        ));
+    }
+
+    Ok(())
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+struct ArmyList {
+    id: String,
+    name: String,
+    game: Game,
+    points_limit: Option<i64>,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+struct ArmyListDetail {
+    id: String,
+    name: String,
+    game: Game,
+    entries: Vec<ArmyListEntry>,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+struct ArmyListEntry {
+    id: String,
+    model_name: String,
+    quantity: i64,
+    points: Option<i64>,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+struct ArmyListValidation {
+    valid: bool,
+    total_points: i64,
+    points_limit: Option<i64>,
+    issues: Vec<ValidationIssue>,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+struct ValidationIssue {
+    message: String,
+}
+
+#[derive(Serialize)]
+struct CreateArmyListRequest {
+    name: String,
+    game_id: String,
+    points_limit: Option<i64>,
+}
+
+#[derive(Serialize)]
+struct CreateArmyListEntryRequest {
+    user_model_id: String,
+    quantity: i64,
+}
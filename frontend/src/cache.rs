@@ -0,0 +1,285 @@
+use rexie::{Index, KeyRange, ObjectStore, Rexie, TransactionMode, TransactionResult};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use wasm_bindgen::JsValue;
+
+use crate::{Game, ModelDefinition, UserModelListItem};
+
+const DB_NAME: &str = "mini-tracker";
+const DB_VERSION: u32 = 1;
+
+/// Index over each record's lowercased name, used for prefix search.
+const SEARCH_INDEX: &str = "search";
+
+/// Something kept in its own object store, keyed by ID and searchable by name.
+pub trait Cached: Serialize + DeserializeOwned {
+    const STORE: &'static str;
+
+    fn id(&self) -> &str;
+
+    /// The text prefix search matches against.
+    fn search_text(&self) -> &str;
+}
+
+impl Cached for Game {
+    const STORE: &'static str = "games";
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn search_text(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Cached for ModelDefinition {
+    const STORE: &'static str = "model_definitions";
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn search_text(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Cached for UserModelListItem {
+    const STORE: &'static str = "user_models";
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn search_text(&self) -> &str {
+        &self.model_name
+    }
+}
+
+const STORES: [&str; 3] = [
+    Game::STORE,
+    ModelDefinition::STORE,
+    UserModelListItem::STORE,
+];
+
+#[derive(Serialize)]
+struct NewRecord<'a, T> {
+    id: &'a str,
+    search: String,
+    value: &'a T,
+}
+
+#[derive(Deserialize)]
+struct Record<T> {
+    value: T,
+}
+
+/// A local IndexedDB copy of what the API returned, so lookups such as the
+/// model-name typeahead don't have to wait for the network.
+pub struct Cache {
+    db: Rexie,
+}
+
+impl Cache {
+    pub async fn init() -> Result<Self, String> {
+        Self::open(DB_NAME).await
+    }
+
+    async fn open(name: &str) -> Result<Self, String> {
+        let mut builder = Rexie::builder(name).version(DB_VERSION);
+        for store in STORES {
+            builder = builder.add_object_store(
+                ObjectStore::new(store)
+                    .key_path("id")
+                    .add_index(Index::new(SEARCH_INDEX, "search")),
+            );
+        }
+
+        let db = builder
+            .build()
+            .await
+            .map_err(|error| format!("Failed to open cache: {error}"))?;
+        Ok(Self { db })
+    }
+
+    /// Adds or overwrites records by ID in a single transaction, so loading the
+    /// same data twice never leaves duplicates.
+    pub async fn bulk_insert<T: Cached>(&self, items: &[T]) -> Result<(), String> {
+        self.write(items, false).await
+    }
+
+    /// Makes the store hold exactly `items`, dropping anything deleted since
+    /// the last load.
+    pub async fn replace_all<T: Cached>(&self, items: &[T]) -> Result<(), String> {
+        self.write(items, true).await
+    }
+
+    /// Up to `limit` records whose name starts with `prefix`, ignoring case,
+    /// in name order.
+    pub async fn search_by_prefix<T: Cached>(
+        &self,
+        prefix: &str,
+        limit: u32,
+    ) -> Result<Vec<T>, String> {
+        let prefix = normalize(prefix);
+        if prefix.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let upper = format!("{prefix}\u{ffff}");
+        let range = KeyRange::bound(
+            &JsValue::from_str(&prefix),
+            &JsValue::from_str(&upper),
+            Some(false),
+            Some(false),
+        )
+        .map_err(|error| format!("Failed to search cache: {error}"))?;
+        let transaction = self
+            .db
+            .transaction(&[T::STORE], TransactionMode::ReadOnly)
+            .map_err(|error| format!("Failed to search cache: {error}"))?;
+        let values = transaction
+            .store(T::STORE)
+            .and_then(|store| store.index(SEARCH_INDEX))
+            .map_err(|error| format!("Failed to search cache: {error}"))?
+            .get_all(Some(range), Some(limit))
+            .await
+            .map_err(|error| format!("Failed to search cache: {error}"))?;
+
+        values
+            .into_iter()
+            .map(|value| {
+                serde_wasm_bindgen::from_value::<Record<T>>(value)
+                    .map(|record| record.value)
+                    .map_err(|error| format!("Failed to read cache: {error}"))
+            })
+            .collect()
+    }
+
+    async fn write<T: Cached>(&self, items: &[T], clear: bool) -> Result<(), String> {
+        let transaction = self
+            .db
+            .transaction(&[T::STORE], TransactionMode::ReadWrite)
+            .map_err(|error| format!("Failed to update cache: {error}"))?;
+        let store = transaction
+            .store(T::STORE)
+            .map_err(|error| format!("Failed to update cache: {error}"))?;
+
+        if clear {
+            store
+                .clear()
+                .await
+                .map_err(|error| format!("Failed to update cache: {error}"))?;
+        }
+        for item in items {
+            let record = NewRecord {
+                id: item.id(),
+                search: normalize(item.search_text()),
+                value: item,
+            };
+            let value = serde_wasm_bindgen::to_value(&record)
+                .map_err(|error| format!("Failed to update cache: {error}"))?;
+            store
+                .put(&value, None)
+                .await
+                .map_err(|error| format!("Failed to update cache: {error}"))?;
+        }
+
+        match transaction
+            .done()
+            .await
+            .map_err(|error| format!("Failed to update cache: {error}"))?
+        {
+            TransactionResult::Committed => Ok(()),
+            TransactionResult::Aborted => Err("Failed to update cache: aborted".to_string()),
+        }
+    }
+}
+
+fn normalize(text: &str) -> String {
+    text.trim().to_lowercase()
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+    use wasm_bindgen_test::*;
+
+    use super::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    const NAMES: [&str; 5] = [
+        "Intercessors",
+        "Hellblasters",
+        "Terminators",
+        "Aggressors",
+        "Inceptors",
+    ];
+
+    fn definitions(count: usize) -> Vec<ModelDefinition> {
+        (0..count)
+            .map(|index| ModelDefinition {
+                id: format!("definition-{index}"),
+                name: format!("{} {index}", NAMES[index % NAMES.len()]),
+                game: Game {
+                    id: "game".to_string(),
+                    name: "Warhammer 40k".to_string(),
+                },
+            })
+            .collect()
+    }
+
+    #[wasm_bindgen_test]
+    async fn reloading_does_not_duplicate_records() {
+        let cache = Cache::open("mini-tracker-test-reload")
+            .await
+            .expect("cache to open");
+        let all = definitions(10);
+
+        cache.replace_all(&all).await.expect("records to insert");
+        cache.bulk_insert(&all[..5]).await.expect("records to insert");
+        let found = cache
+            .search_by_prefix::<ModelDefinition>("INTER", 100)
+            .await
+            .expect("search to succeed");
+        assert_eq!(found.len(), 2);
+
+        cache
+            .replace_all(&all[1..])
+            .await
+            .expect("records to insert");
+        let found = cache
+            .search_by_prefix::<ModelDefinition>("intercessors", 100)
+            .await
+            .expect("search to succeed");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].name, "Intercessors 5");
+    }
+
+    #[wasm_bindgen_test]
+    async fn prefix_search_over_5000_entries_takes_under_20_ms() {
+        let cache = Cache::open("mini-tracker-test-bench")
+            .await
+            .expect("cache to open");
+        cache
+            .replace_all(&definitions(5000))
+            .await
+            .expect("records to insert");
+
+        let performance = web_sys::window()
+            .and_then(|window| window.performance())
+            .expect("performance API");
+        let started = performance.now();
+        let found = cache
+            .search_by_prefix::<ModelDefinition>("Inter", 20)
+            .await
+            .expect("search to succeed");
+        let elapsed = performance.now() - started;
+
+        assert_eq!(found.len(), 20);
+        assert!(found
+            .iter()
+            .all(|definition| definition.name.starts_with("Intercessors")));
+        assert!(elapsed < 20.0, "prefix search took {elapsed:.1} ms");
+    }
+}
@@ -0,0 +1,82 @@
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+use leptos::*;
+use serde::Deserialize;
+use wasm_bindgen::{closure::Closure, JsCast};
+use web_sys::{EventSource, MessageEvent};
+
+use crate::{UserModelListItem, API_BASE};
+
+/// How long to wait before reopening a stream the browser has given up on.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// A change pushed by the backend's `/events` stream.
+#[derive(Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChangeEvent {
+    UserModelUpserted {
+        user_model: UserModelListItem,
+    },
+    UserModelRemoved {
+        id: String,
+    },
+    Changed {
+        entity: String,
+        action: String,
+    },
+    /// Events were missed; everything shown should be reloaded.
+    Resync,
+}
+
+/// Listens for change events for as long as the page is open. The browser
+/// resumes a dropped stream on its own; if it gives up, the stream is reopened
+/// from the last event seen.
+pub fn subscribe(on_event: Callback<ChangeEvent>) {
+    connect(on_event, Rc::default());
+}
+
+fn connect(on_event: Callback<ChangeEvent>, last_id: Rc<RefCell<Option<String>>>) {
+    let url = match last_id.borrow().as_deref() {
+        Some(id) => format!("{API_BASE}/events?last_event_id={id}"),
+        None => format!("{API_BASE}/events"),
+    };
+    let source = match EventSource::new(&url) {
+        Ok(source) => source,
+        Err(error) => {
+            logging::warn!("failed to open event stream: {error:?}");
+            return;
+        }
+    };
+
+    let on_message = {
+        let last_id = last_id.clone();
+        Closure::<dyn Fn(MessageEvent)>::new(move |event: MessageEvent| {
+            let id = event.last_event_id();
+            if !id.is_empty() {
+                *last_id.borrow_mut() = Some(id);
+            }
+            let Some(data) = event.data().as_string() else {
+                return;
+            };
+            match serde_json::from_str::<ChangeEvent>(&data) {
+                Ok(change) => on_event.call(change),
+                Err(error) => logging::warn!("ignoring malformed change event: {error}"),
+            }
+        })
+    };
+    source.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+    on_message.forget();
+
+    let on_error = {
+        let source = source.clone();
+        Closure::<dyn Fn()>::new(move || {
+            if source.ready_state() != EventSource::CLOSED {
+                return;
+            }
+            let last_id = last_id.clone();
+            set_timeout(move || connect(on_event, last_id), RECONNECT_DELAY);
+        })
+    };
+    source.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+    on_error.forget();
+}
@@ -2,7 +2,30 @@ use gloo_net::http::Request;
 use leptos::*;
 use serde::{Deserialize, Serialize};
 
-const API_BASE: &str = "/api";
+mod army_lists;
+mod cache;
+mod events;
+mod presence;
+mod projects;
+mod sessions;
+mod sync;
+mod undo;
+
+use std::rc::Rc;
+
+use army_lists::ArmyListsPage;
+use cache::Cache;
+use events::ChangeEvent;
+use presence::{Avatar, Presence, PresenceBar};
+use projects::ProjectsPage;
+use sessions::SessionStopwatch;
+use sync::{PendingChange, SyncError, SyncQueue};
+use undo::{Change, Direction, Entry, History, UndoToast};
+
+const API_BASE: &str = "/api/v1";
+
+/// How many names the model-name typeahead offers at once.
+const NAME_SUGGESTION_LIMIT: u32 = 8;
 
 fn main() {
     mount_to_body(|| view! { <App /> });
@@ -10,22 +33,105 @@ fn main() {
 
 #[component]
 fn App() -> impl IntoView {
+    let page = create_rw_signal(Page::Collection);
     let games = create_resource(|| (), |_| async { fetch_games().await });
     let definitions_refresh = create_rw_signal(0u32);
     let model_definitions = create_resource(move || definitions_refresh.get(), |_| async {
         fetch_model_definitions().await
     });
     let models_refresh = create_rw_signal(0u32);
+    let location_filter = create_rw_signal(None::<String>);
+    let tree_refresh = create_rw_signal(0u32);
+    let location_tree = create_resource(
+        move || (models_refresh.get(), tree_refresh.get()),
+        |_| async { fetch_location_tree().await },
+    );
     let models = create_rw_signal(Vec::<UserModelListItem>::new());
     let models_loading = create_rw_signal(true);
     let models_error = create_rw_signal(None::<String>);
+    let conflict = create_rw_signal(None::<String>);
 
     let model_name = create_rw_signal(String::new());
+    let name_suggestions = create_rw_signal(Vec::<String>::new());
+    let cache = create_rw_signal(None::<Rc<Cache>>);
+    spawn_local(async move {
+        match Cache::init().await {
+            Ok(opened) => cache.set(Some(Rc::new(opened))),
+            Err(message) => logging::warn!("{message}"),
+        }
+    });
     let selected_game_id = create_rw_signal(String::new());
     let quantity = create_rw_signal(1_i32);
     let status = create_rw_signal(Status::Unassembled);
+    let points = create_rw_signal(String::new());
+    let unit_size = create_rw_signal(String::new());
     let form_error = create_rw_signal(None::<String>);
     let submitting = create_rw_signal(false);
+    let presence = Presence::connect();
+    let sync_queue = create_rw_signal(SyncQueue::load());
+    let syncing = store_value(false);
+    let history = create_rw_signal(History::default());
+    let toast = create_rw_signal(None::<String>);
+
+    let record = move |entry: Entry| {
+        toast.set(Some(entry.label.clone()));
+        history.update(|history| history.push(entry));
+    };
+
+    let on_step = Callback::new(move |direction: Direction| {
+        let Some(entry) = history.try_update(|history| history.start(direction)).flatten() else {
+            return;
+        };
+        spawn_local(async move {
+            let change = entry.change(direction).clone();
+            let result = undo::apply(&change).await;
+            history.update(|history| history.finish(result.is_ok()));
+            if let Err(message) = result {
+                toast.set(Some(message));
+                return;
+            }
+
+            match change {
+                Change::SetStatus { id, status } => models.update(|list| {
+                    if let Some(target) = list.iter_mut().find(|item| item.id == id) {
+                        target.status = status;
+                    }
+                }),
+                Change::SetQuantity { id, quantity } => models.update(|list| {
+                    if let Some(target) = list.iter_mut().find(|item| item.id == id) {
+                        target.quantity = quantity;
+                    }
+                }),
+                Change::Trash { .. } | Change::Restore { .. } => {
+                    models_refresh.update(|value| *value += 1);
+                }
+            }
+            let verb = match direction {
+                Direction::Undo => "Undid",
+                Direction::Redo => "Redid",
+            };
+            toast.set(Some(format!("{verb}: {}", entry.label)));
+        });
+    });
+
+    // Ctrl+Z / Ctrl+Shift+Z (Cmd on macOS), except while typing in a text field
+    // where the browser's own undo should win.
+    let shortcuts = window_event_listener(ev::keydown, move |event| {
+        if !(event.ctrl_key() || event.meta_key()) || !event.key().eq_ignore_ascii_case("z") {
+            return;
+        }
+        let target = event_target::<web_sys::Element>(&event);
+        if matches!(target.tag_name().as_str(), "INPUT" | "TEXTAREA") {
+            return;
+        }
+        event.prevent_default();
+        on_step.call(if event.shift_key() {
+            Direction::Redo
+        } else {
+            Direction::Undo
+        });
+    });
+    on_cleanup(move || shortcuts.remove());
 
     let load_models = {
         let models = models.clone();
@@ -37,9 +143,28 @@ fn App() -> impl IntoView {
             let models = models.clone();
             let models_loading = models_loading.clone();
             let models_error = models_error.clone();
+            let location_id = location_filter.get_untracked();
             spawn_local(async move {
-                match fetch_user_models().await {
-                    Ok(list) => {
+                let unfiltered = location_id.is_none();
+                match fetch_user_models(location_id).await {
+                    Ok(mut list) => {
+                        if let Some(cache) = cache.get_untracked() {
+                            let list = list.clone();
+                            spawn_local(async move {
+                                // A filtered list is only part of the collection.
+                                let result = if unfiltered {
+                                    cache.replace_all(&list).await
+                                } else {
+                                    cache.bulk_insert(&list).await
+                                };
+                                if let Err(message) = result {
+                                    logging::warn!("{message}");
+                                }
+                            });
+                        }
+                        if unfiltered {
+                            sync_queue.with_untracked(|queue| queue.apply_to(&mut list));
+                        }
                         models.set(list);
                         models_loading.set(false);
                     }
@@ -54,9 +179,206 @@ fn App() -> impl IntoView {
 
     create_effect(move |_| {
         models_refresh.get();
+        location_filter.get();
         load_models();
     });
 
+    // Changes made elsewhere (another tab, another device) arrive as events.
+    // Lots are patched in place; anything else that affects the list reloads it.
+    events::subscribe(Callback::new(move |event: ChangeEvent| match event {
+        ChangeEvent::UserModelUpserted { user_model } => {
+            let known =
+                models.with_untracked(|list| list.iter().any(|item| item.id == user_model.id));
+            if known {
+                models.update(|list| {
+                    if let Some(target) = list.iter_mut().find(|item| item.id == user_model.id) {
+                        *target = user_model;
+                    }
+                    // Edits that haven't synced yet still win locally.
+                    sync_queue.with_untracked(|queue| queue.apply_to(list));
+                });
+                tree_refresh.update(|value| *value += 1);
+            } else if location_filter.get_untracked().is_none() {
+                models.update(|list| list.push(user_model));
+                tree_refresh.update(|value| *value += 1);
+            } else {
+                // Only the backend knows whether it belongs under the selected location.
+                models_refresh.update(|value| *value += 1);
+            }
+        }
+        ChangeEvent::UserModelRemoved { id } => {
+            models.update(|list| list.retain(|item| item.id != id));
+            tree_refresh.update(|value| *value += 1);
+        }
+        ChangeEvent::Changed { entity, action } => match (entity.as_str(), action.as_str()) {
+            ("model_definition", "create" | "update") => {
+                definitions_refresh.update(|value| *value += 1);
+            }
+            ("game" | "model_definition", _) | ("tag", "update" | "delete") | ("location", _) => {
+                definitions_refresh.update(|value| *value += 1);
+                models_refresh.update(|value| *value += 1);
+            }
+            _ => {}
+        },
+        ChangeEvent::Resync => {
+            definitions_refresh.update(|value| *value += 1);
+            models_refresh.update(|value| *value += 1);
+        }
+    }));
+
+    // Sends queued offline changes one at a time, oldest first. A network
+    // failure backs off; a change the API refuses is dropped and reported.
+    let flush = move || {
+        if syncing.get_value() || !sync::is_online() {
+            return;
+        }
+        syncing.set_value(true);
+        spawn_local(async move {
+            while let Some(change) =
+                sync_queue.with_untracked(|queue| queue.next_due(js_sys::Date::now()))
+            {
+                match sync::push(&change).await {
+                    Ok(saved) => {
+                        sync_queue.update(|queue| queue.succeeded(&change, &saved.id));
+                        models.update(|list| {
+                            if let PendingChange::Create { local_id, .. } = &change {
+                                // The change event for the new lot may have beaten us here.
+                                if list.iter().any(|item| item.id == saved.id) {
+                                    list.retain(|item| &item.id != local_id);
+                                } else if let Some(target) =
+                                    list.iter_mut().find(|item| &item.id == local_id)
+                                {
+                                    target.id = saved.id.clone();
+                                }
+                            }
+                            if let Some(target) = list.iter_mut().find(|item| item.id == saved.id) {
+                                target.status = saved.status;
+                                target.version = saved.version;
+                                target.is_dirty = false;
+                                target.last_modified = None;
+                            }
+                            sync_queue.with_untracked(|queue| queue.apply_to(list));
+                        });
+                        tree_refresh.update(|value| *value += 1);
+                    }
+                    Err(SyncError::Unavailable) => {
+                        sync_queue.update(|queue| queue.failed(js_sys::Date::now()));
+                        break;
+                    }
+                    Err(SyncError::Rejected(message)) => {
+                        sync_queue.update(SyncQueue::rejected);
+                        models_error.set(Some(message));
+                        models_refresh.update(|value| *value += 1);
+                    }
+                }
+                sync_queue.with_untracked(SyncQueue::save);
+            }
+            sync_queue.with_untracked(SyncQueue::save);
+            syncing.set_value(false);
+        });
+    };
+    flush();
+    if let Ok(handle) = set_interval_with_handle(flush, sync::SYNC_INTERVAL) {
+        on_cleanup(move || handle.clear());
+    }
+    let reconnected = window_event_listener(ev::online, move |_| {
+        sync_queue.update(SyncQueue::reset_backoff);
+        flush();
+    });
+    on_cleanup(move || reconnected.remove());
+    let sync_now = move |_| {
+        sync_queue.update(SyncQueue::reset_backoff);
+        flush();
+    };
+
+    // Keep the local cache in step with what the API last returned.
+    create_effect(move |_| {
+        let (Some(cache), Some(Ok(list))) = (cache.get(), games.get()) else {
+            return;
+        };
+        spawn_local(async move {
+            if let Err(message) = cache.replace_all(&list).await {
+                logging::warn!("{message}");
+            }
+        });
+    });
+    create_effect(move |_| {
+        let (Some(cache), Some(Ok(list))) = (cache.get(), model_definitions.get()) else {
+            return;
+        };
+        spawn_local(async move {
+            if let Err(message) = cache.replace_all(&list).await {
+                logging::warn!("{message}");
+            }
+        });
+    });
+
+    let saved = move |user_model: UserModel| {
+        models.update(|list| {
+            if let Some(target) = list.iter_mut().find(|item| item.id == user_model.id) {
+                target.version = user_model.version;
+            }
+        });
+    };
+
+    // Someone else saved the lot first. Show it as it is now, and say so,
+    // rather than quietly dropping the edit.
+    let resolve_conflict = move |id: String| {
+        spawn_local(async move {
+            match fetch_user_model(&id).await {
+                Ok(current) => {
+                    let mut name = String::new();
+                    models.update(|list| {
+                        if let Some(target) = list.iter_mut().find(|item| item.id == id) {
+                            target.status = current.status;
+                            target.quantity = current.quantity;
+                            target.notes = current.notes.clone();
+                            target.version = current.version;
+                            name = target.model_name.clone();
+                        }
+                    });
+                    conflict.set(Some(format!(
+                        "{name} was changed somewhere else, so your edit was not saved. It is now {} with quantity {}.",
+                        current.status.label(),
+                        current.quantity
+                    )));
+                }
+                Err(message) => {
+                    models_error.set(Some(message));
+                    models_refresh.update(|value| *value += 1);
+                }
+            }
+        });
+    };
+
+    let suggest_names = move |query: String| {
+        let Some(cache) = cache.get_untracked() else {
+            return;
+        };
+        spawn_local(async move {
+            let definitions = cache
+                .search_by_prefix::<ModelDefinition>(&query, NAME_SUGGESTION_LIMIT)
+                .await;
+            let owned = cache
+                .search_by_prefix::<UserModelListItem>(&query, NAME_SUGGESTION_LIMIT)
+                .await;
+            // Typing may have moved on while the cache was searched.
+            if model_name.get_untracked() != query {
+                return;
+            }
+            let mut names = definitions
+                .unwrap_or_default()
+                .into_iter()
+                .map(|definition| definition.name)
+                .chain(owned.unwrap_or_default().into_iter().map(|model| model.model_name))
+                .collect::<Vec<_>>();
+            names.sort_unstable();
+            names.dedup();
+            names.truncate(NAME_SUGGESTION_LIMIT as usize);
+            name_suggestions.set(names);
+        });
+    };
+
     create_effect(move |_| {
         if selected_game_id.get().is_empty() {
             if let Some(Ok(list)) = games.get() {
@@ -71,7 +393,37 @@ fn App() -> impl IntoView {
         <main class="page">
             <header class="page__header">
                 <h1>"Mini Tracker"</h1>
+                <PresenceBar presence />
+                {move || {
+                    let waiting = sync_queue.with(SyncQueue::len);
+                    (waiting > 0).then(|| view! {
+                        <div class="sync-status" role="status">
+                            {if waiting == 1 {
+                                "1 change waiting to sync".to_string()
+                            } else {
+                                format!("{waiting} changes waiting to sync")
+                            }}
+                            <button type="button" class="link-button" on:click=sync_now>
+                                "Sync now"
+                            </button>
+                        </div>
+                    })
+                }}
+                <SessionStopwatch />
+                <nav class="tabs">
+                    {Page::all().into_iter().map(|option| view! {
+                        <button
+                            type="button"
+                            class="tab"
+                            class=("tab--active", move || page.get() == option)
+                            on:click=move |_| page.set(option)
+                        >
+                            {option.label()}
+                        </button>
+                    }).collect_view()}
+                </nav>
             </header>
+            <div class="page__view" class=("page__view--hidden", move || page.get() != Page::Collection)>
             <section class="panel">
                 <h2>"Add Model"</h2>
                 <form class="form" on:submit=move |event| {
@@ -97,6 +449,20 @@ fn App() -> impl IntoView {
                         form_error.set(Some("Quantity must be greater than 0.".to_string()));
                         return;
                     }
+                    let points_value = match parse_optional_number(&points.get()) {
+                        Ok(value) => value,
+                        Err(()) => {
+                            form_error.set(Some("Points must be a whole number.".to_string()));
+                            return;
+                        }
+                    };
+                    let unit_size_value = match parse_optional_number(&unit_size.get()) {
+                        Ok(value) => value,
+                        Err(()) => {
+                            form_error.set(Some("Unit size must be a whole number.".to_string()));
+                            return;
+                        }
+                    };
                     let definitions = match model_definitions {
                         Some(Ok(list)) => list,
                         Some(Err(message)) => {
@@ -115,10 +481,50 @@ fn App() -> impl IntoView {
                             .iter()
                             .find(|definition| definition.name == name && definition.game.id == game_id)
                             .cloned();
+                        if !sync::is_online() {
+                            let Some(definition) = existing else {
+                                form_error.set(Some("Adding a new model type needs a connection.".to_string()));
+                                submitting.set(false);
+                                return;
+                            };
+                            let now = js_sys::Date::now();
+                            let local_id = sync::new_local_id();
+                            sync_queue.update(|queue| {
+                                queue.enqueue(
+                                    PendingChange::Create {
+                                        local_id: local_id.clone(),
+                                        model_definition_id: definition.id.clone(),
+                                        model_name: definition.name.clone(),
+                                        game_name: definition.game.name.clone(),
+                                        quantity: quantity_value,
+                                        status: status_value,
+                                    },
+                                    now,
+                                );
+                                queue.save();
+                            });
+                            models.update(|list| {
+                                sync_queue.with_untracked(|queue| queue.apply_to(list));
+                            });
+                            model_name.set(String::new());
+                            quantity.set(1);
+                            status.set(Status::Unassembled);
+                            points.set(String::new());
+                            unit_size.set(String::new());
+                            submitting.set(false);
+                            return;
+                        }
                         let definition = match existing {
                             Some(definition) => definition,
                             None => {
-                                match create_model_definition(name.clone(), game_id.clone()).await {
+                                match create_model_definition(
+                                    name.clone(),
+                                    game_id.clone(),
+                                    points_value,
+                                    unit_size_value,
+                                )
+                                .await
+                                {
                                     Ok(definition) => {
                                         definitions_refresh.update(|value| *value += 1);
                                         definition
@@ -131,21 +537,26 @@ fn App() -> impl IntoView {
                                 }
                             }
                         };
-                        if let Err(message) = create_user_model(
+                        match create_user_model(
                             definition.id.clone(),
                             quantity_value,
                             status_value,
                         )
                         .await
                         {
-                            form_error.set(Some(message));
-                            submitting.set(false);
-                            return;
+                            Ok(created) => record(Entry::created(created.id, &name)),
+                            Err(message) => {
+                                form_error.set(Some(message));
+                                submitting.set(false);
+                                return;
+                            }
                         }
                         models_refresh.update(|value| *value += 1);
                         model_name.set(String::new());
                         quantity.set(1);
                         status.set(Status::Unassembled);
+                        points.set(String::new());
+                        unit_size.set(String::new());
                         submitting.set(false);
                     });
                 }>
@@ -153,11 +564,20 @@ fn App() -> impl IntoView {
                         <span>"Model Name"</span>
                         <input
                             type="text"
+                            list="model-name-suggestions"
+                            autocomplete="off"
                             prop:value=move || model_name.get()
                             on:input=move |event| {
-                                model_name.set(event_target_value(&event));
+                                let value = event_target_value(&event);
+                                model_name.set(value.clone());
+                                suggest_names(value);
                             }
                         />
+                        <datalist id="model-name-suggestions">
+                            {move || name_suggestions.get().into_iter().map(|name| view! {
+                                <option value=name />
+                            }).collect_view()}
+                        </datalist>
                     </label>
                     <label class="field">
                         <span>"Game"</span>
@@ -205,6 +625,26 @@ fn App() -> impl IntoView {
                             }).collect_view()}
                         </select>
                     </label>
+                    <label class="field">
+                        <span>"Points"</span>
+                        <input
+                            type="number"
+                            min="0"
+                            placeholder="Optional"
+                            prop:value=move || points.get()
+                            on:input=move |event| points.set(event_target_value(&event))
+                        />
+                    </label>
+                    <label class="field">
+                        <span>"Unit Size"</span>
+                        <input
+                            type="number"
+                            min="1"
+                            placeholder="Optional"
+                            prop:value=move || unit_size.get()
+                            on:input=move |event| unit_size.set(event_target_value(&event))
+                        />
+                    </label>
                     <button type="submit" disabled=move || submitting.get()>
                         {move || if submitting.get() { "Saving..." } else { "Add Model" }}
                     </button>
@@ -215,35 +655,170 @@ fn App() -> impl IntoView {
             </section>
             <section class="panel">
                 <h2>"Models"</h2>
+                {move || conflict.get().map(|message| view! {
+                    <p class="state state--conflict" role="alert">
+                        {message}
+                        " "
+                        <button type="button" class="link-button" on:click=move |_| conflict.set(None)>
+                            "Dismiss"
+                        </button>
+                    </p>
+                })}
                 {move || {
                     if models_loading.get() {
                         view! { <p class="state">"Loading models..."</p> }.into_view()
                     } else if let Some(message) = models_error.get() {
                         view! { <p class="state state--error">{message}</p> }.into_view()
                     } else {
-                        let on_status_change = {
-                            let models = models.clone();
-                            let models_error = models_error.clone();
-                            Callback::new(move |(id, next_status): (String, Status)| {
-                                let previous = models.get();
-                                let mut updated = previous.clone();
-                                if let Some(target) = updated.iter_mut().find(|item| item.id == id) {
-                                    target.status = next_status;
-                                    models.set(updated);
-                                    let models = models.clone();
-                                    let models_error = models_error.clone();
-                                    spawn_local(async move {
-                                        if let Err(message) =
-                                            update_user_model_status(id.clone(), next_status).await
-                                        {
+                        let on_status_change = Callback::new(move |(id, next_status): (String, Status)| {
+                            // Offline, or behind edits that haven't synced yet: queue it.
+                            if !sync::is_online() || sync_queue.with_untracked(|queue| queue.has_pending(&id)) {
+                                sync_queue.update(|queue| {
+                                    queue.enqueue(
+                                        PendingChange::SetStatus { id: id.clone(), status: next_status },
+                                        js_sys::Date::now(),
+                                    );
+                                    queue.save();
+                                });
+                                models.update(|list| {
+                                    sync_queue.with_untracked(|queue| queue.apply_to(list));
+                                });
+                                flush();
+                                return;
+                            }
+                            let previous = models.get();
+                            let mut updated = previous.clone();
+                            if let Some(target) = updated.iter_mut().find(|item| item.id == id) {
+                                let entry = Entry::status(
+                                    id.clone(),
+                                    &target.model_name,
+                                    target.status,
+                                    next_status,
+                                );
+                                let version = target.version;
+                                target.status = next_status;
+                                models.set(updated);
+                                spawn_local(async move {
+                                    match update_user_model_status(id.clone(), version, next_status).await {
+                                        Ok(user_model) => {
+                                            saved(user_model);
+                                            record(entry);
+                                        }
+                                        Err(UpdateError::Stale) => resolve_conflict(id),
+                                        Err(UpdateError::Failed(message)) => {
+                                            models.set(previous);
+                                            models_error.set(Some(message));
+                                        }
+                                    }
+                                });
+                            }
+                        });
+                        let on_quantity_change = Callback::new(move |(id, next_quantity): (String, i32)| {
+                            let previous = models.get();
+                            let mut updated = previous.clone();
+                            if let Some(target) = updated.iter_mut().find(|item| item.id == id) {
+                                if target.quantity == next_quantity {
+                                    return;
+                                }
+                                let entry = Entry::quantity(
+                                    id.clone(),
+                                    &target.model_name,
+                                    target.quantity,
+                                    next_quantity,
+                                );
+                                let version = target.version;
+                                target.quantity = next_quantity;
+                                models.set(updated);
+                                spawn_local(async move {
+                                    match update_user_model_quantity(id.clone(), version, next_quantity).await {
+                                        Ok(user_model) => {
+                                            saved(user_model);
+                                            record(entry);
+                                        }
+                                        Err(UpdateError::Stale) => resolve_conflict(id),
+                                        Err(UpdateError::Failed(message)) => {
                                             models.set(previous);
                                             models_error.set(Some(message));
                                         }
-                                    });
+                                    }
+                                });
+                            }
+                        });
+                        let on_delete = Callback::new(move |id: String| {
+                            let Some(model_name) = models.with(|list| {
+                                list.iter()
+                                    .find(|item| item.id == id)
+                                    .map(|item| item.model_name.clone())
+                            }) else {
+                                return;
+                            };
+                            spawn_local(async move {
+                                match undo::delete_user_model(&id).await {
+                                    Ok(()) => {
+                                        record(Entry::deleted(id, &model_name));
+                                        models_refresh.update(|value| *value += 1);
+                                    }
+                                    Err(message) => models_error.set(Some(message)),
                                 }
-                            })
-                        };
-                        view! { <ModelsTable models=models.read_only() on_status_change /> }.into_view()
+                            });
+                        });
+                        let on_notes_change = Callback::new(move |(id, notes): (String, String)| {
+                            let Some(version) = models.with_untracked(|list| {
+                                list.iter().find(|item| item.id == id).map(|item| item.version)
+                            }) else {
+                                return;
+                            };
+                            spawn_local(async move {
+                                match update_user_model_notes(id.clone(), version, notes).await {
+                                    Ok(updated) => models.update(|list| {
+                                        if let Some(target) = list.iter_mut().find(|item| item.id == id) {
+                                            target.notes = updated.notes;
+                                            target.version = updated.version;
+                                        }
+                                    }),
+                                    Err(UpdateError::Stale) => resolve_conflict(id),
+                                    Err(UpdateError::Failed(message)) => models_error.set(Some(message)),
+                                }
+                            });
+                        });
+                        let on_tags_change = Callback::new(move |(id, names): (String, Vec<String>)| {
+                            spawn_local(async move {
+                                match assign_user_model_tags(id.clone(), names).await {
+                                    Ok(tags) => models.update(|list| {
+                                        if let Some(target) = list.iter_mut().find(|item| item.id == id) {
+                                            target.tags = tags;
+                                        }
+                                    }),
+                                    Err(message) => models_error.set(Some(message)),
+                                }
+                            });
+                        });
+                        view! {
+                            <ModelsTable
+                                models=models.read_only()
+                                presence
+                                on_status_change
+                                on_quantity_change
+                                on_delete
+                                on_notes_change
+                                on_tags_change
+                            />
+                        }.into_view()
+                    }
+                }}
+            </section>
+            <section class="panel panel--compact">
+                <h2>"Locations"</h2>
+                {move || match location_tree.get() {
+                    None => view! { <p class="state">"Loading locations..."</p> }.into_view(),
+                    Some(Err(message)) => view! { <p class="state state--error">{message}</p> }.into_view(),
+                    Some(Ok(tree)) => {
+                        let on_select = Callback::new(move |id: Option<String>| {
+                            location_filter.set(id);
+                        });
+                        view! {
+                            <LocationTreeView tree selected=location_filter.read_only() on_select />
+                        }.into_view()
                     }
                 }}
             </section>
@@ -261,6 +836,10 @@ fn App() -> impl IntoView {
                     }.into_view(),
                 }}
             </section>
+            </div>
+            {move || (page.get() == Page::ArmyLists).then(|| view! { <ArmyListsPage games /> })}
+            {move || (page.get() == Page::Projects).then(|| view! { <ProjectsPage /> })}
+            <UndoToast history message=toast on_step />
         </main>
     }
 }
@@ -268,8 +847,14 @@ fn App() -> impl IntoView {
 #[component]
 fn ModelsTable(
     models: ReadSignal<Vec<UserModelListItem>>,
+    presence: Presence,
     on_status_change: Callback<(String, Status)>,
+    on_quantity_change: Callback<(String, i32)>,
+    on_delete: Callback<String>,
+    on_notes_change: Callback<(String, String)>,
+    on_tags_change: Callback<(String, Vec<String>)>,
 ) -> impl IntoView {
+    let expanded = create_rw_signal(None::<String>);
     view! {
         <table class="table">
             <thead>
@@ -278,6 +863,9 @@ fn ModelsTable(
                     <th>"Game"</th>
                     <th class="cell-right">"Quantity"</th>
                     <th>"Status"</th>
+                    <th>"Tags"</th>
+                    <th>"Notes"</th>
+                    <th></th>
                 </tr>
             </thead>
             <tbody>
@@ -287,11 +875,53 @@ fn ModelsTable(
                         .into_iter()
                         .map(|model| {
                             let id = model.id.clone();
+                            let quantity_id = model.id.clone();
+                            let delete_id = model.id.clone();
+                            let notes_id = model.id.clone();
+                            let detail_id = model.id.clone();
+                            let focus_id = model.id.clone();
+                            let avatars_id = model.id.clone();
+                            // Lots created offline don't exist on the server yet.
+                            let is_local = sync::is_local_id(&model.id);
+                            let is_expanded = {
+                                let id = model.id.clone();
+                                move || expanded.get().as_deref() == Some(id.as_str())
+                            };
+                            let tag_names = model
+                                .tags
+                                .iter()
+                                .map(|tag| tag.name.clone())
+                                .collect::<Vec<_>>();
                             view! {
-                                <tr>
-                                    <td>{model.model_name}</td>
+                                <tr on:focusin=move |_| presence.focus(Some(focus_id.clone()))>
+                                    <td>
+                                        {model.model_name}
+                                        {model.is_dirty.then(|| view! {
+                                            <span class="chip chip--pending" title=model.last_modified.map(pending_since).unwrap_or_default()>
+                                                "Pending"
+                                            </span>
+                                        })}
+                                        <span class="avatars avatars--inline">
+                                            {move || presence.on_row(&avatars_id).into_iter().map(|member| view! { <Avatar member /> }).collect_view()}
+                                        </span>
+                                    </td>
                                     <td>{model.game_name}</td>
-                                    <td class="cell-right">{model.quantity}</td>
+                                    <td class="cell-right">
+                                        <input
+                                            type="number"
+                                            min="1"
+                                            class="inline-input inline-input--number"
+                                            disabled=is_local
+                                            prop:value=model.quantity.to_string()
+                                            on:change=move |event| {
+                                                if let Ok(next) = event_target_value(&event).parse::<i32>()
+                                                    && next > 0
+                                                {
+                                                    on_quantity_change.call((quantity_id.clone(), next));
+                                                }
+                                            }
+                                        />
+                                    </td>
                                     <td>
                                         <select
                                             class="inline-select"
@@ -309,7 +939,64 @@ fn ModelsTable(
                                             }).collect_view()}
                                         </select>
                                     </td>
+                                    <td>
+                                        <TagChips
+                                            id=model.id.clone()
+                                            names=tag_names
+                                            on_change=on_tags_change
+                                        />
+                                    </td>
+                                    <td>
+                                        <input
+                                            type="text"
+                                            class="inline-input"
+                                            placeholder="Add a note"
+                                            disabled=is_local
+                                            prop:value=model.notes.unwrap_or_default()
+                                            on:change=move |event| {
+                                                on_notes_change.call((
+                                                    notes_id.clone(),
+                                                    event_target_value(&event),
+                                                ));
+                                            }
+                                        />
+                                    </td>
+                                    <td>
+                                        <button
+                                            type="button"
+                                            class="link-button"
+                                            disabled=is_local
+                                            on:click={
+                                                let id = model.id.clone();
+                                                move |_| {
+                                                    expanded.update(|current| {
+                                                        *current = match current.take() {
+                                                            Some(open) if open == id => None,
+                                                            _ => Some(id.clone()),
+                                                        };
+                                                    });
+                                                }
+                                            }
+                                        >
+                                            "Photos"
+                                        </button>
+                                        <button
+                                            type="button"
+                                            class="link-button"
+                                            disabled=is_local
+                                            on:click=move |_| on_delete.call(delete_id.clone())
+                                        >
+                                            "Delete"
+                                        </button>
+                                    </td>
                                 </tr>
+                                {move || is_expanded().then(|| view! {
+                                    <tr class="row-detail">
+                                        <td colspan="7">
+                                            <PhotoGallery user_model_id=detail_id.clone() />
+                                        </td>
+                                    </tr>
+                                })}
                             }
                         })
                         .collect_view()
@@ -319,6 +1006,212 @@ fn ModelsTable(
     }
 }
 
+#[component]
+fn PhotoGallery(user_model_id: String) -> impl IntoView {
+    let refresh = create_rw_signal(0u32);
+    let upload_error = create_rw_signal(None::<String>);
+    let uploading = create_rw_signal(false);
+    let photos = {
+        let user_model_id = user_model_id.clone();
+        create_resource(move || refresh.get(), move |_| {
+            let user_model_id = user_model_id.clone();
+            async move { fetch_photos(user_model_id).await }
+        })
+    };
+
+    view! {
+        <div class="gallery">
+            {move || match photos.get() {
+                None => view! { <p class="state">"Loading photos..."</p> }.into_view(),
+                Some(Err(message)) => view! { <p class="state state--error">{message}</p> }.into_view(),
+                Some(Ok(list)) if list.is_empty() => {
+                    view! { <p class="state">"No photos yet."</p> }.into_view()
+                }
+                Some(Ok(list)) => list
+                    .into_iter()
+                    .map(|photo| view! {
+                        <a
+                            class="gallery__item"
+                            href=format!("{API_BASE}/photos/{}", photo.id)
+                            target="_blank"
+                        >
+                            <img
+                                src=format!("{API_BASE}/photos/{}/thumbnail", photo.id)
+                                alt=photo.file_name
+                                loading="lazy"
+                            />
+                        </a>
+                    })
+                    .collect_view(),
+            }}
+            <label class="gallery__upload">
+                {move || if uploading.get() { "Uploading..." } else { "Add photo" }}
+                <input
+                    type="file"
+                    accept="image/jpeg,image/png,image/webp"
+                    disabled=move || uploading.get()
+                    on:change=move |event| {
+                        let input = event_target::<web_sys::HtmlInputElement>(&event);
+                        let Some(file) = input.files().and_then(|files| files.get(0)) else {
+                            return;
+                        };
+                        input.set_value("");
+                        upload_error.set(None);
+                        uploading.set(true);
+                        let user_model_id = user_model_id.clone();
+                        spawn_local(async move {
+                            match upload_photo(user_model_id, file).await {
+                                Ok(_) => refresh.update(|value| *value += 1),
+                                Err(message) => upload_error.set(Some(message)),
+                            }
+                            uploading.set(false);
+                        });
+                    }
+                />
+            </label>
+            {move || upload_error.get().map(|message| view! {
+                <p class="state state--error">{message}</p>
+            })}
+        </div>
+    }
+}
+
+#[component]
+fn TagChips(
+    id: String,
+    names: Vec<String>,
+    on_change: Callback<(String, Vec<String>)>,
+) -> impl IntoView {
+    let chips = names
+        .iter()
+        .cloned()
+        .map(|name| {
+            let id = id.clone();
+            let remaining = names
+                .iter()
+                .filter(|other| **other != name)
+                .cloned()
+                .collect::<Vec<_>>();
+            view! {
+                <span class="chip">
+                    {name}
+                    <button
+                        type="button"
+                        class="chip__remove"
+                        on:click=move |_| on_change.call((id.clone(), remaining.clone()))
+                    >
+                        "×"
+                    </button>
+                </span>
+            }
+        })
+        .collect_view();
+    view! {
+        <div class="chips">
+            {chips}
+            <input
+                type="text"
+                class="chip-input"
+                placeholder="+ tag"
+                on:keydown=move |event| {
+                    if event.key() != "Enter" {
+                        return;
+                    }
+                    event.prevent_default();
+                    let value = event_target_value(&event).trim().to_string();
+                    if value.is_empty() || names.contains(&value) {
+                        return;
+                    }
+                    let mut next = names.clone();
+                    next.push(value);
+                    on_change.call((id.clone(), next));
+                }
+            />
+        </div>
+    }
+}
+
+#[component]
+fn LocationTreeView(
+    tree: LocationTree,
+    selected: ReadSignal<Option<String>>,
+    on_select: Callback<Option<String>>,
+) -> impl IntoView {
+    view! {
+        <ul class="locations">
+            <li>
+                <button
+                    type="button"
+                    class="location"
+                    class=("location--selected", move || selected.get().is_none())
+                    on:click=move |_| on_select.call(None)
+                >
+                    "All locations"
+                </button>
+            </li>
+            {tree
+                .nodes
+                .into_iter()
+                .map(|node| location_node_view(node, selected, on_select))
+                .collect_view()}
+            <li class="location location--unassigned">
+                "Unassigned"
+                <span class="location__quantity">{tree.unassigned_quantity}</span>
+            </li>
+        </ul>
+    }
+}
+
+fn location_node_view(
+    node: LocationNode,
+    selected: ReadSignal<Option<String>>,
+    on_select: Callback<Option<String>>,
+) -> View {
+    let id = node.id.clone();
+    let is_selected = {
+        let id = id.clone();
+        move || selected.get().as_deref() == Some(id.as_str())
+    };
+    let children = node
+        .children
+        .into_iter()
+        .map(|child| location_node_view(child, selected, on_select))
+        .collect_view();
+    view! {
+        <li>
+            <button
+                type="button"
+                class="location"
+                class=("location--selected", is_selected)
+                on:click=move |_| on_select.call(Some(id.clone()))
+            >
+                {node.name}
+                <span class="location__kind">{node.kind.label()}</span>
+                <span class="location__quantity">{node.quantity}</span>
+            </button>
+            <ul class="locations">{children}</ul>
+        </li>
+    }
+    .into_view()
+}
+
+/// Tooltip for a row with unsynced changes, e.g. "Changed at 14:05, not synced yet".
+fn pending_since(last_modified: f64) -> String {
+    let date = js_sys::Date::new(&last_modified.into());
+    format!(
+        "Changed at {:02}:{:02}, not synced yet",
+        date.get_hours(),
+        date.get_minutes()
+    )
+}
+
+fn parse_optional_number(value: &str) -> Result<Option<i64>, ()> {
+    match value.trim() {
+        "" => Ok(None),
+        value => value.parse::<i64>().map(Some).map_err(|_| ()),
+    }
+}
+
 async fn fetch_games() -> Result<Vec<Game>, String> {
     let response = Request::get(&format!("{API_BASE}/games"))
         .send()
@@ -357,8 +1250,12 @@ async fn fetch_model_definitions() -> Result<Vec<ModelDefinition>, String> {
         .map_err(|error| format!("Failed to parse model definitions: {error}"))
 }
 
-async fn fetch_user_models() -> Result<Vec<UserModelListItem>, String> {
-    let response = Request::get(&format!("{API_BASE}/user-models"))
+async fn fetch_user_models(location_id: Option<String>) -> Result<Vec<UserModelListItem>, String> {
+    let url = match location_id {
+        Some(location_id) => format!("{API_BASE}/user-models?location_id={location_id}"),
+        None => format!("{API_BASE}/user-models"),
+    };
+    let response = Request::get(&url)
         .send()
         .await
         .map_err(|error| format!("Failed to load models: {error}"))?;
@@ -376,16 +1273,43 @@ async fn fetch_user_models() -> Result<Vec<UserModelListItem>, String> {
         .map_err(|error| format!("Failed to parse models: {error}"))
 }
 
+async fn fetch_location_tree() -> Result<LocationTree, String> {
+    let response = Request::get(&format!("{API_BASE}/locations/tree"))
+        .send()
+        .await
+        .map_err(|error| format!("Failed to load locations: {error}"))?;
+
+    if !response.ok() {
+        return Err(format!(
+            "Failed to load locations: {}",
+            response.status()
+        ));
+    }
+
+    response
+        .json::<LocationTree>()
+        .await
+        .map_err(|error| format!("Failed to parse locations: {error}"))
+}
+
 async fn create_model_definition(
     name: String,
     game_id: String,
+    points: Option<i64>,
+    unit_size: Option<i64>,
 ) -> Result<ModelDefinition, String> {
     let response = Request::post(&format!("{API_BASE}/model-definitions"))
         .header("content-type", "application/json")
         .body(
-            serde_json::to_string(&CreateModelDefinitionRequest { name, game_id })
-                .map_err(|error| format!("Failed to serialize model definition: {error}"))?,
+            serde_json::to_string(&CreateModelDefinitionRequest {
+                name,
+                game_id,
+                points,
+                unit_size,
+            })
+            .map_err(|error| format!("Failed to serialize model definition: {error}"))?,
         )
+        .map_err(|error| format!("Failed to create model definition: {error}"))?
         .send()
         .await
         .map_err(|error| format!("Failed to create model definition: {error}"))?;
@@ -418,6 +1342,7 @@ async fn create_user_model(
             })
             .map_err(|error| format!("Failed to serialize user model: {error}"))?,
         )
+        .map_err(|error| format!("Failed to create user model: {error}"))?
         .send()
         .await
         .map_err(|error| format!("Failed to create user model: {error}"))?;
@@ -435,21 +1360,120 @@ async fn create_user_model(
         .map_err(|error| format!("Failed to parse user model: {error}"))
 }
 
-async fn update_user_model_status(id: String, status: Status) -> Result<UserModel, String> {
-    let response = Request::patch(&format!("{API_BASE}/user-models/{id}"))
-        .header("content-type", "application/json")
+async fn update_user_model_status(
+    id: String,
+    version: i64,
+    status: Status,
+) -> Result<UserModel, UpdateError> {
+    update_user_model_if_match(
+        id,
+        Some(version),
+        UpdateUserModelRequest {
+            status: Some(status),
+            ..UpdateUserModelRequest::default()
+        },
+    )
+    .await
+}
+
+async fn update_user_model_quantity(
+    id: String,
+    version: i64,
+    quantity: i32,
+) -> Result<UserModel, UpdateError> {
+    update_user_model_if_match(
+        id,
+        Some(version),
+        UpdateUserModelRequest {
+            quantity: Some(quantity),
+            ..UpdateUserModelRequest::default()
+        },
+    )
+    .await
+}
+
+async fn update_user_model_notes(
+    id: String,
+    version: i64,
+    notes: String,
+) -> Result<UserModel, UpdateError> {
+    update_user_model_if_match(
+        id,
+        Some(version),
+        UpdateUserModelRequest {
+            notes: Some(notes),
+            ..UpdateUserModelRequest::default()
+        },
+    )
+    .await
+}
+
+/// Why a `PATCH /user-models/:id` did not go through.
+enum UpdateError {
+    /// 412: the lot was saved elsewhere after the version we sent.
+    Stale,
+    Failed(String),
+}
+
+/// Updates a lot regardless of what others have saved since it was loaded.
+async fn update_user_model(id: String, payload: UpdateUserModelRequest) -> Result<UserModel, String> {
+    update_user_model_if_match(id, None, payload)
+        .await
+        .map_err(|error| match error {
+            UpdateError::Stale => "Failed to update user model: it was changed elsewhere".to_string(),
+            UpdateError::Failed(message) => message,
+        })
+}
+
+/// Updates a lot, sending `version` as `If-Match` so an edit based on an
+/// outdated copy fails instead of overwriting someone else's.
+async fn update_user_model_if_match(
+    id: String,
+    version: Option<i64>,
+    payload: UpdateUserModelRequest,
+) -> Result<UserModel, UpdateError> {
+    let mut request = Request::patch(&format!("{API_BASE}/user-models/{id}"))
+        .header("content-type", "application/json");
+    if let Some(version) = version {
+        request = request.header("if-match", &format!("\"{version}\""));
+    }
+    let response = request
         .body(
-            serde_json::to_string(&UpdateUserModelRequest { status })
-                .map_err(|error| format!("Failed to serialize status: {error}"))?,
+            serde_json::to_string(&payload).map_err(|error| {
+                UpdateError::Failed(format!("Failed to serialize user model update: {error}"))
+            })?,
         )
+        .map_err(|error| UpdateError::Failed(format!("Failed to update user model: {error}")))?
         .send()
         .await
-        .map_err(|error| format!("Failed to update user model: {error}"))?;
+        .map_err(|error| UpdateError::Failed(format!("Failed to update user model: {error}")))?;
 
+    if response.status() == 412 {
+        return Err(UpdateError::Stale);
+    }
     if !response.ok() {
-        return Err(format!(
+        return Err(UpdateError::Failed(format!(
             "Failed to update user model: {}",
             response.status()
+        )));
+    }
+
+    response
+        .json::<UserModel>()
+        .await
+        .map_err(|error| UpdateError::Failed(format!("Failed to parse user model: {error}")))
+}
+
+async fn fetch_user_model(id: &str) -> Result<UserModel, String> {
+    let response = Request::get(&format!("{API_BASE}/user-models/{id}"))
+        .send()
+        .await
+        .map_err(|error| format!("Failed to load user model: {error}"))?;
+
+    if !response.ok() {
+        return Err(format!(
+            "Failed to load user model: {}",
+            response.status()
         ));
     }
 
@@ -459,26 +1483,214 @@ async fn update_user_model_status(id: String, status: Status) -> Result<UserMode
         .map_err(|error| format!("Failed to parse user model: {error}"))
 }
 
-#[derive(Clone, Deserialize)]
+async fn fetch_photos(user_model_id: String) -> Result<Vec<Photo>, String> {
+    let response = Request::get(&format!("{API_BASE}/user-models/{user_model_id}/photos"))
+        .send()
+        .await
+        .map_err(|error| format!("Failed to load photos: {error}"))?;
+
+    if !response.ok() {
+        return Err(format!(
+            "Failed to load photos: {}",
+            response.status()
+        ));
+    }
+
+    response
+        .json::<Vec<Photo>>()
+        .await
+        .map_err(|error| format!("Failed to parse photos: {error}"))
+}
+
+async fn upload_photo(user_model_id: String, file: web_sys::File) -> Result<Photo, String> {
+    let form = web_sys::FormData::new()
+        .map_err(|_| "Failed to upload photo: could not build form".to_string())?;
+    form.append_with_blob_and_filename("photo", &file, &file.name())
+        .map_err(|_| "Failed to upload photo: could not attach file".to_string())?;
+
+    let response = Request::post(&format!("{API_BASE}/user-models/{user_model_id}/photos"))
+        .body(form)
+        .map_err(|error| format!("Failed to upload photo: {error}"))?
+        .send()
+        .await
+        .map_err(|error| format!("Failed to upload photo: {error}"))?;
+
+    match response.status() {
+        413 => return Err("Photo is too large (10 MB maximum).".to_string()),
+        415 => return Err("Photos must be JPEG, PNG or WebP images.".to_string()),
+        _ if !response.ok() => {
+            return Err(format!(
+                "Failed to upload photo: {}",
+                response.status()
+            ));
+        }
+        _ => {}
+    }
+
+    response
+        .json::<Photo>()
+        .await
+        .map_err(|error| format!("Failed to parse photo: {error}"))
+}
+
+async fn fetch_tags() -> Result<Vec<Tag>, String> {
+    let response = Request::get(&format!("{API_BASE}/tags"))
+        .send()
+        .await
+        .map_err(|error| format!("Failed to load tags: {error}"))?;
+
+    if !response.ok() {
+        return Err(format!(
+            "Failed to load tags: {}",
+            response.status()
+        ));
+    }
+
+    response
+        .json::<Vec<Tag>>()
+        .await
+        .map_err(|error| format!("Failed to parse tags: {error}"))
+}
+
+async fn create_tag(name: String) -> Result<Tag, String> {
+    let response = Request::post(&format!("{API_BASE}/tags"))
+        .header("content-type", "application/json")
+        .body(
+            serde_json::to_string(&CreateTagRequest { name })
+                .map_err(|error| format!("Failed to serialize tag: {error}"))?,
+        )
+        .map_err(|error| format!("Failed to create tag: {error}"))?
+        .send()
+        .await
+        .map_err(|error| format!("Failed to create tag: {error}"))?;
+
+    if !response.ok() {
+        return Err(format!(
+            "Failed to create tag: {}",
+            response.status()
+        ));
+    }
+
+    response
+        .json::<Tag>()
+        .await
+        .map_err(|error| format!("Failed to parse tag: {error}"))
+}
+
+/// Replaces the tags on a user model, creating any tag names that do not exist yet.
+async fn assign_user_model_tags(id: String, names: Vec<String>) -> Result<Vec<Tag>, String> {
+    let mut existing = fetch_tags().await?;
+    let mut tag_ids = Vec::with_capacity(names.len());
+    for name in names {
+        let tag = match existing.iter().find(|tag| tag.name == name) {
+            Some(tag) => tag.clone(),
+            None => {
+                let tag = create_tag(name).await?;
+                existing.push(tag.clone());
+                tag
+            }
+        };
+        tag_ids.push(tag.id);
+    }
+
+    let response = Request::put(&format!("{API_BASE}/user-models/{id}/tags"))
+        .header("content-type", "application/json")
+        .body(
+            serde_json::to_string(&SetTagsRequest { tag_ids })
+                .map_err(|error| format!("Failed to serialize tags: {error}"))?,
+        )
+        .map_err(|error| format!("Failed to update tags: {error}"))?
+        .send()
+        .await
+        .map_err(|error| format!("Failed to update tags: {error}"))?;
+
+    if !response.ok() {
+        return Err(format!(
+            "Failed to update tags: {}",
+            response.status()
+        ));
+    }
+
+    response
+        .json::<Vec<Tag>>()
+        .await
+        .map_err(|error| format!("Failed to parse tags: {error}"))
+}
+
+#[derive(Clone, Deserialize, Serialize)]
 struct Game {
     id: String,
     name: String,
 }
 
-#[derive(Clone, Deserialize)]
+#[derive(Clone, Deserialize, Serialize)]
 struct ModelDefinition {
     id: String,
     name: String,
     game: Game,
 }
 
-#[derive(Clone, Deserialize)]
+#[derive(Clone, Deserialize, Serialize)]
 struct UserModelListItem {
     id: String,
     model_name: String,
     game_name: String,
     quantity: i32,
     status: Status,
+    notes: Option<String>,
+    tags: Vec<Tag>,
+    version: i64,
+    /// Changed locally and not yet synced.
+    #[serde(default)]
+    is_dirty: bool,
+    /// When the unsynced change was made, in milliseconds since the epoch.
+    #[serde(default)]
+    last_modified: Option<f64>,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+struct Tag {
+    id: String,
+    name: String,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+struct Photo {
+    id: String,
+    file_name: String,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+struct LocationTree {
+    nodes: Vec<LocationNode>,
+    unassigned_quantity: i64,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+struct LocationNode {
+    id: String,
+    name: String,
+    kind: LocationKind,
+    quantity: i64,
+    children: Vec<LocationNode>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum LocationKind {
+    Room,
+    Shelf,
+    Box,
+}
+
+impl LocationKind {
+    fn label(self) -> &'static str {
+        match self {
+            LocationKind::Room => "Room",
+            LocationKind::Shelf => "Shelf",
+            LocationKind::Box => "Box",
+        }
+    }
 }
 
 #[derive(Clone, Deserialize)]
@@ -487,12 +1699,16 @@ struct UserModel {
     model_definition_id: String,
     quantity: i32,
     status: Status,
+    notes: Option<String>,
+    version: i64,
 }
 
 #[derive(Serialize)]
 struct CreateModelDefinitionRequest {
     name: String,
     game_id: String,
+    points: Option<i64>,
+    unit_size: Option<i64>,
 }
 
 #[derive(Serialize)]
@@ -502,9 +1718,45 @@ struct CreateUserModelRequest {
     status: Status,
 }
 
-#[derive(Serialize)]
+#[derive(Default, Serialize)]
 struct UpdateUserModelRequest {
-    status: Status,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<Status>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    quantity: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    notes: Option<String>,
+}
+
+#[derive(Serialize)]
+struct CreateTagRequest {
+    name: String,
+}
+
+#[derive(Serialize)]
+struct SetTagsRequest {
+    tag_ids: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Page {
+    Collection,
+    ArmyLists,
+    Projects,
+}
+
+impl Page {
+    fn all() -> [Page; 3] {
+        [Page::Collection, Page::ArmyLists, Page::Projects]
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Page::Collection => "Collection",
+            Page::ArmyLists => "Army Lists",
+            Page::Projects => "Projects",
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
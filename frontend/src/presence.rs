@@ -0,0 +1,222 @@
+use std::time::Duration;
+
+use leptos::*;
+use serde::Deserialize;
+use serde_json::json;
+use wasm_bindgen::{closure::Closure, JsCast};
+use web_sys::{MessageEvent, WebSocket};
+
+use crate::API_BASE;
+
+/// How long to wait before reconnecting after the socket closes.
+const RECONNECT_DELAY: Duration = Duration::from_secs(3);
+
+const NAME_KEY: &str = "mini-tracker-name";
+
+/// Someone with the collection open, as reported by the backend.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Member {
+    pub client_id: String,
+    pub name: String,
+    pub color: String,
+    pub user_model_id: Option<String>,
+}
+
+impl Member {
+    pub fn initials(&self) -> String {
+        self.name
+            .split_whitespace()
+            .filter_map(|word| word.chars().next())
+            .take(2)
+            .flat_map(char::to_uppercase)
+            .collect()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+enum ServerMessage {
+    Hello {
+        client_id: String,
+    },
+    Presence {
+        members: Vec<Member>,
+    },
+    Error {
+        message: String,
+    },
+    /// Change events also arrive here, but the page already gets them from `/events`.
+    #[serde(other)]
+    Other,
+}
+
+/// Who else is connected over `/ws`, and which row this tab has open.
+#[derive(Clone, Copy)]
+pub struct Presence {
+    members: RwSignal<Vec<Member>>,
+    client_id: RwSignal<Option<String>>,
+    socket: StoredValue<Option<WebSocket>>,
+    focused: StoredValue<Option<String>>,
+}
+
+impl Presence {
+    /// Opens the collaboration socket, reconnecting whenever it closes.
+    pub fn connect() -> Self {
+        let presence = Self {
+            members: create_rw_signal(Vec::new()),
+            client_id: create_rw_signal(None),
+            socket: store_value(None),
+            focused: store_value(None),
+        };
+        presence.open(display_name());
+        presence
+    }
+
+    /// Everyone else who is connected.
+    pub fn others(&self) -> Vec<Member> {
+        let client_id = self.client_id.get();
+        self.members.with(|members| {
+            members
+                .iter()
+                .filter(|member| Some(&member.client_id) != client_id.as_ref())
+                .cloned()
+                .collect()
+        })
+    }
+
+    /// Others who have the given row open.
+    pub fn on_row(&self, user_model_id: &str) -> Vec<Member> {
+        self.others()
+            .into_iter()
+            .filter(|member| member.user_model_id.as_deref() == Some(user_model_id))
+            .collect()
+    }
+
+    /// Tells the others which row this tab has open.
+    pub fn focus(&self, user_model_id: Option<String>) {
+        if self.focused.get_value() == user_model_id {
+            return;
+        }
+        self.focused.set_value(user_model_id);
+        self.send_focus();
+    }
+
+    fn send_focus(&self) {
+        let message = json!({ "type": "row-focus", "user_model_id": self.focused.get_value() });
+        self.send(&message.to_string());
+    }
+
+    fn send(&self, text: &str) {
+        self.socket.with_value(|socket| {
+            if let Some(socket) = socket
+                .as_ref()
+                .filter(|socket| socket.ready_state() == WebSocket::OPEN)
+            {
+                let _ = socket.send_with_str(text);
+            }
+        });
+    }
+
+    fn open(self, name: String) {
+        let socket = match WebSocket::new(&socket_url()) {
+            Ok(socket) => socket,
+            Err(error) => {
+                logging::warn!("failed to open collaboration socket: {error:?}");
+                return;
+            }
+        };
+
+        let on_open = {
+            let socket = socket.clone();
+            let hello = json!({ "type": "hello", "name": name }).to_string();
+            Closure::<dyn Fn()>::new(move || {
+                let _ = socket.send_with_str(&hello);
+            })
+        };
+        socket.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+        on_open.forget();
+
+        let on_message = Closure::<dyn Fn(MessageEvent)>::new(move |event: MessageEvent| {
+            let Some(data) = event.data().as_string() else {
+                return;
+            };
+            match serde_json::from_str::<ServerMessage>(&data) {
+                Ok(ServerMessage::Hello { client_id }) => {
+                    self.client_id.set(Some(client_id));
+                    // After a reconnect, tell the others again where we are.
+                    if self.focused.get_value().is_some() {
+                        self.send_focus();
+                    }
+                }
+                Ok(ServerMessage::Presence { members }) => self.members.set(members),
+                Ok(ServerMessage::Error { message }) => {
+                    logging::warn!("collaboration error: {message}");
+                }
+                Ok(ServerMessage::Other) => {}
+                Err(error) => logging::warn!("ignoring malformed collaboration message: {error}"),
+            }
+        });
+        socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        on_message.forget();
+
+        // The server pings every few seconds and the browser answers on its
+        // own; a connection that stops answering is dropped and lands here.
+        let on_close = Closure::<dyn Fn()>::new(move || {
+            self.members.set(Vec::new());
+            self.client_id.set(None);
+            self.socket.set_value(None);
+            let name = name.clone();
+            set_timeout(move || self.open(name), RECONNECT_DELAY);
+        });
+        socket.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+        on_close.forget();
+
+        self.socket.set_value(Some(socket));
+    }
+}
+
+/// Avatars for everyone else who is connected.
+#[component]
+pub fn PresenceBar(presence: Presence) -> impl IntoView {
+    view! {
+        <div class="avatars" aria-label="Also here">
+            {move || presence.others().into_iter().map(|member| view! { <Avatar member /> }).collect_view()}
+        </div>
+    }
+}
+
+#[component]
+pub fn Avatar(member: Member) -> impl IntoView {
+    view! {
+        <span class="avatar" style=format!("background: {}", member.color) title=member.name.clone()>
+            {member.initials()}
+        </span>
+    }
+}
+
+/// The name shown to others, remembered per browser. New visitors get a guest name.
+fn display_name() -> String {
+    let storage = window().local_storage().ok().flatten();
+    if let Some(name) = storage
+        .as_ref()
+        .and_then(|storage| storage.get_item(NAME_KEY).ok().flatten())
+    {
+        return name;
+    }
+
+    let name = format!("Guest {}", (js_sys::Math::random() * 9000.0) as u32 + 1000);
+    if let Some(storage) = storage {
+        let _ = storage.set_item(NAME_KEY, &name);
+    }
+    name
+}
+
+fn socket_url() -> String {
+    let location = window().location();
+    let scheme = match location.protocol().as_deref() {
+        Ok("https:") => "wss",
+        _ => "ws",
+    };
+    let host = location.host().unwrap_or_default();
+    format!("{scheme}://{host}{API_BASE}/ws")
+}
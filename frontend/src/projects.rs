@@ -0,0 +1,319 @@
+use gloo_net::http::Request;
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{fetch_user_models, Status, API_BASE};
+
+#[component]
+pub fn ProjectsPage() -> impl IntoView {
+    let projects_refresh = create_rw_signal(0u32);
+    let projects = create_resource(move || projects_refresh.get(), |_| async {
+        fetch_projects().await
+    });
+    let owned = create_resource(|| (), |_| async { fetch_user_models(None).await });
+
+    let name = create_rw_signal(String::new());
+    let target_status = create_rw_signal(Status::Painted);
+    let due_date = create_rw_signal(String::new());
+    let selected = create_rw_signal(Vec::<String>::new());
+    let form_error = create_rw_signal(None::<String>);
+
+    view! {
+        <section class="panel">
+            <h2>"New Project"</h2>
+            <form class="form" on:submit=move |event| {
+                event.prevent_default();
+                let name_value = name.get().trim().to_string();
+                if name_value.is_empty() {
+                    form_error.set(Some("Project name is required.".to_string()));
+                    return;
+                }
+                let due_date_value = Some(due_date.get()).filter(|value| !value.is_empty());
+                let request = CreateProjectRequest {
+                    name: name_value,
+                    target_status: target_status.get(),
+                    due_date: due_date_value,
+                    user_model_ids: selected.get(),
+                };
+                form_error.set(None);
+                spawn_local(async move {
+                    match create_project(request).await {
+                        Ok(_) => {
+                            name.set(String::new());
+                            due_date.set(String::new());
+                            selected.set(Vec::new());
+                            projects_refresh.update(|value| *value += 1);
+                        }
+                        Err(message) => form_error.set(Some(message)),
+                    }
+                });
+            }>
+                <label class="field">
+                    <span>"Project Name"</span>
+                    <input
+                        type="text"
+                        prop:value=move || name.get()
+                        on:input=move |event| name.set(event_target_value(&event))
+                    />
+                </label>
+                <label class="field">
+                    <span>"Target Status"</span>
+                    <select
+                        prop:value=move || target_status.get().as_str().to_string()
+                        on:change=move |event| {
+                            if let Ok(next) = event_target_value(&event).parse::<Status>() {
+                                target_status.set(next);
+                            }
+                        }
+                    >
+                        {Status::all().into_iter().map(|option| view! {
+                            <option value={option.as_str()}>{option.label()}</option>
+                        }).collect_view()}
+                    </select>
+                </label>
+                <label class="field">
+                    <span>"Due Date"</span>
+                    <input
+                        type="date"
+                        prop:value=move || due_date.get()
+                        on:input=move |event| due_date.set(event_target_value(&event))
+                    />
+                </label>
+                <button type="submit">"Create Project"</button>
+                <fieldset class="lot-picker">
+                    <legend>"Models"</legend>
+                    {move || match owned.get() {
+                        None => view! { <p class="state">"Loading models..."</p> }.into_view(),
+                        Some(Err(message)) => view! { <p class="state state--error">{message}</p> }.into_view(),
+                        Some(Ok(models)) => models.into_iter().map(|model| {
+                            let id = model.id.clone();
+                            let is_checked = {
+                                let id = id.clone();
+                                move || selected.get().contains(&id)
+                            };
+                            view! {
+                                <label class="lot-picker__item">
+                                    <input
+                                        type="checkbox"
+                                        prop:checked=is_checked
+                                        on:change=move |event| {
+                                            let checked = event_target_checked(&event);
+                                            selected.update(|ids| {
+                                                ids.retain(|other| *other != id);
+                                                if checked {
+                                                    ids.push(id.clone());
+                                                }
+                                            });
+                                        }
+                                    />
+                                    {format!("{} × {} ({})", model.quantity, model.model_name, model.game_name)}
+                                </label>
+                            }
+                        }).collect_view(),
+                    }}
+                </fieldset>
+                {move || form_error.get().map(|message| view! {
+                    <p class="state state--error">{message}</p>
+                })}
+            </form>
+        </section>
+        <section class="panel">
+            <h2>"Projects"</h2>
+            {move || match projects.get() {
+                None => view! { <p class="state">"Loading projects..."</p> }.into_view(),
+                Some(Err(message)) => view! { <p class="state state--error">{message}</p> }.into_view(),
+                Some(Ok(list)) if list.is_empty() => {
+                    view! { <p class="state">"No projects yet."</p> }.into_view()
+                }
+                Some(Ok(list)) => view! {
+                    <ul class="projects">
+                        {list.into_iter().map(|project| view! {
+                            <ProjectCard
+                                project
+                                on_deleted=Callback::new(move |_| {
+                                    projects_refresh.update(|value| *value += 1);
+                                })
+                            />
+                        }).collect_view()}
+                    </ul>
+                }.into_view(),
+            }}
+        </section>
+    }
+}
+
+#[component]
+fn ProjectCard(project: Project, on_deleted: Callback<()>) -> impl IntoView {
+    let progress = {
+        let id = project.id.clone();
+        create_resource(|| (), move |_| {
+            let id = id.clone();
+            async move { fetch_project_progress(id).await }
+        })
+    };
+    let error = create_rw_signal(None::<String>);
+    let id = project.id.clone();
+
+    view! {
+        <li
+            class="project"
+            class=("project--overdue", move || {
+                matches!(progress.get(), Some(Ok(progress)) if progress.overdue)
+            })
+        >
+            <div class="project__header">
+                <strong>{project.name}</strong>
+                <span class="project__meta">
+                    {format!("Target: {}", project.target_status.label())}
+                    {project.due_date.map(|due_date| format!(" · Due {due_date}"))}
+                </span>
+                <button
+                    type="button"
+                    class="link-button"
+                    on:click=move |_| {
+                        let id = id.clone();
+                        spawn_local(async move {
+                            match delete_project(id).await {
+                                Ok(()) => on_deleted.call(()),
+                                Err(message) => error.set(Some(message)),
+                            }
+                        });
+                    }
+                >
+                    "Delete"
+                </button>
+            </div>
+            {move || match progress.get() {
+                None => view! { <p class="state">"Loading progress..."</p> }.into_view(),
+                Some(Err(message)) => view! { <p class="state state--error">{message}</p> }.into_view(),
+                Some(Ok(progress)) => {
+                    let percent = if progress.total_quantity > 0 {
+                        progress.completed_quantity * 100 / progress.total_quantity
+                    } else {
+                        0
+                    };
+                    view! {
+                        <div class="progress">
+                            <div class="progress__bar" style:width=format!("{percent}%")></div>
+                        </div>
+                        <p class="project__meta">
+                            {format!(
+                                "{} of {} done, {} remaining",
+                                progress.completed_quantity,
+                                progress.total_quantity,
+                                progress.remaining_quantity
+                            )}
+                            {progress.overdue.then_some(" · Overdue")}
+                        </p>
+                    }.into_view()
+                }
+            }}
+            {move || error.get().map(|message| view! {
+                <p class="state state--error">{message}</p>
+            })}
+        </li>
+    }
+}
+
+async fn fetch_projects() -> Result<Vec<Project>, String> {
+    let response = Request::get(&format!("{API_BASE}/projects"))
+        .send()
+        .await
+        .map_err(|error| format!("Failed to load projects: {error}"))?;
+
+    if !response.ok() {
+        return Err(format!(
+            "Failed to load projects: {}",
+            response.status()
+        ));
+    }
+
+    response
+        .json::<Vec<Project>>()
+        .await
+        .map_err(|error| format!("Failed to parse projects: {error}"))
+}
+
+async fn fetch_project_progress(id: String) -> Result<ProjectProgress, String> {
+    let response = Request::get(&format!("{API_BASE}/projects/{id}/progress"))
+        .send()
+        .await
+        .map_err(|error| format!("Failed to load project progress: {error}"))?;
+
+    if !response.ok() {
+        return Err(format!(
+            "Failed to load project progress: {}",
+            response.status()
+        ));
+    }
+
+    response
+        .json::<ProjectProgress>()
+        .await
+        .map_err(|error| format!("Failed to parse project progress: {error}"))
+}
+
+async fn create_project(request: CreateProjectRequest) -> Result<Project, String> {
+    let response = Request::post(&format!("{API_BASE}/projects"))
+        .header("content-type", "application/json")
+        .body(
+            serde_json::to_string(&request)
+                .map_err(|error| format!("Failed to serialize project: {error}"))?,
+        )
+        .map_err(|error| format!("Failed to create project: {error}"))?
+        .send()
+        .await
+        .map_err(|error| format!("Failed to create project: {error}"))?;
+
+    if !response.ok() {
+        return Err(format!(
+            "Failed to create project: {}",
+            response.status()
+        ));
+    }
+
+    response
+        .json::<Project>()
+        .await
+        .map_err(|error| format!("Failed to parse project: {error}"))
+}
+
+async fn delete_project(id: String) -> Result<(), String> {
+    let response = Request::delete(&format!("{API_BASE}/projects/{id}"))
+        .send()
+        .await
+        .map_err(|error| format!("Failed to delete project: {error}"))?;
+
+    if !response.ok() {
+        return Err(format!(
+            "Failed to delete project: {}",
+            response.status()
+        ));
+    }
+
+    Ok(())
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+struct Project {
+    id: String,
+    name: String,
+    target_status: Status,
+    due_date: Option<String>,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+struct ProjectProgress {
+    total_quantity: i64,
+    completed_quantity: i64,
+    remaining_quantity: i64,
+    overdue: bool,
+}
+
+#[derive(Serialize)]
+struct CreateProjectRequest {
+    name: String,
+    target_status: Status,
+    due_date: Option<String>,
+    user_model_ids: Vec<String>,
+}
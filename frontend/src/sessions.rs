@@ -0,0 +1,189 @@
+use std::time::Duration;
+
+use gloo_net::http::Request;
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{fetch_user_models, API_BASE};
+
+/// Header stopwatch for painting sessions. The running session lives on the
+/// server, so reloading the page picks the timer back up where it was.
+#[component]
+pub fn SessionStopwatch() -> impl IntoView {
+    let active = create_rw_signal(None::<Session>);
+    let now = create_rw_signal(js_sys::Date::now());
+    let selected_model_id = create_rw_signal(String::new());
+    let error = create_rw_signal(None::<String>);
+    let owned = create_resource(|| (), |_| async { fetch_user_models(None).await });
+
+    spawn_local(async move {
+        match fetch_active_session().await {
+            Ok(session) => active.set(session),
+            Err(message) => error.set(Some(message)),
+        }
+    });
+
+    if let Ok(handle) = set_interval_with_handle(
+        move || now.set(js_sys::Date::now()),
+        Duration::from_secs(1),
+    ) {
+        on_cleanup(move || handle.clear());
+    }
+
+    let elapsed = move || {
+        active.with(|session| {
+            session
+                .as_ref()
+                .map(|session| format_elapsed(now.get() - started_at_millis(&session.started_at)))
+        })
+    };
+
+    let start = move |_| {
+        let user_model_ids = Some(selected_model_id.get())
+            .filter(|id| !id.is_empty())
+            .into_iter()
+            .collect::<Vec<_>>();
+        error.set(None);
+        spawn_local(async move {
+            match start_session(user_model_ids).await {
+                Ok(session) => {
+                    now.set(js_sys::Date::now());
+                    active.set(Some(session));
+                }
+                Err(message) => error.set(Some(message)),
+            }
+        });
+    };
+
+    let stop = move |_| {
+        let Some(id) = active.with_untracked(|session| session.as_ref().map(|session| session.id.clone()))
+        else {
+            return;
+        };
+        error.set(None);
+        spawn_local(async move {
+            match stop_session(id).await {
+                Ok(()) => active.set(None),
+                Err(message) => error.set(Some(message)),
+            }
+        });
+    };
+
+    view! {
+        <div class="stopwatch" class=("stopwatch--running", move || active.with(Option::is_some))>
+            {move || match elapsed() {
+                Some(elapsed) => view! {
+                    <span class="stopwatch__time">{elapsed}</span>
+                    <button type="button" on:click=stop>"Stop"</button>
+                }.into_view(),
+                None => view! {
+                    <select
+                        class="inline-select"
+                        prop:value=move || selected_model_id.get()
+                        on:change=move |event| selected_model_id.set(event_target_value(&event))
+                    >
+                        <option value="">"No model"</option>
+                        {move || owned.get().and_then(Result::ok).map(|models| {
+                            models.into_iter().map(|model| view! {
+                                <option value={model.id.clone()}>{model.model_name}</option>
+                            }).collect_view()
+                        })}
+                    </select>
+                    <button type="button" on:click=start>"Start painting"</button>
+                }.into_view(),
+            }}
+            {move || error.get().map(|message| view! {
+                <p class="state state--error">{message}</p>
+            })}
+        </div>
+    }
+}
+
+/// Parses the server's `YYYY-MM-DD HH:MM:SS` UTC timestamps.
+fn started_at_millis(value: &str) -> f64 {
+    js_sys::Date::parse(&format!("{}Z", value.replace(' ', "T")))
+}
+
+fn format_elapsed(millis: f64) -> String {
+    let seconds = (millis.max(0.0) / 1000.0) as u64;
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+async fn fetch_active_session() -> Result<Option<Session>, String> {
+    let response = Request::get(&format!("{API_BASE}/sessions/active"))
+        .send()
+        .await
+        .map_err(|error| format!("Failed to load active session: {error}"))?;
+
+    if !response.ok() {
+        return Err(format!(
+            "Failed to load active session: {}",
+            response.status()
+        ));
+    }
+
+    response
+        .json::<Option<Session>>()
+        .await
+        .map_err(|error| format!("Failed to parse active session: {error}"))
+}
+
+async fn start_session(user_model_ids: Vec<String>) -> Result<Session, String> {
+    let response = Request::post(&format!("{API_BASE}/sessions/start"))
+        .header("content-type", "application/json")
+        .body(
+            serde_json::to_string(&StartSessionRequest { user_model_ids })
+                .map_err(|error| format!("Failed to serialize session: {error}"))?,
+        )
+        .map_err(|error| format!("Failed to start session: {error}"))?
+        .send()
+        .await
+        .map_err(|error| format!("Failed to start session: {error}"))?;
+
+    if !response.ok() {
+        return Err(format!(
+            "Failed to start session: {}",
+            response.status()
+        ));
+    }
+
+    response
+        .json::<Session>()
+        .await
+        .map_err(|error| format!("Failed to parse session: {error}"))
+}
+
+async fn stop_session(id: String) -> Result<(), String> {
+    let response = Request::post(&format!("{API_BASE}/sessions/{id}/stop"))
+        .header("content-type", "application/json")
+        .body("{}")
+        .map_err(|error| format!("Failed to stop session: {error}"))?
+        .send()
+        .await
+        .map_err(|error| format!("Failed to stop session: {error}"))?;
+
+    if !response.ok() {
+        return Err(format!(
+            "Failed to stop session: {}",
+            response.status()
+        ));
+    }
+
+    Ok(())
+}
+
+#[derive(Clone, Deserialize)]
+struct Session {
+    id: String,
+    started_at: String,
+}
+
+#[derive(Serialize)]
+struct StartSessionRequest {
+    user_model_ids: Vec<String>,
+}
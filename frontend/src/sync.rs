@@ -0,0 +1,429 @@
+use std::time::Duration;
+
+use gloo_net::http::Request;
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    CreateUserModelRequest, Status, UpdateUserModelRequest, UserModel, UserModelListItem, API_BASE,
+};
+
+/// How often queued changes are retried while there is a connection.
+pub const SYNC_INTERVAL: Duration = Duration::from_secs(30);
+
+/// First retry delay after a failed sync; it doubles with every failure.
+const BACKOFF_BASE_MS: f64 = 5_000.0;
+const BACKOFF_MAX_MS: f64 = 10.0 * 60.0 * 1_000.0;
+
+const STORAGE_KEY: &str = "mini-tracker-sync-queue";
+
+/// Prefix for IDs given to lots created while offline.
+const LOCAL_ID_PREFIX: &str = "local-";
+
+/// A change made without a connection, waiting to be sent to the API.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum PendingChange {
+    Create {
+        local_id: String,
+        model_definition_id: String,
+        /// Kept so the row can be shown again after a reload.
+        model_name: String,
+        game_name: String,
+        quantity: i32,
+        status: Status,
+    },
+    SetStatus {
+        id: String,
+        status: Status,
+    },
+}
+
+impl PendingChange {
+    fn id(&self) -> &str {
+        match self {
+            PendingChange::Create { local_id, .. } => local_id,
+            PendingChange::SetStatus { id, .. } => id,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct Queued {
+    change: PendingChange,
+    last_modified: f64,
+}
+
+/// Changes waiting to be synced, oldest first, plus the retry schedule.
+/// It is saved to local storage so a reload does not lose anything.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct SyncQueue {
+    pending: Vec<Queued>,
+    failures: u32,
+    next_attempt_at: f64,
+}
+
+impl SyncQueue {
+    pub fn load() -> Self {
+        window()
+            .local_storage()
+            .ok()
+            .flatten()
+            .and_then(|storage| storage.get_item(STORAGE_KEY).ok().flatten())
+            .and_then(|saved| serde_json::from_str(&saved).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        let Some(storage) = window().local_storage().ok().flatten() else {
+            return;
+        };
+        if let Ok(saved) = serde_json::to_string(self) {
+            let _ = storage.set_item(STORAGE_KEY, &saved);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Whether a change to this lot has to wait its turn behind queued ones.
+    pub fn has_pending(&self, id: &str) -> bool {
+        is_local_id(id) || self.pending.iter().any(|queued| queued.change.id() == id)
+    }
+
+    /// Queues a change. A status change to a lot that is already queued
+    /// replaces the earlier one, or is folded into its create.
+    pub fn enqueue(&mut self, change: PendingChange, now: f64) {
+        if let PendingChange::SetStatus { id, status } = &change {
+            let existing = self
+                .pending
+                .iter_mut()
+                .find(|queued| queued.change.id() == id);
+            if let Some(queued) = existing {
+                match &mut queued.change {
+                    PendingChange::Create {
+                        status: queued_status,
+                        ..
+                    }
+                    | PendingChange::SetStatus {
+                        status: queued_status,
+                        ..
+                    } => {
+                        *queued_status = *status;
+                    }
+                }
+                queued.last_modified = now;
+                return;
+            }
+        }
+
+        self.pending.push(Queued {
+            change,
+            last_modified: now,
+        });
+    }
+
+    /// The change to send next, if there is one and its retry time has come.
+    pub fn next_due(&self, now: f64) -> Option<PendingChange> {
+        if now < self.next_attempt_at {
+            return None;
+        }
+        self.pending.first().map(|queued| queued.change.clone())
+    }
+
+    /// Records that `sent` was accepted as the lot with `server_id`. If the
+    /// lot was edited again while the request was in flight, the newer edit
+    /// stays queued, now aimed at the server's ID.
+    pub fn succeeded(&mut self, sent: &PendingChange, server_id: &str) {
+        self.reset_backoff();
+        let Some(queued) = self.pending.first_mut() else {
+            return;
+        };
+        if &queued.change == sent {
+            self.pending.remove(0);
+            return;
+        }
+        if let PendingChange::Create { status, .. } = &queued.change {
+            queued.change = PendingChange::SetStatus {
+                id: server_id.to_string(),
+                status: *status,
+            };
+        }
+    }
+
+    /// Drops the change at the front, which the API refused.
+    pub fn rejected(&mut self) {
+        self.reset_backoff();
+        if !self.pending.is_empty() {
+            self.pending.remove(0);
+        }
+    }
+
+    /// Schedules the next attempt with exponential backoff.
+    pub fn failed(&mut self, now: f64) {
+        self.failures += 1;
+        self.next_attempt_at = now + backoff_ms(self.failures);
+    }
+
+    /// Retries straight away, e.g. when the browser comes back online.
+    pub fn reset_backoff(&mut self) {
+        self.failures = 0;
+        self.next_attempt_at = 0.0;
+    }
+
+    /// Shows queued changes in a freshly fetched list: local creates are
+    /// added and local status changes win over what the server returned.
+    pub fn apply_to(&self, models: &mut Vec<UserModelListItem>) {
+        for queued in &self.pending {
+            match &queued.change {
+                PendingChange::Create {
+                    local_id,
+                    model_name,
+                    game_name,
+                    quantity,
+                    status,
+                    ..
+                } => {
+                    if models.iter().all(|model| &model.id != local_id) {
+                        models.push(UserModelListItem {
+                            id: local_id.clone(),
+                            model_name: model_name.clone(),
+                            game_name: game_name.clone(),
+                            quantity: *quantity,
+                            status: *status,
+                            notes: None,
+                            tags: Vec::new(),
+                            version: 0,
+                            is_dirty: true,
+                            last_modified: Some(queued.last_modified),
+                        });
+                    }
+                }
+                PendingChange::SetStatus { id, status } => {
+                    if let Some(model) = models.iter_mut().find(|model| &model.id == id) {
+                        model.status = *status;
+                        model.is_dirty = true;
+                        model.last_modified = Some(queued.last_modified);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn backoff_ms(failures: u32) -> f64 {
+    let exponent = failures.saturating_sub(1).min(16) as i32;
+    (BACKOFF_BASE_MS * 2f64.powi(exponent)).min(BACKOFF_MAX_MS)
+}
+
+pub fn is_local_id(id: &str) -> bool {
+    id.starts_with(LOCAL_ID_PREFIX)
+}
+
+pub fn new_local_id() -> String {
+    format!(
+        "{LOCAL_ID_PREFIX}{}-{}",
+        js_sys::Date::now() as u64,
+        (js_sys::Math::random() * 1e9) as u64
+    )
+}
+
+pub fn is_online() -> bool {
+    window().navigator().on_line()
+}
+
+/// Why a queued change could not be sent.
+pub enum SyncError {
+    /// No connection or a server hiccup; try again later.
+    Unavailable,
+    /// The API refused the change, so retrying will not help.
+    Rejected(String),
+}
+
+/// Sends one queued change, returning the lot as the server now has it.
+pub async fn push(change: &PendingChange) -> Result<UserModel, SyncError> {
+    let request = match change {
+        PendingChange::Create {
+            model_definition_id,
+            quantity,
+            status,
+            ..
+        } => Request::post(&format!("{API_BASE}/user-models")).json(&CreateUserModelRequest {
+            model_definition_id: model_definition_id.clone(),
+            quantity: *quantity,
+            status: *status,
+        }),
+        PendingChange::SetStatus { id, status } => {
+            Request::patch(&format!("{API_BASE}/user-models/{id}")).json(&UpdateUserModelRequest {
+                status: Some(*status),
+                ..UpdateUserModelRequest::default()
+            })
+        }
+    };
+
+    let response = request
+        .map_err(|error| SyncError::Rejected(format!("Failed to sync change: {error}")))?
+        .send()
+        .await
+        .map_err(|_| SyncError::Unavailable)?;
+
+    if response.status() >= 500 {
+        return Err(SyncError::Unavailable);
+    }
+    if !response.ok() {
+        return Err(SyncError::Rejected(format!(
+            "Failed to sync change: {}",
+            response.status()
+        )));
+    }
+
+    response
+        .json::<UserModel>()
+        .await
+        .map_err(|error| SyncError::Rejected(format!("Failed to parse synced model: {error}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create(local_id: &str) -> PendingChange {
+        PendingChange::Create {
+            local_id: local_id.to_string(),
+            model_definition_id: "definition".to_string(),
+            model_name: "Intercessors".to_string(),
+            game_name: "Warhammer 40k".to_string(),
+            quantity: 5,
+            status: Status::Unassembled,
+        }
+    }
+
+    #[test]
+    fn status_changes_are_folded_into_queued_changes() {
+        let mut queue = SyncQueue::default();
+        queue.enqueue(create("local-1"), 1.0);
+        queue.enqueue(
+            PendingChange::SetStatus {
+                id: "local-1".to_string(),
+                status: Status::Painted,
+            },
+            2.0,
+        );
+        queue.enqueue(
+            PendingChange::SetStatus {
+                id: "a".to_string(),
+                status: Status::Assembled,
+            },
+            3.0,
+        );
+        queue.enqueue(
+            PendingChange::SetStatus {
+                id: "a".to_string(),
+                status: Status::Painted,
+            },
+            4.0,
+        );
+
+        assert_eq!(queue.len(), 2);
+        assert!(queue.has_pending("a"));
+        assert!(!queue.has_pending("b"));
+        assert!(matches!(
+            queue.next_due(5.0),
+            Some(PendingChange::Create {
+                status: Status::Painted,
+                ..
+            })
+        ));
+        queue.rejected();
+        assert_eq!(
+            queue.next_due(5.0),
+            Some(PendingChange::SetStatus {
+                id: "a".to_string(),
+                status: Status::Painted,
+            })
+        );
+    }
+
+    #[test]
+    fn failures_back_off_exponentially() {
+        let mut queue = SyncQueue::default();
+        queue.enqueue(create("local-1"), 0.0);
+
+        queue.failed(0.0);
+        assert_eq!(queue.next_due(BACKOFF_BASE_MS - 1.0), None);
+        assert!(queue.next_due(BACKOFF_BASE_MS).is_some());
+
+        queue.failed(0.0);
+        assert_eq!(queue.next_due(2.0 * BACKOFF_BASE_MS - 1.0), None);
+        for _ in 0..20 {
+            queue.failed(0.0);
+        }
+        assert!(queue.next_due(BACKOFF_MAX_MS).is_some());
+
+        queue.reset_backoff();
+        assert!(queue.next_due(0.0).is_some());
+        queue.failed(0.0);
+        queue.succeeded(&create("local-1"), "a");
+        assert_eq!(queue.len(), 0);
+        queue.enqueue(create("local-2"), 0.0);
+        assert!(queue.next_due(0.0).is_some());
+    }
+
+    #[test]
+    fn edits_made_while_a_create_is_in_flight_are_kept() {
+        let mut queue = SyncQueue::default();
+        queue.enqueue(create("local-1"), 1.0);
+        let sent = queue.next_due(1.0).expect("create to be due");
+        queue.enqueue(
+            PendingChange::SetStatus {
+                id: "local-1".to_string(),
+                status: Status::Assembled,
+            },
+            2.0,
+        );
+
+        queue.succeeded(&sent, "a");
+        assert_eq!(
+            queue.next_due(2.0),
+            Some(PendingChange::SetStatus {
+                id: "a".to_string(),
+                status: Status::Assembled,
+            })
+        );
+    }
+
+    #[test]
+    fn queued_changes_show_up_in_fetched_lists() {
+        let mut queue = SyncQueue::default();
+        queue.enqueue(create("local-1"), 1.0);
+        queue.enqueue(
+            PendingChange::SetStatus {
+                id: "a".to_string(),
+                status: Status::Painted,
+            },
+            2.0,
+        );
+
+        let mut models = vec![UserModelListItem {
+            id: "a".to_string(),
+            model_name: "Hellblasters".to_string(),
+            game_name: "Warhammer 40k".to_string(),
+            quantity: 3,
+            status: Status::Assembled,
+            notes: None,
+            tags: Vec::new(),
+            version: 1,
+            is_dirty: false,
+            last_modified: None,
+        }];
+        queue.apply_to(&mut models);
+        queue.apply_to(&mut models);
+
+        assert_eq!(models.len(), 2);
+        assert_eq!(models[0].status, Status::Painted);
+        assert!(models[0].is_dirty);
+        assert_eq!(models[0].last_modified, Some(2.0));
+        assert_eq!(models[1].id, "local-1");
+        assert!(models[1].is_dirty);
+    }
+}
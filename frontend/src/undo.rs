@@ -0,0 +1,381 @@
+use gloo_net::http::Request;
+use leptos::*;
+
+use crate::{update_user_model, Status, UpdateUserModelRequest, API_BASE};
+
+/// How many actions can be undone before the oldest ones are forgotten.
+const HISTORY_LIMIT: usize = 50;
+
+/// A single API call that moves a user model into a known state.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Change {
+    SetStatus {
+        id: String,
+        status: Status,
+    },
+    SetQuantity {
+        id: String,
+        quantity: i32,
+    },
+    /// Soft-deletes the lot, which is how a create is taken back.
+    Trash {
+        id: String,
+    },
+    /// Brings the lot back out of the trash.
+    Restore {
+        id: String,
+    },
+}
+
+/// A recorded action with the calls needed to reverse and replay it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+    pub label: String,
+    pub undo: Change,
+    pub redo: Change,
+}
+
+impl Entry {
+    pub fn status(id: String, model_name: &str, from: Status, to: Status) -> Self {
+        Self {
+            label: format!("{model_name}: {} → {}", from.label(), to.label()),
+            undo: Change::SetStatus {
+                id: id.clone(),
+                status: from,
+            },
+            redo: Change::SetStatus { id, status: to },
+        }
+    }
+
+    pub fn quantity(id: String, model_name: &str, from: i32, to: i32) -> Self {
+        Self {
+            label: format!("{model_name}: quantity {from} → {to}"),
+            undo: Change::SetQuantity {
+                id: id.clone(),
+                quantity: from,
+            },
+            redo: Change::SetQuantity { id, quantity: to },
+        }
+    }
+
+    pub fn created(id: String, model_name: &str) -> Self {
+        Self {
+            label: format!("Added {model_name}"),
+            undo: Change::Trash { id: id.clone() },
+            redo: Change::Restore { id },
+        }
+    }
+
+    pub fn deleted(id: String, model_name: &str) -> Self {
+        Self {
+            label: format!("Deleted {model_name}"),
+            undo: Change::Restore { id: id.clone() },
+            redo: Change::Trash { id },
+        }
+    }
+
+    pub fn change(&self, direction: Direction) -> &Change {
+        match direction {
+            Direction::Undo => &self.undo,
+            Direction::Redo => &self.redo,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Undo,
+    Redo,
+}
+
+/// Undo and redo stacks. Only one step runs at a time: [`History::start`] takes
+/// the entry off its stack and [`History::finish`] files it on the other stack
+/// once the API call has succeeded, or puts it back if the call failed.
+#[derive(Clone, Debug, Default)]
+pub struct History {
+    done: Vec<Entry>,
+    undone: Vec<Entry>,
+    in_flight: Option<InFlight>,
+}
+
+#[derive(Clone, Debug)]
+struct InFlight {
+    entry: Entry,
+    direction: Direction,
+    /// A new action was recorded while this step was running.
+    superseded: bool,
+}
+
+impl History {
+    /// Records a new action. Anything that was undone can no longer be redone.
+    pub fn push(&mut self, entry: Entry) {
+        self.done.push(entry);
+        if self.done.len() > HISTORY_LIMIT {
+            self.done.remove(0);
+        }
+        self.undone.clear();
+        if let Some(in_flight) = &mut self.in_flight {
+            in_flight.superseded = true;
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.in_flight.is_none() && !self.done.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.in_flight.is_none() && !self.undone.is_empty()
+    }
+
+    /// Takes the next entry to undo or redo, or `None` if there is nothing to
+    /// do or another step is still running.
+    pub fn start(&mut self, direction: Direction) -> Option<Entry> {
+        if self.in_flight.is_some() {
+            return None;
+        }
+
+        let entry = match direction {
+            Direction::Undo => self.done.pop(),
+            Direction::Redo => self.undone.pop(),
+        }?;
+        self.in_flight = Some(InFlight {
+            entry: entry.clone(),
+            direction,
+            superseded: false,
+        });
+        Some(entry)
+    }
+
+    /// Files the running step according to whether its API call succeeded.
+    /// A step overtaken by a newer action is dropped either way, since its
+    /// place in the history no longer makes sense.
+    pub fn finish(&mut self, succeeded: bool) {
+        let Some(in_flight) = self.in_flight.take() else {
+            return;
+        };
+        if in_flight.superseded {
+            return;
+        }
+
+        let target = match (in_flight.direction, succeeded) {
+            (Direction::Undo, true) | (Direction::Redo, false) => &mut self.undone,
+            (Direction::Undo, false) | (Direction::Redo, true) => &mut self.done,
+        };
+        target.push(in_flight.entry);
+    }
+}
+
+/// Issues the API call for a change.
+pub async fn apply(change: &Change) -> Result<(), String> {
+    match change {
+        Change::SetStatus { id, status } => update_user_model(
+            id.clone(),
+            UpdateUserModelRequest {
+                status: Some(*status),
+                ..UpdateUserModelRequest::default()
+            },
+        )
+        .await
+        .map(|_| ()),
+        Change::SetQuantity { id, quantity } => update_user_model(
+            id.clone(),
+            UpdateUserModelRequest {
+                quantity: Some(*quantity),
+                ..UpdateUserModelRequest::default()
+            },
+        )
+        .await
+        .map(|_| ()),
+        Change::Trash { id } => delete_user_model(id).await,
+        Change::Restore { id } => restore_user_model(id).await,
+    }
+}
+
+pub async fn delete_user_model(id: &str) -> Result<(), String> {
+    let response = Request::delete(&format!("{API_BASE}/user-models/{id}"))
+        .send()
+        .await
+        .map_err(|error| format!("Failed to delete model: {error}"))?;
+
+    if !response.ok() {
+        return Err(format!("Failed to delete model: {}", response.status()));
+    }
+
+    Ok(())
+}
+
+async fn restore_user_model(id: &str) -> Result<(), String> {
+    let response = Request::post(&format!("{API_BASE}/trash/{id}/restore"))
+        .send()
+        .await
+        .map_err(|error| format!("Failed to restore model: {error}"))?;
+
+    if !response.ok() {
+        return Err(format!("Failed to restore model: {}", response.status()));
+    }
+
+    Ok(())
+}
+
+/// Shows the latest action or undo result with buttons to step through the history.
+#[component]
+pub fn UndoToast(
+    history: RwSignal<History>,
+    message: RwSignal<Option<String>>,
+    on_step: Callback<Direction>,
+) -> impl IntoView {
+    move || {
+        message.get().map(|text| {
+            view! {
+                <div class="toast" role="status">
+                    <span class="toast__message">{text}</span>
+                    <button
+                        type="button"
+                        class="link-button"
+                        disabled=move || !history.with(History::can_undo)
+                        on:click=move |_| on_step.call(Direction::Undo)
+                    >
+                        "Undo"
+                    </button>
+                    <button
+                        type="button"
+                        class="link-button"
+                        disabled=move || !history.with(History::can_redo)
+                        on:click=move |_| on_step.call(Direction::Redo)
+                    >
+                        "Redo"
+                    </button>
+                    <button
+                        type="button"
+                        class="chip__remove"
+                        aria-label="Dismiss"
+                        on:click=move |_| message.set(None)
+                    >
+                        "×"
+                    </button>
+                </div>
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status_entry(id: &str) -> Entry {
+        Entry::status(
+            id.to_string(),
+            "Intercessors",
+            Status::Assembled,
+            Status::Painted,
+        )
+    }
+
+    #[test]
+    fn undo_then_redo_moves_entries_between_stacks() {
+        let mut history = History::default();
+        history.push(status_entry("a"));
+        assert!(history.can_undo());
+        assert!(!history.can_redo());
+
+        let entry = history.start(Direction::Undo).expect("entry to undo");
+        assert_eq!(
+            entry.change(Direction::Undo),
+            &Change::SetStatus {
+                id: "a".to_string(),
+                status: Status::Assembled,
+            }
+        );
+        assert!(!history.can_undo());
+        history.finish(true);
+        assert!(!history.can_undo());
+        assert!(history.can_redo());
+
+        let entry = history.start(Direction::Redo).expect("entry to redo");
+        assert_eq!(
+            entry.change(Direction::Redo),
+            &Change::SetStatus {
+                id: "a".to_string(),
+                status: Status::Painted,
+            }
+        );
+        history.finish(true);
+        assert!(history.can_undo());
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn failed_step_puts_the_entry_back() {
+        let mut history = History::default();
+        history.push(Entry::created("a".to_string(), "Intercessors"));
+
+        let entry = history.start(Direction::Undo).expect("entry to undo");
+        assert_eq!(
+            entry.undo,
+            Change::Trash {
+                id: "a".to_string()
+            }
+        );
+        assert_eq!(history.start(Direction::Undo), None);
+        history.finish(false);
+        assert!(history.can_undo());
+        assert!(!history.can_redo());
+
+        history.start(Direction::Undo);
+        history.finish(true);
+        let entry = history.start(Direction::Redo).expect("entry to redo");
+        assert_eq!(
+            entry.redo,
+            Change::Restore {
+                id: "a".to_string()
+            }
+        );
+        history.finish(false);
+        assert!(history.can_redo());
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn new_action_clears_redo_and_supersedes_running_step() {
+        let mut history = History::default();
+        history.push(Entry::deleted("a".to_string(), "Intercessors"));
+        history.push(Entry::quantity("b".to_string(), "Hellblasters", 5, 10));
+        history.start(Direction::Undo);
+        history.finish(true);
+        assert!(history.can_redo());
+
+        history.push(status_entry("c"));
+        assert!(!history.can_redo());
+
+        let entry = history.start(Direction::Undo).expect("entry to undo");
+        assert_eq!(entry.label, "Intercessors: Assembled → Painted");
+        history.push(status_entry("d"));
+        history.finish(true);
+        assert!(!history.can_redo());
+
+        let entry = history.start(Direction::Undo).expect("entry to undo");
+        assert_eq!(
+            entry.undo,
+            Change::SetStatus {
+                id: "d".to_string(),
+                status: Status::Assembled,
+            }
+        );
+    }
+
+    #[test]
+    fn history_forgets_the_oldest_entries() {
+        let mut history = History::default();
+        for index in 0..HISTORY_LIMIT + 5 {
+            history.push(status_entry(&index.to_string()));
+        }
+
+        let mut undone = 0;
+        while history.start(Direction::Undo).is_some() {
+            history.finish(true);
+            undone += 1;
+        }
+        assert_eq!(undone, HISTORY_LIMIT);
+    }
+}